@@ -104,7 +104,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         eprintln!("mmaptrace: child {pid} signaled with {sig:?}");
         break;
       }
-      WaitStatus::PtraceEvent(pid, sig, evt) => match evt {
+      WaitStatus::PtraceEvent(_pid, _sig, evt) => match evt {
         PTRACE_EVENT_EXEC => {
           ptrace_syscall(child, None)?;
         }
@@ -89,7 +89,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         eprintln!("rstrace: child {pid} signaled with {sig:?}");
         break;
       }
-      WaitStatus::PtraceEvent(pid, sig, evt) => match evt {
+      WaitStatus::PtraceEvent(_pid, _sig, evt) => match evt {
         PTRACE_EVENT_EXEC => {
           is_last_exec_successful = true;
           ptrace_syscall(child, None)?;
@@ -103,17 +103,13 @@ fn main() -> Result<(), Box<dyn Error>> {
           let mut modified_args = raw.inspect_sysexit(pid, &regs);
           // Fix the result of exec syscalls
           match &mut modified_args {
-            SyscallModifiedArgs::Execve(info) => {
-              if is_last_exec_successful {
-                info.syscall_result = 0;
-                is_last_exec_successful = false;
-              }
+            SyscallModifiedArgs::Execve(info) if is_last_exec_successful => {
+              info.syscall_result = 0;
+              is_last_exec_successful = false;
             }
-            SyscallModifiedArgs::Execveat(info) => {
-              if is_last_exec_successful {
-                info.syscall_result = 0;
-                is_last_exec_successful = false;
-              }
+            SyscallModifiedArgs::Execveat(info) if is_last_exec_successful => {
+              info.syscall_result = 0;
+              is_last_exec_successful = false;
             }
             _ => (),
           }
@@ -35,14 +35,37 @@ struct Decoder {
 }
 
 impl Decoder {
+  /// Whether this decoder decodes the arg from all the raw args instead of the raw arg with the same name.
+  ///
+  /// The type of such args is used as is, without wrapping.
+  fn is_raw_args_decoder(&self) -> bool {
+    matches!(self.func.to_string().as_str(), "from_raw_args" | "from_raw_args_and_result")
+  }
+
   fn decoded(&self, arg_name: &Ident, arg_type: proc_macro2::TokenStream, span: Span) -> proc_macro2::TokenStream {
     let mut counter = 0u32;
     let func: &Ident = &self.func;
+    match func.to_string().as_str() {
+      "from_raw_args" => {
+        return quote_spanned! {
+          span =>
+          let #arg_name = <#arg_type as InspectFromRawArgs<_>>::inspect_from(inspectee_pid, &raw_args);
+        }
+      }
+      "from_raw_args_and_result" => {
+        return quote_spanned! {
+          span =>
+          let #arg_name = <#arg_type as InspectResultFromRawArgs<_>>::inspect_from(inspectee_pid, &raw_args, syscall_result as isize);
+        }
+      }
+      _ => (),
+    }
     let (target_trait, is_result) = match func.to_string().as_str() {
       "sized_by" => (format_ident!("InspectDynSizedFromPid"), false),
       "counted_by" => (format_ident!("InspectCountedFromPid"), false),
       "sized_by_result" => (format_ident!("InspectDynSizedFromPid"), true),
       "counted_by_result" => (format_ident!("InspectCountedFromPid"), true),
+      "sized_string" => (format_ident!("InspectSizedStringFromPid"), false),
       _ => panic!("Unsupported decoder function: {:?}", func),
     };
     let args = &self.args;
@@ -266,6 +289,12 @@ fn gen_syscall_args_struct(
     let arg_name = &arg.ident;
     arg_names.push(arg_name.clone());
     let arg_type = &arg.ty;
+    if let Some(decoder) = arg.decoder.as_ref().filter(|d| d.is_raw_args_decoder()) {
+      let arg_type = arg_type.to_token_stream();
+      wrapped_arg_types.push(arg_type.clone());
+      inspects.push(decoder.decoded(arg_name, arg_type, span));
+      continue;
+    }
     let (wrapped_arg_type, need_memory_inspection) = wrap_syscall_arg_type(arg_type, span);
     wrapped_arg_types.push(wrapped_arg_type.clone());
     if !need_memory_inspection {
@@ -321,6 +350,12 @@ fn gen_syscall_args_struct(
         #arg_name: Err(InspectError::SyscallFailure)
       });
       let arg_type = &modified_arg.ty;
+      if let Some(decoder) = modified_arg.decoder.as_ref().filter(|d| d.is_raw_args_decoder()) {
+        let arg_type = arg_type.to_token_stream();
+        modified_arg_types.push(arg_type.clone());
+        inspect_modified_args.push(decoder.decoded(arg_name, arg_type, span));
+        continue;
+      }
       let (wrapped_arg_type, need_memory_inspection) = wrap_syscall_arg_type(arg_type, span);
       modified_arg_types.push(wrapped_arg_type.clone());
       if !need_memory_inspection {
@@ -501,6 +536,7 @@ pub fn gen_syscalls(input: TokenStream) -> TokenStream {
   let mut syscall_names_dedup = vec![];
   let mut supported_archs_dedup = vec![];
   let mut syscall_consts = vec![];
+  let mut syscall_name_strs = vec![];
   for syscall in &input {
     let GenSyscallArgsStructResult {
       args_struct,
@@ -524,6 +560,7 @@ pub fn gen_syscalls(input: TokenStream) -> TokenStream {
     supported_archs.push(archs.clone());
     syscall_numbers.push(syscall_number.clone());
     syscall_consts.push(syscall_const_name);
+    syscall_name_strs.push(syscall.name.to_string());
     if syscall_names_dedup.last() != Some(&syscall.name) {
       syscall_names_dedup.push(syscall.name.clone());
       supported_archs_dedup.push(archs.clone());
@@ -634,6 +671,17 @@ pub fn gen_syscalls(input: TokenStream) -> TokenStream {
       }
     }
 
+    /// Get the name of a syscall from its number on the current architecture.
+    pub fn syscall_name(syscall_number: isize) -> Option<&'static str> {
+      match syscall_number {
+        #(
+          #[cfg(any(#(target_arch = #supported_archs),*))]
+          #syscall_numbers => Some(#syscall_name_strs),
+        )*
+        _ => None,
+      }
+    }
+
     impl SyscallStopInspect for SyscallRawArgs {
       type Args = SyscallArgs;
       type Result = SyscallModifiedArgs;
@@ -741,6 +789,7 @@ fn wrap_syscall_arg_type(ty: &Type, span: Span) -> (proc_macro2::TokenStream, bo
         // Types that need to be wrapped
         "sockaddr"
         | "CString"
+        | "OsString"
         | "PathBuf"
         | "iovec"
         | "timex"
@@ -753,6 +802,7 @@ fn wrap_syscall_arg_type(ty: &Type, span: Span) -> (proc_macro2::TokenStream, bo
         | "stat"
         | "statfs"
         | "futex_waitv"
+        | "FutexRequeueWaiters"
         | "user_desc"
         | "itimerval"
         | "rlimit"
@@ -785,7 +835,10 @@ fn wrap_syscall_arg_type(ty: &Type, span: Span) -> (proc_macro2::TokenStream, bo
         | "ustat"
         | "shmid_ds"
         | "cachestat"
-        | "cachestat_range" => (quote_spanned!(span => InspectResult<#ty>), true),
+        | "cachestat_range"
+        | "CpuSet"
+        | "NodeMask"
+        | "SignalInfo" => (quote_spanned!(span => InspectResult<#ty>), true),
         _ => {
           if ty.ident == "Option" {
             let PathArguments::AngleBracketed(arg) = &ty.arguments else {
@@ -796,7 +849,8 @@ fn wrap_syscall_arg_type(ty: &Type, span: Span) -> (proc_macro2::TokenStream, bo
               "PathBuf" | "timespec" | "Vec < CString >" | "CString" | "Vec < c_ulong >" | "Vec < c_uint >"
               | "Vec < gid_t >" | "timezone" | "mq_attr" | "siginfo_t" | "sigset_t" | "iovec" | "rlimit64"
               | "fd_set" | "sockaddr" | "sigaction" | "timeval" | "itimerval" | "stack_t" | "timer_t" | "time_t"
-              | "sigevent" | "itimerspec" | "utimbuf" | "rusage" => (quote_spanned!(span => InspectResult<#ty>), true),
+              | "sigevent" | "itimerspec" | "utimbuf" | "rusage" | "FdSet" | "NodeMask" | "SignalInfo" | "OsString"
+              | "c_uint" => (quote_spanned!(span => InspectResult<#ty>), true),
               "[timespec; 2]" | "[timeval; 2]" | "[timespec ; 2]" | "[timeval ; 2]" => {
                 let GenericArgument::Type(inner) = arg.args.first().unwrap() else {
                   panic!("Unsupported inner syscall arg type: {:?}", argstr);
@@ -824,13 +878,15 @@ fn wrap_syscall_arg_type(ty: &Type, span: Span) -> (proc_macro2::TokenStream, bo
             }
           } else if ty.ident == "InspectResult" {
             (quote_spanned!(span => #ty), true)
+          } else if ty.ident == "Extensible" {
+            (quote_spanned!(span => InspectResult<#ty>), true)
           } else if ty.ident == "Arc" {
             let PathArguments::AngleBracketed(arg) = &ty.arguments else {
               panic!("Unsupported inner syscall arg type: {:?}", ty_str);
             };
             let arg = arg.args.to_token_stream().to_string();
             match arg.as_str() {
-              "rseq" | "statmount" | "msgbuf" | "file_handle" | "xattr_args" | "mount_attr" => {
+              "rseq" | "statmount" | "msgbuf" | "file_handle" | "xattr_args" => {
                 (quote_spanned!(span => InspectResult<#ty>), true)
               }
               _ => panic!("Unsupported inner syscall arg type: {:?}", arg),
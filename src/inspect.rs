@@ -10,10 +10,10 @@ use std::{
 use nix::{
   errno::Errno,
   libc::{
-    c_long, c_ulong, clone_args, epoll_event, fd_set, iocb, iovec, itimerspec, itimerval, memcpy, mmsghdr, mq_attr,
-    msghdr, msqid_ds, open_how, pollfd, rlimit, rlimit64, rusage, sched_attr, sched_param, sembuf, shmid_ds, sigaction,
-    sigevent, siginfo_t, sigset_t, sockaddr, stack_t, stat, statfs, statx, sysinfo, timespec, timeval, timex, tms,
-    utimbuf, utsname,
+    c_long, c_ulong, clone_args, epoll_event, fd_set, flock, iocb, iovec, itimerspec, itimerval, memcpy, mmsghdr,
+    mq_attr, msghdr, msginfo, msqid_ds, open_how, pollfd, rlimit, rlimit64, rusage, sched_attr, sched_param, sembuf,
+    shmid_ds, sigaction, sigevent, siginfo_t, sigset_t, sock_filter, sock_fprog, sockaddr, stack_t, stat, statfs,
+    statx, sysinfo, timespec, timeval, timex, tms, utimbuf, utsname,
   },
   sys::ptrace::{self, AddressType},
   unistd::{sysconf, Pid, SysconfVar},
@@ -23,9 +23,10 @@ use once_cell::sync::OnceCell;
 use crate::{
   arch::PtraceRegisters,
   types::{
-    __aio_sigset, __mount_arg, cachestat, cachestat_range, cap_user_data, cap_user_header, futex_waitv, io_event,
-    io_uring_params, kexec_segment, landlock_ruleset_attr, linux_dirent, linux_dirent64, mnt_id_req, mount_attr,
-    timezone, ustat,
+    __aio_sigset, __mount_arg, cachestat, cachestat_range, cap_user_data, cap_user_header, f_owner_ex, futex_waitv,
+    io_event, io_uring_params, kexec_segment, landlock_net_port_attr, landlock_path_beneath_attr,
+    landlock_ruleset_attr, linux_dirent, linux_dirent64, mnt_id_req, mount_attr, prctl_mm_map, semid_ds, seminfo,
+    shm_info, shminfo, timezone, ustat, FutexRequeueWaiters,
   },
 };
 
@@ -89,9 +90,8 @@ pub unsafe fn read_remote_memory(
   } else if SHOULD_USE_PROCESS_VM_READV.load(Ordering::Relaxed) {
     let result = read_by_process_vm_readv(pid, remote_addr, len, dest);
     result
-      .map_err(|e| {
+      .inspect_err(|_| {
         SHOULD_USE_PROCESS_VM_READV.store(false, Ordering::SeqCst);
-        e
       })
       .or_else(|_| read_by_ptrace_peek(pid, remote_addr, len, dest))
   } else {
@@ -251,7 +251,8 @@ impl_marker! {
   statfs, futex_waitv, itimerval, iocb, __aio_sigset, io_uring_params, io_event, kexec_segment,
   rlimit, rusage, timezone, linux_dirent, linux_dirent64, landlock_ruleset_attr, __mount_arg,
   timeval, mount_attr, mq_attr, iovec, rlimit64, siginfo_t, pollfd, fd_set, open_how, msqid_ds,
-  sigevent, mmsghdr, msghdr, sigset_t
+  sigevent, mmsghdr, msghdr, sigset_t, sock_filter, sock_fprog, prctl_mm_map, FutexRequeueWaiters, flock, f_owner_ex,
+  landlock_path_beneath_attr, landlock_net_port_attr, semid_ds, seminfo, shminfo, shm_info, msginfo
 }
 
 /// Use ptrace to inspect the process with the given pid and return the inspection result.
@@ -269,6 +270,26 @@ pub(crate) trait InspectDynSizedFromPid {
   fn inspect_from(pid: Pid, address: AddressType, size: usize) -> Self;
 }
 
+/// Use ptrace to inspect the process with the given pid and return the inspection result.
+///
+/// This is used for strings whose length is passed in another argument instead of being NUL-terminated.
+pub(crate) trait InspectSizedStringFromPid {
+  fn inspect_from(pid: Pid, address: AddressType, len: usize) -> Self;
+}
+
+/// Use ptrace to inspect the process with the given pid and decode the value from all the raw syscall arguments.
+///
+/// This is used for syscall arguments whose meaning depends on other arguments.
+pub(crate) trait InspectFromRawArgs<RawArgs> {
+  fn inspect_from(pid: Pid, raw_args: &RawArgs) -> Self;
+}
+
+/// Use ptrace to inspect the process with the given pid and decode the value from all the raw syscall arguments
+/// and the syscall result on sysexit.
+pub(crate) trait InspectResultFromRawArgs<RawArgs> {
+  fn inspect_from(pid: Pid, raw_args: &RawArgs, syscall_result: isize) -> Self;
+}
+
 const WORD_SIZE: usize = size_of::<c_long>();
 
 impl<T: Clone + PartialEq + ReprCMarker> InspectFromPid for InspectResult<T> {
@@ -296,10 +317,20 @@ fn read_generic_string<TString: Clone + PartialEq>(
   pid: Pid,
   address: AddressType,
   ctor: impl Fn(Vec<u8>) -> TString,
+) -> InspectResult<TString> {
+  read_generic_string_bounded(pid, address, usize::MAX, ctor)
+}
+
+/// Read a NUL-terminated string word by word, stopping after `limit` bytes if there is no NUL.
+fn read_generic_string_bounded<TString: Clone + PartialEq>(
+  pid: Pid,
+  address: AddressType,
+  limit: usize,
+  ctor: impl Fn(Vec<u8>) -> TString,
 ) -> InspectResult<TString> {
   let mut buf = Vec::new();
   let mut address = address;
-  loop {
+  while buf.len() < limit {
     let word = match ptrace::read(pid, address) {
       Err(e) => {
         return Err(InspectError::ReadFailure {
@@ -310,7 +341,7 @@ fn read_generic_string<TString: Clone + PartialEq>(
       Ok(word) => word,
     };
     let word_bytes = word.to_ne_bytes();
-    for &byte in word_bytes.iter() {
+    for &byte in word_bytes.iter().take(limit - buf.len()) {
       if byte == 0 {
         return Ok(ctor(buf));
       }
@@ -318,6 +349,7 @@ fn read_generic_string<TString: Clone + PartialEq>(
     }
     address = unsafe { address.add(WORD_SIZE) };
   }
+  Ok(ctor(buf))
 }
 
 #[allow(unused)]
@@ -329,11 +361,119 @@ fn read_pathbuf(pid: Pid, address: AddressType) -> InspectResult<PathBuf> {
   read_generic_string(pid, address, |x| PathBuf::from(OsString::from_vec(x)))
 }
 
+/// Read a string of exactly `len` bytes, capped at [`READ_SIZE_MAX`]. NUL bytes are kept as is.
+fn read_sized_string<TString: Clone + PartialEq>(
+  pid: Pid,
+  address: AddressType,
+  len: usize,
+  ctor: impl Fn(Vec<u8>) -> TString,
+) -> InspectResult<TString> {
+  read_bytes(pid, address, len.min(READ_SIZE_MAX))
+    .map(&ctor)
+    .map_err(|e| e.map_ptrace_failure(&ctor))
+}
+
+impl InspectSizedStringFromPid for InspectResult<OsString> {
+  fn inspect_from(pid: Pid, address: AddressType, len: usize) -> Self {
+    read_sized_string(pid, address, len, OsString::from_vec)
+  }
+}
+
+impl InspectSizedStringFromPid for InspectResult<PathBuf> {
+  fn inspect_from(pid: Pid, address: AddressType, len: usize) -> Self {
+    read_sized_string(pid, address, len, |x| PathBuf::from(OsString::from_vec(x)))
+  }
+}
+
+/// A buffer of `size` bytes read at once, capped at [`READ_SIZE_MAX`].
+impl InspectDynSizedFromPid for InspectResult<Vec<u8>> {
+  fn inspect_from(pid: Pid, address: AddressType, size: usize) -> Self {
+    read_bytes(pid, address, size.min(READ_SIZE_MAX))
+  }
+}
+
+#[allow(unused)]
 fn read_lossy_string(pid: Pid, address: AddressType) -> InspectResult<String> {
   // Waiting on https://github.com/rust-lang/libs-team/issues/116
   read_generic_string(pid, address, |x| String::from_utf8_lossy(&x).into_owned())
 }
 
+/// The maximum number of bytes read for a buffer whose size is passed by the tracee, larger buffers are truncated.
+pub(crate) const READ_SIZE_MAX: usize = 1 << 20;
+/// UIO_MAXIOV, the kernel rejects longer iovec arrays.
+pub(crate) const UIO_MAXIOV: usize = 1024;
+
+/// Read exactly `len` bytes from the tracee memory.
+pub(crate) fn read_bytes(pid: Pid, address: AddressType, len: usize) -> InspectResult<Vec<u8>> {
+  let mut buf = vec![0u8; len];
+  unsafe { read_remote_memory(pid, address, len, buf.as_mut_ptr() as AddressType) }.map_err(|errno| {
+    InspectError::ReadFailure {
+      errno,
+      incomplete: None,
+    }
+  })?;
+  Ok(buf)
+}
+
+/// Reads the fields of a C struct from its bytes at their offsets.
+///
+/// The bytes after the end of the buffer are treated as zero, which is how the kernel handles
+/// extensible structs that are smaller than what it knows.
+pub(crate) struct StructFields<'a>(pub &'a [u8]);
+
+impl StructFields<'_> {
+  pub fn bytes<const N: usize>(&self, offset: usize) -> [u8; N] {
+    let mut buf = [0u8; N];
+    if let Some(avail) = self.0.get(offset..) {
+      let len = avail.len().min(N);
+      buf[..len].copy_from_slice(&avail[..len]);
+    }
+    buf
+  }
+
+  pub fn u16(&self, offset: usize) -> u16 {
+    u16::from_ne_bytes(self.bytes(offset))
+  }
+
+  pub fn u32(&self, offset: usize) -> u32 {
+    u32::from_ne_bytes(self.bytes(offset))
+  }
+
+  pub fn i32(&self, offset: usize) -> i32 {
+    i32::from_ne_bytes(self.bytes(offset))
+  }
+
+  pub fn u64(&self, offset: usize) -> u64 {
+    u64::from_ne_bytes(self.bytes(offset))
+  }
+
+  pub fn ptr(&self, offset: usize) -> AddressType {
+    self.u64(offset) as usize as AddressType
+  }
+
+  /// A string stored in a `char[N]` member, which is not necessarily null terminated.
+  pub fn cstring<const N: usize>(&self, offset: usize) -> CString {
+    let name: [u8; N] = self.bytes(offset);
+    let len = name.iter().position(|&b| b == 0).unwrap_or(N);
+    CString::new(&name[..len]).unwrap()
+  }
+}
+
+/// Read a NUL-terminated string from a buffer of at most `size` bytes.
+///
+/// The string is truncated to `size` bytes if there is no NUL in the buffer, which matches how the kernel
+/// treats fixed size string buffers like the task comm name. It is read word by word so that a short string
+/// near the end of a mapping can be read.
+pub(crate) fn read_bounded_cstring(pid: Pid, address: AddressType, size: usize) -> InspectResult<CString> {
+  read_generic_string_bounded(pid, address, size, |x| CString::new(x).unwrap())
+}
+
+/// Read a classic BPF program from a `struct sock_fprog` at the given address.
+pub(crate) fn read_sock_fprog(pid: Pid, address: AddressType) -> InspectResult<Vec<sock_filter>> {
+  let fprog = InspectResult::<sock_fprog>::inspect_from(pid, address).map_err(|e| e.map_ptrace_failure(|_| vec![]))?;
+  InspectCountedFromPid::inspect_from(pid, fprog.filter as AddressType, fprog.len as usize)
+}
+
 fn read_null_ended_array<TItem: Clone + PartialEq>(pid: Pid, mut address: AddressType) -> InspectResult<Vec<TItem>>
 where
   InspectResult<TItem>: InspectFromPid,
@@ -397,7 +537,8 @@ where
   InspectResult<T>: InspectFromPid,
 {
   fn inspect_from(pid: Pid, address: AddressType, count: usize) -> Self {
-    let mut res = Vec::with_capacity(count);
+    // `count` is passed by the tracee, don't let it size the allocation.
+    let mut res = Vec::with_capacity(count.min(READ_SIZE_MAX / size_of::<T>().max(1)));
     for i in 0..count {
       let item_address = unsafe { address.byte_add(i * size_of::<T>()) };
       let item = match InspectResult::<T>::inspect_from(pid, item_address) {
@@ -499,3 +640,22 @@ where
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_sized_buffer_is_read_at_once_and_capped() {
+    let buffer: Vec<u8> = (0..READ_SIZE_MAX + 64).map(|i| i as u8).collect();
+    let address = buffer.as_ptr() as AddressType;
+    assert_eq!(
+      <InspectResult<Vec<u8>> as InspectDynSizedFromPid>::inspect_from(Pid::this(), address, 32),
+      Ok(buffer[..32].to_vec())
+    );
+    assert_eq!(
+      <InspectResult<Vec<u8>> as InspectDynSizedFromPid>::inspect_from(Pid::this(), address, usize::MAX),
+      Ok(buffer[..READ_SIZE_MAX].to_vec())
+    );
+  }
+}
@@ -3,15 +3,15 @@
 
 use std::path::PathBuf;
 use std::{
-  ffi::{c_int, CString},
+  ffi::{c_int, CString, OsString},
   os::fd::RawFd,
 };
 
 use crate::{
   arch::{syscall_arg, syscall_no_from_regs, syscall_res_from_regs, PtraceRegisters},
   types::*,
-  InspectCountedFromPid, InspectDynSizedFromPid, InspectError, InspectFromPid, InspectResult, SyscallNumber,
-  SyscallStopInspect,
+  InspectCountedFromPid, InspectDynSizedFromPid, InspectError, InspectFromPid, InspectFromRawArgs, InspectResult,
+  InspectResultFromRawArgs, InspectSizedStringFromPid, SyscallNumber, SyscallStopInspect,
 };
 use crate::{ptrace_getregs, SyscallGroups, SyscallGroupsGetter};
 use enumflags2::BitFlags;
@@ -76,7 +76,7 @@ gen_syscalls! {
   access(pathname: *const c_char, mode: c_int) / { pathname: PathBuf, mode: c_int } -> c_int ~ [File] for [x86_64: 21],
   acct(filename: *const c_char) / { filename: Option<PathBuf> } -> c_int ~ [File] for [x86_64: 163, aarch64: 89, riscv64: 89],
   add_key(r#type: *const c_char, description: *const c_char, payload: *const c_void, plen: size_t, keyring: key_serial_t ) /
-    { r#type: CString, description: CString, payload: Vec<u8> @ counted_by(raw_args.plen), keyring: key_serial_t }
+    { r#type: CString, description: CString, payload: Vec<u8> @ sized_by(raw_args.plen), keyring: KeySerial @ from_raw_args() }
     -> key_serial_t ~ [] for [x86_64: 248, aarch64: 217, riscv64: 217],
   adjtimex(buf: *mut timex) / { buf: timex } -> c_int ~ [Clock] for [x86_64: 159, aarch64: 171, riscv64: 171],
   alarm(seconds: c_uint) / { seconds: c_uint } -> c_uint ~ [] for [x86_64: 37],
@@ -85,19 +85,19 @@ gen_syscalls! {
   // arm_fadvise64_64, atomic_barrier, atomic_barrier
   bind(socketfd: RawFd, addr: *const sockaddr, addrlen: socklen_t) /
     { socketfd: RawFd, addr: sockaddr, addrlen: socklen_t } -> c_int ~ [Network] for [x86_64: 49, aarch64: 200, riscv64: 200],
-  // TODO: https://elixir.bootlin.com/linux/latest/source/include/uapi/linux/bpf.h#L1454 and https://www.man7.org/linux/man-pages/man2/bpf.2.html
   bpf(cmd: c_int, attr: *mut c_void, size: c_uint) /
-    { cmd: c_int, attr: Vec<u8> @ counted_by(raw_args.size) } -> c_int ~ [Desc] for [x86_64: 321, aarch64: 280, riscv64: 280],
+    { cmd: BpfCmd @ from_raw_args(), attr: InspectResult<Extensible<BpfAttr>> @ from_raw_args() } -> c_int +
+    { result: InspectResult<BpfResult> @ from_raw_args_and_result() } ~ [Desc] for [x86_64: 321, aarch64: 280, riscv64: 280],
   brk(addr: *mut c_void) / { addr: AddressType } -> c_int ~ [Memory] for [x86_64: 12, aarch64: 214, riscv64: 214],
   // cachectl, cacheflush
   // cachestat: TODO: https://github.com/golang/go/issues/61917
   cachestat(fd: RawFd, cstat_range: *mut cachestat_range, cstat: *mut cachestat, flags: c_uint) /
     { fd: RawFd, cstat_range: cachestat_range, cstat: cachestat, flags: c_uint } -> c_int
     ~ [Desc] for [x86_64: 451, aarch64: 451, riscv64: 451],
-  capget(hdrp: *mut cap_user_header, datap: *mut cap_user_data) / { } -> c_int +
-    { hdrp: cap_user_header, datap: cap_user_data } ~ [Creds] for [x86_64: 125, aarch64: 90, riscv64: 90],
+  capget(hdrp: *mut cap_user_header, datap: *mut cap_user_data) / { hdrp: cap_user_header } -> c_int +
+    { hdrp: cap_user_header, datap: InspectResult<Option<CapUserData>> @ from_raw_args_and_result() } ~ [Creds] for [x86_64: 125, aarch64: 90, riscv64: 90],
   capset(hdrp: *mut cap_user_header, datap: *const cap_user_data) /
-    { hdrp: cap_user_header, datap: cap_user_data } -> c_int ~ [Creds] for [x86_64: 126, aarch64: 91, riscv64: 91],
+    { hdrp: cap_user_header, datap: InspectResult<Option<CapUserData>> @ from_raw_args() } -> c_int ~ [Creds] for [x86_64: 126, aarch64: 91, riscv64: 91],
   chdir(path: *const c_char) / { path: PathBuf } -> c_int ~ [File] for [x86_64: 80, aarch64: 49, riscv64: 49],
   chmod(pathname: *const c_char, mode: mode_t) / { pathname: PathBuf, mode: mode_t } -> c_int ~ [File] for [x86_64: 90],
   chown(pathname: *const c_char, owner: uid_t, group: gid_t)
@@ -121,12 +121,13 @@ gen_syscalls! {
   // clock_settime64
   // clone, the arguments vary for different architectures.
   clone(flags: c_ulong, stack: AddressType, parent_tid: *mut pid_t, child_tid: *mut pid_t, tls: c_ulong) /
-   { flags: c_ulong, stack: AddressType, tls: c_ulong } -> c_long +
+   { request: CloneRequest @ from_raw_args() } -> c_long +
    { parent_tid: InspectResult<pid_t>, child_tid: InspectResult<pid_t> } ~ [Process] for [x86_64: 56],
   clone(flags: c_ulong, stack: AddressType, parent_tid: *mut pid_t, tls: c_ulong, child_tid: *mut pid_t) /
-   { flags: c_ulong, stack: AddressType, tls: c_ulong } -> c_long +
+   { request: CloneRequest @ from_raw_args() } -> c_long +
    { parent_tid: InspectResult<pid_t>, child_tid: InspectResult<pid_t> } ~ [Process] for [aarch64: 220, riscv64: 220],
-  clone3(cl_args: *mut clone_args, size: size_t) / { cl_args: clone_args, size: size_t } -> c_int ~ [Process] for [x86_64: 435, aarch64: 435, riscv64: 435],
+  clone3(cl_args: *mut clone_args, size: size_t) /
+    { request: InspectResult<Extensible<CloneRequest>> @ from_raw_args(), size: size_t } -> c_int ~ [Process] for [x86_64: 435, aarch64: 435, riscv64: 435],
   close(fd: RawFd) / { fd: RawFd } -> c_int ~ [Desc] for [x86_64: 3, aarch64: 57, riscv64: 57],
   close_range(first: c_uint, last: c_uint, flags: c_uint) / { first: c_uint, last: c_uint, flags: c_uint }
     -> c_int ~ [] for [x86_64: 436, aarch64: 436, riscv64: 436],
@@ -196,11 +197,13 @@ gen_syscalls! {
   // fchown32
   fchownat(dirfd: RawFd, pathname: *const c_char, owner: uid_t, group: gid_t, flags: c_int) /
     { dirfd: RawFd, pathname: PathBuf, owner: uid_t, group: gid_t, flags: c_int } -> c_int ~ [Desc, File] for [x86_64: 260, aarch64: 54, riscv64: 54],
-  fcntl(fd: RawFd, cmd: c_int, arg: usize) / { fd: RawFd, cmd: c_int, arg: usize } -> c_int ~ [Desc] for [x86_64: 72, aarch64: 25, riscv64: 25],
+  fcntl(fd: RawFd, cmd: c_int, arg: usize) / { fd: RawFd, cmd: FcntlCmd @ from_raw_args() } -> c_int +
+    { result: InspectResult<FcntlResult> @ from_raw_args_and_result() } ~ [Desc] for [x86_64: 72, aarch64: 25, riscv64: 25],
   // fcntl64
   fdatasync(fd: RawFd) / { fd: RawFd } -> c_int ~ [Desc] for [x86_64: 75, aarch64: 83, riscv64: 83],
   fgetxattr(fd: RawFd, name: *const c_char, value: *mut c_void, size: size_t) /
-    { fd: RawFd, name: CString, value: CString, size: size_t } -> ssize_t ~ [Desc] for [x86_64: 193, aarch64: 10, riscv64: 10],
+    { fd: RawFd, name: CString, size: size_t } -> ssize_t
+    + { value: InspectResult<Option<XattrValue>> @ from_raw_args_and_result() } ~ [Desc] for [x86_64: 193, aarch64: 10, riscv64: 10],
   finit_module(fd: RawFd, param_values: *const c_char, flags: c_int) /
     { fd: RawFd, param_values: CString, flags: c_int } -> c_int ~ [Desc] for [x86_64: 313, aarch64: 273, riscv64: 273],
   flistxattr(fd: RawFd, list: *mut c_char, size: size_t) /
@@ -210,14 +213,16 @@ gen_syscalls! {
   fremovexattr(fd: RawFd, name: *const c_char) / { fd: RawFd, name: CString } -> c_int ~ [Desc] for [x86_64: 199, aarch64: 16, riscv64: 16],
   // fsconfig: https://go-review.googlesource.com/c/sys/+/484995 and https://lwn.net/Articles/766155/
   fsconfig(fd: RawFd, cmd: c_uint, key: *const c_char, value: *const c_char, aux: c_int) /
-    { fd: RawFd, cmd: c_uint, key: CString, value: CString, aux: c_int } -> c_int ~ [Desc, File] for [x86_64: 431, aarch64: 431, riscv64: 431],
+    { fd: RawFd, command: FsconfigCommand @ from_raw_args() } -> c_int ~ [Desc, File] for [x86_64: 431, aarch64: 431, riscv64: 431],
   fsetxattr(fd: RawFd, name: *const c_char, value: *const c_void, size: size_t, flags: c_int) /
-    { fd: RawFd, name: CString, value: CString, size: size_t, flags: c_int } -> c_int ~ [Desc] for [x86_64: 190, aarch64: 7, riscv64: 7],
+    { fd: RawFd, name: CString, value: InspectResult<XattrValue> @ from_raw_args(), size: size_t, flags: c_int } -> c_int ~ [Desc] for [x86_64: 190, aarch64: 7, riscv64: 7],
   // https://lwn.net/Articles/759499/
   fsmount(fd: RawFd, flags: c_uint, ms_flags: c_uint) /
-    { fd: RawFd, flags: c_uint, ms_flags: c_uint } -> c_int ~ [Desc] for [x86_64: 432, aarch64: 432, riscv64: 432],
-  fsopen(fsname: *const c_char, flags: c_uint) / { fsname: CString, flags: c_uint } -> c_int ~ [Desc] for [x86_64: 430, aarch64: 430, riscv64: 430],
-  fspick(dirfd: RawFd, pathname: *const c_char, flags: c_uint) / { dirfd: RawFd, pathname: CString, flags: c_uint } -> c_int
+    { fd: RawFd, flags: BitFlags<FsmountFlags> @ from_raw_args(), ms_flags: BitFlags<MountAttrFlags> @ from_raw_args() } -> c_int
+    ~ [Desc] for [x86_64: 432, aarch64: 432, riscv64: 432],
+  fsopen(fsname: *const c_char, flags: c_uint) / { fsname: CString, flags: BitFlags<FsopenFlags> @ from_raw_args() } -> c_int
+    ~ [Desc] for [x86_64: 430, aarch64: 430, riscv64: 430],
+  fspick(dirfd: RawFd, pathname: *const c_char, flags: c_uint) / { dirfd: RawFd, pathname: CString, flags: BitFlags<FspickFlags> @ from_raw_args() } -> c_int
     ~ [Desc, File] for [x86_64: 433, aarch64: 433, riscv64: 433],
   fstat(fd: RawFd, statbuf: *mut stat) / { fd: RawFd } -> c_int + { statbuf: stat } ~ [Desc, FStat, StatLike] for [x86_64: 5, aarch64: 80, riscv64: 80],
   // fstat64, fstatat64
@@ -226,15 +231,16 @@ gen_syscalls! {
   fsync(fd: RawFd) / { fd: RawFd } -> c_int ~ [Desc] for [x86_64: 74, aarch64: 82, riscv64: 82],
   ftruncate(fd: RawFd, length: off_t) / { fd: RawFd, length: off_t } -> c_int ~ [Desc] for [x86_64: 77, aarch64: 46, riscv64: 46],
   // ftruncate64
-  // futex: val2 can be a pointer to timespec or a u32 value. val2, uaddr2 and val3 is optional for some ops. TODO: design a better rust interface
+  // futex: val2 can be a pointer to timespec or a u32 value. val2, uaddr2 and val3 is optional for some ops.
   futex(uaddr: *mut u32, futex_op: c_int, val: u32, val2: usize, uaddr2: *mut u32, val3: u32) /
-    { uaddr: InspectResult<u32>, futex_op: c_int, val: u32, val2: usize, uaddr2: InspectResult<u32>, val3: u32 }
-    -> c_long + { uaddr: InspectResult<u32>, uaddr2: InspectResult<u32> } ~ [] for [x86_64: 202, aarch64: 98, riscv64: 98],
+    { uaddr: InspectResult<u32>, op: FutexOp @ from_raw_args(), flags: BitFlags<FutexFlags> @ from_raw_args() }
+    -> c_long + { uaddr: InspectResult<u32>, uaddr2: InspectResult<Option<u32>> @ from_raw_args_and_result() }
+    ~ [] for [x86_64: 202, aarch64: 98, riscv64: 98],
   // https://elixir.bootlin.com/linux/v6.9.3/source/include/linux/syscalls.h#L568
-  // futex_requeue: waiters is always a two-element array of futex_waitv. TODO: design a better rust interface
+  // futex_requeue: waiters is always a two-element array of futex_waitv.
   futex_requeue(waiters: *mut futex_waitv, flags: c_uint, nr_wake: c_int, nr_requeue: c_int) /
-    { waiters: [futex_waitv; 2], flags: c_uint, nr_wake: c_int, nr_requeue: c_int }
-    -> c_long + { waiters: [futex_waitv; 2] } ~ [] for [x86_64: 456, aarch64: 456, riscv64: 456],
+    { waiters: FutexRequeueWaiters, flags: c_uint, nr_wake: c_int, nr_requeue: c_int }
+    -> c_long + { waiters: FutexRequeueWaiters } ~ [] for [x86_64: 456, aarch64: 456, riscv64: 456],
   // futex_time64
   futex_wait(uaddr: *mut u32, val: c_ulong, mask: c_ulong, flags: c_uint, timespec: *mut timespec, clockid: clockid_t) /
     { uaddr: InspectResult<u32>, val: c_ulong, mask: c_ulong, flags: c_uint, timespec: timespec, clockid: clockid_t }
@@ -247,11 +253,10 @@ gen_syscalls! {
     -> c_long + { uaddr: InspectResult<u32> } ~ [] for [x86_64: 454, aarch64: 454, riscv64: 454],
   futimesat(dirfd: RawFd, pathname: *const c_char, times: *const timeval) /
     { dirfd: RawFd, pathname: PathBuf, times: [timeval;2] } -> c_int ~ [Desc, File] for [x86_64: 261],
-  // get_mempolicy: nodemask: [c_ulong; (maxnode + ULONG_WIDTH - 1) / ULONG_WIDTH]
   get_mempolicy(mode: *mut c_int, nodemask: *mut c_ulong, maxnode: c_ulong, addr: AddressType, flags: c_ulong) /
-    { maxnode: c_ulong, addr: AddressType, flags: c_ulong }
-    -> c_long + { mode: InspectResult<Option<c_int>>,
-      nodemask: Option<Vec<c_ulong>> @ counted_by((raw_args.maxnode as usize + (8 * std::mem::size_of::<c_ulong>() - 1)) / (8 * std::mem::size_of::<c_ulong>())) }
+    { maxnode: c_ulong, addr: AddressType, flags: BitFlags<GetMempolicyFlags> @ from_raw_args() }
+    -> c_long + { mode: InspectResult<Option<GetMempolicyMode>> @ from_raw_args_and_result(),
+      nodemask: Option<NodeMask> @ counted_by(raw_args.maxnode.saturating_sub(1)) }
     ~ [Memory] for [x86_64: 239, aarch64: 236, riscv64: 236],
   get_robust_list(pid: pid_t, head_ptr: *mut *mut robust_list_head, len_ptr: *mut size_t) /
     { pid: pid_t, head_ptr: InspectResult<AddressType>, len_ptr: size_t } -> c_long ~ [] for [x86_64: 274, aarch64: 100, riscv64: 100],
@@ -261,10 +266,10 @@ gen_syscalls! {
   getcwd(buf: *mut c_char, size: size_t) / { size: size_t } -> c_long + { buf: CString } ~ [File] for [x86_64: 79, aarch64: 17, riscv64: 17],
   // getdents: count is size of buffer! This syscall returns the number of bytes read.
   getdents(fd: RawFd, dirp: *mut linux_dirent, count: c_uint) / { fd: RawFd, count: c_uint }
-    -> c_int + { dirp: Vec<linux_dirent> @ counted_by((syscall_result as usize) / size_of::<linux_dirent>()) }
+    -> c_int + { dirp: InspectResult<Vec<DirEntry>> @ from_raw_args_and_result() }
     ~ [Desc] for [x86_64: 78],
   getdents64(fd: RawFd, dirp: *mut linux_dirent64, count: c_uint) / { fd: RawFd, count: c_uint }
-    -> c_int + { dirp: Vec<linux_dirent64> @ counted_by((syscall_result as usize) / size_of::<linux_dirent>()) }
+    -> c_int + { dirp: InspectResult<Vec<DirEntry>> @ from_raw_args_and_result() }
     ~ [Desc] for [x86_64: 217, aarch64: 61, riscv64: 61],
   // getdomainname
   // getdtablesize
@@ -314,10 +319,11 @@ gen_syscalls! {
   getuid() / {} -> uid_t ~ [Creds, Pure] for [x86_64: 102, aarch64: 174, riscv64: 174],
   // getuid32
   getxattr(pathname: *const c_char, name: *const c_char, value: *mut c_void, size: size_t) /
-    { pathname: PathBuf, name: CString, size: size_t } -> ssize_t + { value: Vec<u8> @ counted_by(syscall_result) } ~ [File] for [x86_64: 191, aarch64: 8, riscv64: 8],
-  // TODO: support result inspection (need to inspect args inside xattr_args)
+    { pathname: PathBuf, name: CString, size: size_t } -> ssize_t
+    + { value: InspectResult<Option<XattrValue>> @ from_raw_args_and_result() } ~ [File] for [x86_64: 191, aarch64: 8, riscv64: 8],
   getxattrat(dfd: RawFd, pathname: *const c_char, at_flags: c_uint, name: *const c_char, uargs: *mut c_void, r#usize: size_t) /
-    { dfd: RawFd, pathname: PathBuf, at_flags: c_uint, name: CString, uargs: Arc<xattr_args> @ sized_by(raw_args.r#usize) } -> ssize_t ~ [File, Desc] for [x86_64: 464, aarch64: 464, riscv64: 464],
+    { dfd: RawFd, pathname: PathBuf, at_flags: c_uint, name: CString, uargs: InspectResult<Extensible<XattrArgs>> @ from_raw_args() } -> ssize_t
+    + { value: InspectResult<Option<XattrValue>> @ from_raw_args_and_result() } ~ [File, Desc] for [x86_64: 464, aarch64: 464, riscv64: 464],
   // getxgid
   // getxpid
   // getxuid
@@ -335,23 +341,23 @@ gen_syscalls! {
 
   io_getevents(ctx_id: aio_context_t, min_nr: c_long, nr: c_long, events: *mut io_event, timeout: *mut timespec) /
     { ctx_id: aio_context_t, min_nr: c_long, nr: c_long, timeout: Option<timespec> }
-    -> c_int + { events: Vec<io_event> @ counted_by(syscall_result) } ~ [] for [x86_64: 208, aarch64: 4, riscv64: 4],
+    -> c_int + { events: InspectResult<Vec<AioCompletion>> @ from_raw_args_and_result() } ~ [] for [x86_64: 208, aarch64: 4, riscv64: 4],
   io_pgetevents(ctx_id: aio_context_t, min_nr: c_long, nr: c_long, events: *mut io_event, timeout: *mut timespec, sig: *const __aio_sigset) /
     { ctx_id: aio_context_t, min_nr: c_long, nr: c_long, timeout: Option<timespec>, sig: __aio_sigset }
-    -> c_int + { events: Vec<io_event> @ counted_by(syscall_result) } ~ [] for [x86_64: 333, aarch64: 292, riscv64: 292],
+    -> c_int + { events: InspectResult<Vec<AioCompletion>> @ from_raw_args_and_result() } ~ [] for [x86_64: 333, aarch64: 292, riscv64: 292],
   // io_pgetevents_time64
   io_setup(nr_events: c_ulong, ctx_idp: *mut aio_context_t) / { nr_events: c_ulong }
     -> c_int + { ctx_idp: InspectResult<aio_context_t> } ~ [Memory] for [x86_64: 206, aarch64: 0, riscv64: 0],
-  // io_submit: iocbpp is an array of iocb pointers. TODO: how to handle it?
   io_submit(ctx: aio_context_t, nr: c_long, iocbs: *mut *mut iocb) /
-    { ctx: aio_context_t, iocbs: Vec<AddressType> @ counted_by(raw_args.nr) } -> c_int ~ [] for [x86_64: 209, aarch64: 2, riscv64: 2],
-  // io_uring_enter: arg can be a sigset_t ptr or io_uring_getevents_arg ptr depending on the flags
+    { ctx: aio_context_t, iocbs: InspectResult<Vec<InspectResult<AioRequest>>> @ from_raw_args() } -> c_int ~ [] for [x86_64: 209, aarch64: 2, riscv64: 2],
   io_uring_enter(fd: c_uint, to_submit: c_uint, min_complete: c_uint, flags: c_uint, arg: AddressType, argsz: size_t) /
-    { fd: c_uint, to_submit: c_uint, min_complete: c_uint, flags: c_uint, arg: Vec<u8> @ counted_by(raw_args.argsz) }
+    { fd: c_uint, to_submit: c_uint, min_complete: c_uint, flags: BitFlags<IoUringEnterFlags> @ from_raw_args(),
+      arg: InspectResult<IoUringEnterArg> @ from_raw_args(), argsz: size_t }
     -> c_int ~ [Desc, File] for [x86_64: 426, aarch64: 426, riscv64: 426],
   // arg can point to a lot of different struct (array) depending on the op
   io_uring_register(fd: c_uint, op: c_uint, arg: AddressType, nr_args: c_uint) /
-    { fd: c_uint, op: c_uint, arg: AddressType, nr_args: c_uint } -> c_int ~ [Desc, Memory] for [x86_64: 427, aarch64: 427, riscv64: 427],
+    { fd: c_uint, op: IoUringRegister @ from_raw_args(), nr_args: c_uint } -> c_int
+    + { result: InspectResult<IoUringRegisterResult> @ from_raw_args_and_result() } ~ [Desc, Memory] for [x86_64: 427, aarch64: 427, riscv64: 427],
   io_uring_setup(entries: u32, p: *mut io_uring_params) /
     { entries: c_uint, p: io_uring_params } -> c_int + { p: io_uring_params } ~ [Desc] for [x86_64: 425, aarch64: 425, riscv64: 425],
   ioctl(fd: RawFd, request: c_ulong, argp: AddressType) / { fd: RawFd, request: c_ulong, argp: AddressType }
@@ -365,24 +371,25 @@ gen_syscalls! {
     { pid1: pid_t, pid2: pid_t, r#type: c_int, idx1: c_ulong, idx2: c_ulong } -> c_int ~ [] for [x86_64: 312, aarch64: 272, riscv64: 272],
   // kern_features
   kexec_file_load(kernel_fd: RawFd, initrd_fd: RawFd, cmdline_len: c_ulong, cmdline: *const c_char, flags: c_ulong) /
-    { kernel_fd: RawFd, initrd_fd: RawFd, cmdline_len: c_ulong, cmdline: CString, flags: c_ulong } -> c_long
+    { kernel_fd: RawFd, initrd_fd: RawFd, cmdline_len: c_ulong, cmdline: OsString @ sized_string(raw_args.cmdline_len), flags: c_ulong } -> c_long
     ~ [Desc] for [x86_64: 320, aarch64: 294, riscv64: 294],
   kexec_load(entry: c_ulong, nr_segments: c_ulong, segments: *mut kexec_segment, flags: c_ulong) /
     { entry: c_ulong, segments: Vec<kexec_segment> @ counted_by(raw_args.nr_segments), flags: c_ulong } -> c_long
     ~ [] for [x86_64: 246, aarch64: 104, riscv64: 104],
   keyctl(option: c_int, arg2: c_ulong, arg3: c_ulong, arg4: c_ulong, arg5: c_ulong) /
-    { option: c_int, arg2: c_ulong, arg3: c_ulong, arg4: c_ulong, arg5: c_ulong } -> c_long ~ [] for [x86_64: 250, aarch64: 219, riscv64: 219],
+    { operation: KeyctlOp @ from_raw_args() } -> c_long + { result: InspectResult<KeyctlResult> @ from_raw_args_and_result() }
+    ~ [] for [x86_64: 250, aarch64: 219, riscv64: 219],
   kill(pid: pid_t, sig: c_int) / { pid: pid_t, sig: c_int } -> c_int ~ [Signal, Process] for [x86_64: 62, aarch64: 129, riscv64: 129],
-  // TODO: rule_type and rule_attr forms a sum type.
   landlock_add_rule(ruleset_fd: RawFd, rule_type: c_long, rule_attr: *const c_void, flags: u32) /
-    { ruleset_fd: RawFd, rule_type: c_long, rule_attr: Vec<u8>, flags: u32 } -> c_int ~ [Desc] for [x86_64: 445, aarch64: 445, riscv64: 445],
+    { ruleset_fd: RawFd, rule: InspectResult<LandlockRule> @ from_raw_args(), flags: u32 } -> c_int ~ [Desc] for [x86_64: 445, aarch64: 445, riscv64: 445],
   landlock_create_ruleset(ruleset_attr: *const landlock_ruleset_attr, size: size_t, flags: u32) /
-    { ruleset_attr: landlock_ruleset_attr, size: size_t, flags: u32 } -> c_int ~ [Desc] for [x86_64: 444, aarch64: 444, riscv64: 444],
+    { ruleset_attr: InspectResult<Option<Extensible<LandlockRulesetAttr>>> @ from_raw_args(), size: size_t, flags: u32 } -> c_int ~ [Desc] for [x86_64: 444, aarch64: 444, riscv64: 444],
   landlock_restrict_self(ruleset_fd: RawFd, flags: u32) / { ruleset_fd: RawFd, flags: u32 } -> c_int ~ [Desc] for [x86_64: 446, aarch64: 446, riscv64: 446],
   lchown(pathname: *const c_char, owner: uid_t, group: gid_t) / { pathname: PathBuf, owner: uid_t, group: gid_t } -> c_int ~ [File] for [x86_64: 94],
   // lchown32
   lgetxattr(pathname: *const c_char, name: *const c_char, value: *mut c_void, size: size_t) /
-  { pathname: PathBuf, name: CString, size: size_t } -> ssize_t + { value: Vec<u8> @ counted_by(syscall_result) } ~ [File] for [x86_64: 192, aarch64: 9, riscv64: 9],
+    { pathname: PathBuf, name: CString, size: size_t } -> ssize_t
+    + { value: InspectResult<Option<XattrValue>> @ from_raw_args_and_result() } ~ [File] for [x86_64: 192, aarch64: 9, riscv64: 9],
   link(oldpath: *const c_char, newpath: *const c_char) / { oldpath: PathBuf, newpath: PathBuf } -> c_int ~ [File] for [x86_64: 86],
   linkat(olddirfd: RawFd, oldpath: *const c_char, newdirfd: RawFd, newpath: *const c_char, flags: c_int) /
     { olddirfd: RawFd, oldpath: PathBuf, newdirfd: RawFd, newpath: PathBuf, flags: c_int } -> c_int
@@ -404,7 +411,8 @@ gen_syscalls! {
   lremovexattr(path: *const c_char, name: *const c_char) / { path: PathBuf, name: CString } -> c_int ~ [File] for [x86_64: 198, aarch64: 15, riscv64: 15],
   lseek(fd: RawFd, offset: off_t, whence: c_int) / { fd: RawFd, offset: off_t, whence: c_int } -> off_t ~ [Desc] for [x86_64: 8, aarch64: 62, riscv64: 62],
   lsetxattr(path: *const c_char, name: *const c_char, value: *const c_void, size: size_t, flags: c_int) /
-    { path: PathBuf, name: CString, value: CString, size: size_t, flags: c_int } -> c_int ~ [File] for [x86_64: 189, aarch64: 6, riscv64: 6],
+    { path: PathBuf, name: CString, value: InspectResult<XattrValue> @ from_raw_args(), size: size_t, flags: c_int } -> c_int
+    ~ [File] for [x86_64: 189, aarch64: 6, riscv64: 6],
   // lsm: https://lwn.net/Articles/919545/
   // TODO: how to deal with DST arrays?
   lsm_get_self_attr(attr: c_uint, ctx: *mut c_void, size: *mut u32, flags: u32) / { attr: c_uint, size: InspectResult<u32>, flags: u32 }
@@ -419,10 +427,11 @@ gen_syscalls! {
     ~ [Memory] for [x86_64: 28, aarch64: 233, riscv64: 233],
   map_shadow_stack(addr: *mut c_void, len: c_ulong, flags: c_int) / { addr: AddressType, len: c_ulong, flags: c_int } -> c_int
     ~ [Memory] for [x86_64: 453, aarch64: 453, riscv64: 453],
+  // The kernel only uses the first maxnode - 1 bits of the nodemask
   mbind(addr: *mut c_void, len: c_ulong, mode: c_int, nodemask: *const c_ulong, maxnode: c_ulong, flags: c_uint) /
-    { len: c_ulong, mode: c_int,
-       nodemask: Vec<c_ulong> @ counted_by((raw_args.maxnode as usize + (8 * std::mem::size_of::<c_ulong>() - 1)) / (8 * std::mem::size_of::<c_ulong>())),
-       maxnode: c_ulong, flags: c_uint } -> c_long
+    { len: c_ulong, mode: Mempolicy @ from_raw_args(),
+       nodemask: Option<NodeMask> @ counted_by(raw_args.maxnode.saturating_sub(1)),
+       maxnode: c_ulong, flags: BitFlags<MbindFlags> @ from_raw_args() } -> c_long
     ~ [Memory] for [x86_64: 237, aarch64: 235, riscv64: 235],
   membarrier(cmd: c_int, flags: c_uint, cpu_id: c_int) / { cmd: c_int, flags: c_int, cpu_id: c_int } -> c_int
     ~ [Memory] for [x86_64: 324, aarch64: 283, riscv64: 283],
@@ -430,10 +439,11 @@ gen_syscalls! {
     ~ [Desc] for [x86_64: 319, aarch64: 279, riscv64: 279],
   memfd_secret(flags: c_uint) / { flags: c_uint } -> RawFd ~ [Desc] for [x86_64: 447, aarch64: 447, riscv64: 447],
   // memory_ordering
+  // The kernel only uses the first maxnode - 1 bits of the nodemask
   migrate_pages(pid: pid_t, maxnode: c_ulong, old_nodes: *const c_ulong, new_nodes: *const c_ulong) /
     { pid: pid_t, maxnode: c_ulong,
-      old_nodes: Vec<c_ulong> @ counted_by((raw_args.maxnode as usize + (8 * std::mem::size_of::<c_ulong>() - 1)) / (8 * std::mem::size_of::<c_ulong>())),
-      new_nodes: Vec<c_ulong> @ counted_by((raw_args.maxnode as usize + (8 * std::mem::size_of::<c_ulong>() - 1)) / (8 * std::mem::size_of::<c_ulong>())) }
+      old_nodes: Option<NodeMask> @ counted_by(raw_args.maxnode.saturating_sub(1)),
+      new_nodes: Option<NodeMask> @ counted_by(raw_args.maxnode.saturating_sub(1)) }
     -> c_long ~ [Memory] for [x86_64: 256, aarch64: 238, riscv64: 238],
   // mincore: vec is at least of len (length+PAGE_SIZE-1) / PAGE_SIZE, where PAGE_SIZE is sysconf(_SC_PAGESIZE)
   mincore(addr: *mut c_void, length: size_t, vec: *mut c_uchar) / { addr: AddressType, length: size_t } -> c_int + { vec: Vec<c_uchar> }
@@ -458,10 +468,11 @@ gen_syscalls! {
     { source: CString, target: PathBuf, filesystemtype: CString, mountflags: c_ulong, data: Option<CString> } -> c_int
     ~ [File] for [x86_64: 165, aarch64: 40, riscv64: 40],
   mount_setattr(dirfd: RawFd, pathname: *const c_char, flags: c_uint, uattr: *mut c_void, r#usize: size_t) /
-    { dirfd: RawFd, pathname: PathBuf, flags: c_uint, uattr: Arc<mount_attr> @ sized_by(raw_args.r#usize) } -> c_int
+    { dirfd: RawFd, pathname: PathBuf, flags: c_uint, uattr: Extensible<mount_attr> @ sized_by(raw_args.r#usize) } -> c_int
     ~ [Desc, File] for [x86_64: 442, aarch64: 442, riscv64: 442],
   move_mount(from_dfd: RawFd, from_path: *const c_char, to_dfd: RawFd, to_path: *const c_char, ms_flags: c_uint) /
-    { from_dfd: RawFd, from_path: PathBuf, to_dfd: RawFd, to_path: PathBuf, ms_flags: c_uint } -> c_int
+    { from_dfd: RawFd, from_path: PathBuf, to_dfd: RawFd, to_path: PathBuf, ms_flags: BitFlags<MoveMountFlags> @ from_raw_args() }
+    -> c_int
     ~ [Desc, File] for [x86_64: 429, aarch64: 429, riscv64: 429],
   // move_pages: pages, nodes and status are arrays of size count
   move_pages(pid: pid_t, count: c_ulong, pages: *mut *mut c_void, nodes: *const c_int, status: *mut c_int, flags: c_int) /
@@ -479,13 +490,13 @@ gen_syscalls! {
   mq_open(name: *const c_char, oflag: c_int, mode: mode_t, attr: *mut mq_attr) /
     { name: CString, oflag: c_int, mode: mode_t, attr: Option<mq_attr> } -> mqd_t
     ~ [Desc] for [x86_64: 240, aarch64: 180, riscv64: 180],
-  mq_timedreceive(mqdes: mqd_t, msg_ptr: *mut *mut c_char, msg_len: size_t, msg_prio: *mut c_uint, abs_timeout: *const timespec) /
+  mq_timedreceive(mqdes: mqd_t, msg_ptr: *mut c_char, msg_len: size_t, msg_prio: *mut c_uint, abs_timeout: *const timespec) /
     { mqdes: mqd_t, msg_len: size_t, abs_timeout: timespec } -> ssize_t
-    + { msg_ptr: Vec<CString> @ counted_by(raw_args.msg_len), msg_prio: Option<Vec<c_uint>> @ counted_by(raw_args.msg_len) }
+    + { msg_ptr: Vec<u8> @ sized_by(syscall_result), msg_prio: Option<c_uint> }
     ~ [Desc] for [x86_64: 243, aarch64: 183, riscv64: 183],
   // mq_timedreceive_time64
   mq_timedsend(mqdes: mqd_t, msg_ptr: *const c_char, msg_len: size_t, msg_prio: c_uint, abs_timeout: *const timespec) /
-    { mqdes: mqd_t, msg_ptr: CString, msg_len: size_t, msg_prio: c_uint, abs_timeout: timespec } -> c_int
+    { mqdes: mqd_t, msg_ptr: Vec<u8> @ sized_by(raw_args.msg_len), msg_len: size_t, msg_prio: c_uint, abs_timeout: timespec } -> c_int
     ~ [Desc] for [x86_64: 242, aarch64: 182, riscv64: 182],
   // mq_timedsend_time64
   mq_unlink(name: *const c_char) / { name: CString } -> c_int ~ [] for [x86_64: 241, aarch64: 181, riscv64: 181],
@@ -495,14 +506,15 @@ gen_syscalls! {
   // mseal: 6.10 https://lwn.net/Articles/954936/
   mseal(start: AddressType, len: size_t, types: c_ulong, flags: c_ulong) / { start: AddressType, len: size_t, types: c_ulong, flags: c_ulong } -> c_int
     ~ [Memory] for [x86_64: 462, aarch64: 462, riscv64: 462],
-  msgctl(msqid: c_int, cmd: c_int, buf: *mut msqid_ds) / { msqid: c_int, cmd: c_int } -> c_int + { buf: msqid_ds }
+  msgctl(msqid: c_int, cmd: c_int, buf: *mut msqid_ds) / { msqid: c_int, cmd: MsgctlCmd @ from_raw_args() } -> c_int
+    + { buf: InspectResult<MsgctlResult> @ from_raw_args_and_result() }
     ~ [] for [x86_64: 71, aarch64: 187, riscv64: 187],
   msgget(key: key_t, msgflg: c_int) / { key: key_t, msgflg: c_int } -> c_int ~ [] for [x86_64: 68, aarch64: 186, riscv64: 186],
   msgrcv(msqid: c_int, msgp: *mut c_void, msgsz: size_t, msgtyp: c_long, msgflg: c_int) /
     { msqid: c_int, msgsz: size_t, msgtyp: c_long, msgflg: c_int } -> ssize_t + { msgp: Arc<msgbuf> @ sized_by(size_of::<c_long>() + syscall_result as usize) }
     ~ [] for [x86_64: 70, aarch64: 188, riscv64: 188],
   msgsnd(msqid: c_int, msgp: *const c_void, msgsz: size_t, msgflg: c_int) /
-    { msqid: c_int, msgp: Arc<msgbuf> @ sized_by(size_of::<c_long>() + raw_args.msgsz as usize), msgflg: c_int } -> c_int ~ [] for [x86_64: 69, aarch64: 189, riscv64: 189],
+    { msqid: c_int, msgp: Arc<msgbuf> @ sized_by(size_of::<c_long>() + raw_args.msgsz), msgflg: c_int } -> c_int ~ [] for [x86_64: 69, aarch64: 189, riscv64: 189],
   msync(addr: *mut c_void, length: size_t, flags: c_int) / { addr: AddressType, length: size_t, flags: c_int } -> c_int
     ~ [Memory] for [x86_64: 26, aarch64: 227, riscv64: 227],
   // multiplexer
@@ -534,24 +546,23 @@ gen_syscalls! {
       handle: Arc<file_handle> @ sized_by_result(<InspectResult<c_uint> as InspectFromPid>::inspect_from(inspectee_pid, raw_args.handle as AddressType)),
       flags: c_int }
     -> RawFd ~ [Desc] for [x86_64: 304, aarch64: 265, riscv64: 265],
-  open_tree(dirfd: RawFd, path: *const c_char, flags: c_uint) / { dirfd: RawFd, path: PathBuf, flags: c_uint } -> c_int
+  open_tree(dirfd: RawFd, path: *const c_char, flags: c_uint) / { dirfd: RawFd, path: PathBuf, flags: BitFlags<OpenTreeFlags> @ from_raw_args() } -> c_int
     ~ [Desc, File] for [x86_64: 428, aarch64: 428, riscv64: 428],
   open_tree_attr(dfd: RawFd, filename: *const c_char, flags: c_uint, uattr: *mut c_void, r#usize: size_t) /
-    { dfd: RawFd, filename: PathBuf, flags: c_uint, uattr: Arc<mount_attr> @ sized_by(raw_args.r#usize) } -> c_int
+    { dfd: RawFd, filename: PathBuf, flags: BitFlags<OpenTreeFlags> @ from_raw_args(), uattr: Extensible<mount_attr> @ sized_by(raw_args.r#usize) } -> c_int
     ~ [Desc, File] for [x86_64: 467, aarch64: 467, riscv64: 467],
   openat(dirfd: RawFd, pathname: *const c_char, flags: c_int, mode: mode_t) /
     { dirfd: RawFd, pathname: PathBuf, flags: c_int, mode: mode_t } -> RawFd ~ [Desc, File] for [x86_64: 257, aarch64: 56, riscv64: 56],
   openat2(dirfd: RawFd, pathname: *const c_char, how: *mut open_how, size: size_t) /
-    { dirfd: RawFd, pathname: PathBuf, how: open_how, size: size_t } -> c_int ~ [Desc, File] for [x86_64: 437, aarch64: 437, riscv64: 437],
+    { dirfd: RawFd, pathname: PathBuf, how: Extensible<open_how> @ sized_by(raw_args.size), size: size_t } -> c_int ~ [Desc, File] for [x86_64: 437, aarch64: 437, riscv64: 437],
   // or1k_atomic
   // osf_*
   pause() / {} -> c_int ~ [Signal] for [x86_64: 34],
   // pciconfig_iobase
   // pciconfig_read
   // pciconfig_write
-  // perf_event_open: TODO: attr is perf_event_attr struct
   perf_event_open(attr: *mut c_void, pid: pid_t, cpu: c_int, group_fd: RawFd, flags: c_ulong) /
-    { attr: AddressType, pid: pid_t, cpu: c_int, group_fd: RawFd, flags: c_ulong } -> RawFd
+    { attr: Extensible<PerfEventAttr>, pid: pid_t, cpu: c_int, group_fd: RawFd, flags: c_ulong } -> RawFd
     ~ [Desc] for [x86_64: 298, aarch64: 241, riscv64: 241],
   // perfctr
   personality(persona: c_ulong) / { persona: c_ulong } -> c_int ~ [] for [x86_64: 135, aarch64: 92, riscv64: 92],
@@ -559,7 +570,7 @@ gen_syscalls! {
     ~ [Desc] for [x86_64: 438, aarch64: 438, riscv64: 438],
   pidfd_open(pid: pid_t, flags: c_uint) / { pid: pid_t, flags: c_uint } -> RawFd ~ [Desc] for [x86_64: 434, aarch64: 434, riscv64: 434],
   pidfd_send_signal(pidfd: RawFd, sig: c_int, info: *mut siginfo_t, flags: c_uint) /
-    { pidfd: RawFd, sig: c_int, info: Option<siginfo_t>, flags: c_uint } -> c_int
+    { pidfd: RawFd, sig: c_int, info: Option<SignalInfo>, flags: c_uint } -> c_int
     ~ [Desc, Signal, Process] for [x86_64: 424, aarch64: 424, riscv64: 424],
   pipe(pipefd: *mut c_int) / {} -> c_int + { pipefd: [RawFd; 2] } ~ [Desc] for [x86_64: 22],
   pipe2(pipefd: *mut c_int, flags: c_int) / { flags: c_int } -> c_int + { pipefd: [RawFd; 2] } ~ [Desc] for [x86_64: 293, aarch64: 59, riscv64: 59],
@@ -577,7 +588,7 @@ gen_syscalls! {
     ~ [Desc] for [x86_64: 271, aarch64: 73, riscv64: 73],
   // ppoll_time64
   prctl(option: c_int, arg2: c_ulong, arg3: c_ulong, arg4: c_ulong, arg5: c_ulong) /
-    { option: c_int, arg2: c_ulong, arg3: c_ulong, arg4: c_ulong, arg5: c_ulong } -> c_int
+    { op: PrctlOp @ from_raw_args() } -> c_int + { result: InspectResult<PrctlResult> @ from_raw_args_and_result() }
     ~ [Clock] for [x86_64: 157, aarch64: 167, riscv64: 167],
   pread64(fd: RawFd, buf: *mut c_void, count: size_t, offset: loff_t) /
     { fd: RawFd, count: size_t, offset: loff_t } -> ssize_t + { buf: Vec<u8> @ counted_by(syscall_result) }
@@ -599,13 +610,16 @@ gen_syscalls! {
   process_vm_writev(pid: pid_t, local_iov: *const iovec, liovcnt: c_ulong, remote_iov: *const iovec, riovcnt: c_ulong, flags: c_ulong) /
     { pid: pid_t, local_iov: Vec<iovec> @ counted_by(raw_args.liovcnt), remote_iov: Vec<iovec> @ counted_by(raw_args.riovcnt), flags: c_ulong }
     -> ssize_t ~ [] for [x86_64: 311, aarch64: 271, riscv64: 271],
-  // TODO: sigmask is { const kernel_sigset_t *ss;  size_t ss_len; /* Size (in bytes) of object pointed to by 'ss' */ }
   pselect6(nfds: c_int, readfds: *mut fd_set, writefds: *mut fd_set, exceptfds: *mut fd_set, timeout: *mut timespec, sigmask: *const c_void) /
-    { readfds: Option<fd_set>, writefds: Option<fd_set>, exceptfds: Option<fd_set>, timeout: Option<timespec>, sigmask: Option<sigset_t> }
-    -> c_int + { readfds: Option<fd_set>, writefds: Option<fd_set>, exceptfds: Option<fd_set> }
+    { nfds: c_int, readfds: Option<FdSet> @ counted_by(raw_args.nfds.max(0)), writefds: Option<FdSet> @ counted_by(raw_args.nfds.max(0)),
+      exceptfds: Option<FdSet> @ counted_by(raw_args.nfds.max(0)), timeout: Option<timespec>,
+      sigmask: InspectResult<Option<SigSet>> @ from_raw_args() }
+    -> c_int + { readfds: Option<FdSet> @ counted_by(raw_args.nfds.max(0)), writefds: Option<FdSet> @ counted_by(raw_args.nfds.max(0)),
+      exceptfds: Option<FdSet> @ counted_by(raw_args.nfds.max(0)) }
     ~ [Desc] for [x86_64: 270, aarch64: 72, riscv64: 72],
   // pselect6_time64
-  ptrace(request: c_int, pid: pid_t, addr: *mut c_void, data: *mut c_void) / { } -> c_long
+  ptrace(request: c_int, pid: pid_t, addr: *mut c_void, data: *mut c_void) /
+    { request: PtraceRequest @ from_raw_args(), pid: pid_t } -> c_long + { data: InspectResult<PtraceResult> @ from_raw_args_and_result() }
     ~ [] for [x86_64: 101, aarch64: 117, riscv64: 117],
   pwrite64(fd: RawFd, buf: *const c_void, count: size_t, offset: loff_t) /
     { fd: RawFd, buf: Vec<u8> @ counted_by(raw_args.count), offset: loff_t } -> ssize_t ~ [Desc] for [x86_64: 18, aarch64: 68, riscv64: 68],
@@ -623,9 +637,9 @@ gen_syscalls! {
     ~ [Desc] for [x86_64: 187, aarch64: 213, riscv64: 213],
   // readdir(fd: RawFd, dirp: AddressType, count: c_uint) / { fd: RawFd, count: c_uint } -> c_int + { dirp: Vec<u8> } ~ [Desc] for [],
   readlink(pathname: *const c_char, buf: *mut c_char, bufsiz: size_t) / { pathname: PathBuf, bufsiz: size_t } -> ssize_t
-    + { buf: Vec<u8> @ counted_by(syscall_result) } ~ [File] for [x86_64: 89],
+    + { buf: PathBuf @ sized_string(syscall_result) } ~ [File] for [x86_64: 89],
   readlinkat(dirfd: RawFd, pathname: *const c_char, buf: *mut c_char, bufsiz: size_t) /
-    { dirfd: RawFd, pathname: PathBuf, bufsiz: size_t } -> ssize_t + { buf: Vec<u8> @ counted_by(syscall_result) }
+    { dirfd: RawFd, pathname: PathBuf, bufsiz: size_t } -> ssize_t + { buf: PathBuf @ sized_string(syscall_result) }
     ~ [Desc, File] for [x86_64: 267, aarch64: 78, riscv64: 78],
   readv(fd: RawFd, iov: *const iovec, iovcnt: c_int) / { fd: RawFd, iov: Vec<iovec> @ counted_by(raw_args.iovcnt) } -> ssize_t
     ~ [Desc] for [x86_64: 19, aarch64: 65, riscv64: 65],
@@ -658,7 +672,8 @@ gen_syscalls! {
     { olddirfd: RawFd, oldpath: PathBuf, newdirfd: RawFd, newpath: PathBuf, flags: c_uint } -> c_int
     ~ [Desc, File] for [x86_64: 316, aarch64: 276, riscv64: 276],
   request_key(r#type: *const c_char, description: *const c_char, callout_info: *const c_char, dest_keyring: key_serial_t) /
-    { r#type: CString, description: CString, callout_info: Option<CString>, dest_keyring: key_serial_t } -> key_serial_t
+    { r#type: CString, description: CString, callout_info: Option<CString>, dest_keyring: KeySerial @ from_raw_args() }
+    -> key_serial_t
     ~ [] for [x86_64: 249, aarch64: 218, riscv64: 218],
   restart_syscall() / {} -> c_long ~ [] for [x86_64: 219, aarch64: 128, riscv64: 128],
   riscv_flush_icache(start: *mut c_void, end: *mut c_void, flags: c_ulong) / { start: AddressType, end: AddressType, flags: c_ulong } -> c_int
@@ -666,7 +681,7 @@ gen_syscalls! {
   // https://docs.kernel.org/6.5/riscv/hwprobe.html
   riscv_hwprobe(pairs: *mut riscv_hwprobe, pair_count: size_t, cpu_count: size_t, cpus: *mut c_ulong, flags: c_uint) /
     { pairs: Vec<riscv_hwprobe> @ counted_by(raw_args.pair_count), pair_count: size_t, cpu_count: size_t,
-      cpus: Vec<c_ulong> @ counted_by((raw_args.cpu_count as usize + (8 * std::mem::size_of::<c_ulong>() - 1)) / (8 * std::mem::size_of::<c_ulong>())),
+      cpus: Vec<c_ulong> @ counted_by((raw_args.cpu_count as usize).div_ceil(8 * std::mem::size_of::<c_ulong>())),
       flags: c_uint }
     -> c_int + { pairs: Vec<riscv_hwprobe> @ counted_by(raw_args.pair_count) }
     ~ [] for [riscv64: 258],
@@ -674,32 +689,36 @@ gen_syscalls! {
   rseq(rseq: *mut c_void, rseq_len: u32, flags: c_int, sig: u32) / { rseq: Arc<rseq> @ sized_by(raw_args.rseq_len), flags: c_int, sig: u32 } -> c_int
     ~ [] for [x86_64: 334, aarch64: 293, riscv64: 293],
   rt_sigaction(sig: c_int, act: *const sigaction, oact: *mut sigaction, sigsetsize: size_t) /
-    { sig: c_int, act: Option<sigaction>, oact: Option<sigaction>, sigsetsize: size_t } -> c_int + { oact: Option<sigaction> }
+    { sig: c_int, act: InspectResult<Option<KernelSigaction>> @ from_raw_args(), sigsetsize: size_t } -> c_int
+    + { oact: InspectResult<Option<KernelSigaction>> @ from_raw_args_and_result() }
     ~ [Signal] for [x86_64: 13, aarch64: 134, riscv64: 134],
-  rt_sigpending(set: *mut sigset_t, sigsetsize: size_t) / { set: sigset_t, sigsetsize: size_t } -> c_int
+  rt_sigpending(set: *mut sigset_t, sigsetsize: size_t) / { sigsetsize: size_t } -> c_int
+    + { set: InspectResult<SigSet> @ from_raw_args_and_result() }
     ~ [Signal] for [x86_64: 127, aarch64: 136, riscv64: 136],
   rt_sigprocmask(how: c_int, set: *const sigset_t, oldset: *mut sigset_t, sigsetsize: size_t) /
-    { how: c_int, set: Option<sigset_t>, oldset: Option<sigset_t>, sigsetsize: size_t } -> c_int + { oldset: Option<sigset_t> }
+    { how: c_int, set: InspectResult<Option<SigSet>> @ from_raw_args(), sigsetsize: size_t } -> c_int
+    + { oldset: InspectResult<Option<SigSet>> @ from_raw_args_and_result() }
     ~ [Signal] for [x86_64: 14, aarch64: 135, riscv64: 135],
-  rt_sigqueueinfo(pid: pid_t, sig: c_int, info: *mut siginfo_t) / { pid: pid_t, sig: c_int, info: siginfo_t } -> c_int
+  rt_sigqueueinfo(pid: pid_t, sig: c_int, info: *mut siginfo_t) / { pid: pid_t, sig: c_int, info: SignalInfo } -> c_int
     ~ [Signal, Process] for [x86_64: 129, aarch64: 138, riscv64: 138],
   // TODO: regs is pt_regs struct
   rt_sigreturn(regs: *mut c_void) / {} -> c_int ~ [Signal] for [x86_64: 15, aarch64: 139, riscv64: 139],
   rt_sigsuspend(newset: *mut sigset_t, sigsetsize: size_t) /
-    { newset: sigset_t, sigsetsize: size_t } -> c_int ~ [Signal] for [x86_64: 130, aarch64: 133, riscv64: 133],
+    { newset: InspectResult<SigSet> @ from_raw_args(), sigsetsize: size_t } -> c_int ~ [Signal] for [x86_64: 130, aarch64: 133, riscv64: 133],
   rt_sigtimedwait(set: *const sigset_t, info: *mut siginfo_t, timeout: *const timespec, sigsetsize: size_t) /
-    { set: sigset_t, info: siginfo_t, timeout: timespec, sigsetsize: size_t } -> c_int + { info: Option<siginfo_t> }
+    { set: InspectResult<SigSet> @ from_raw_args(), timeout: Option<timespec>, sigsetsize: size_t } -> c_int
+    + { info: Option<SignalInfo> }
     ~ [Signal] for [x86_64: 128, aarch64: 137, riscv64: 137],
   // rt_sigtimedwait_time64
   rt_tgsigqueueinfo(tgid: pid_t, pid: pid_t, sig: c_int, info: *mut siginfo_t) /
-    { tgid: pid_t, pid: pid_t, sig: c_int, info: siginfo_t } -> c_int ~ [Signal, Process] for [x86_64: 297, aarch64: 240, riscv64: 240],
+    { tgid: pid_t, pid: pid_t, sig: c_int, info: SignalInfo } -> c_int ~ [Signal, Process] for [x86_64: 297, aarch64: 240, riscv64: 240],
   // rtas
   // sched_get_affinity
   sched_get_priority_max(policy: c_int) / { policy: c_int } -> c_int ~ [] for [x86_64: 146, aarch64: 125, riscv64: 125],
   sched_get_priority_min(policy: c_int) / { policy: c_int } -> c_int ~ [] for [x86_64: 147, aarch64: 126, riscv64: 126],
   // sched_getaffinity syscall returns (in bytes) the number of bytes placed copied into the mask buffer. arg cpusetsize is also in bytes
   sched_getaffinity(pid: pid_t, cpusetsize: size_t, mask: *mut c_ulong) /
-    { pid: pid_t, cpusetsize: size_t } -> c_int + { mask: Vec<u8> @ counted_by(syscall_result) }
+    { pid: pid_t, cpusetsize: size_t } -> c_int + { mask: CpuSet @ counted_by(syscall_result as usize * 8) }
     ~ [] for [x86_64: 204, aarch64: 123, riscv64: 123],
   sched_getattr(pid: pid_t, attr: *mut sched_attr, size: c_uint, flags: c_uint) /
     { pid: pid_t, size: c_uint, flags: c_uint } -> c_int + { attr: sched_attr }
@@ -712,10 +731,10 @@ gen_syscalls! {
   // sched_rr_get_interval_time64
   // sched_set_affinity
   sched_setaffinity(pid: pid_t, cpusetsize: size_t, mask: *const c_ulong) /
-    { pid: pid_t, cpusetsize: size_t, mask: Vec<u8> @ counted_by(cpusetsize) } -> c_int
+    { pid: pid_t, cpusetsize: size_t, mask: CpuSet @ counted_by(raw_args.cpusetsize.saturating_mul(8)) } -> c_int
     ~ [] for [x86_64: 203, aarch64: 122, riscv64: 122],
   // sched_setattr size is the first field of sched_attr struct
-  sched_setattr(pid: pid_t, attr: *mut sched_attr, flags: c_uint) / { pid: pid_t, attr: sched_attr, flags: c_uint } -> c_int
+  sched_setattr(pid: pid_t, attr: *mut sched_attr, flags: c_uint) / { pid: pid_t, attr: Extensible<sched_attr>, flags: c_uint } -> c_int
     ~ [] for [x86_64: 314, aarch64: 274, riscv64: 274],
   sched_setparam(pid: pid_t, param: *const sched_param) / { pid: pid_t, param: sched_param } -> c_int
     ~ [] for [x86_64: 142, aarch64: 118, riscv64: 118],
@@ -723,15 +742,16 @@ gen_syscalls! {
     { pid: pid_t, policy: c_int, param: sched_param } -> c_int
     ~ [] for [x86_64: 144, aarch64: 119, riscv64: 119],
   sched_yield() / {} -> c_int ~ [] for [x86_64: 24, aarch64: 124, riscv64: 124],
-  // seccomp: TODO: decode args
-  seccomp(operation: c_int, flags: c_uint, args: *mut c_void) / { operation: c_int, flags: c_uint } -> c_int
+  seccomp(operation: c_uint, flags: c_uint, args: *mut c_void) / { operation: SeccompOp @ from_raw_args() } -> c_int
     ~ [] for [x86_64: 317, aarch64: 277, riscv64: 277],
   select(nfds: c_int, readfds: *mut fd_set, writefds: *mut fd_set, exceptfds: *mut fd_set, timeout: *mut timeval) /
-    { nfds: c_int, readfds: Option<fd_set>, writefds: Option<fd_set>, exceptfds: Option<fd_set>, timeout: Option<timeval> }
-    -> c_int + { readfds: Option<fd_set>, writefds: Option<fd_set>, exceptfds: Option<fd_set> }
+    { nfds: c_int, readfds: Option<FdSet> @ counted_by(raw_args.nfds.max(0)), writefds: Option<FdSet> @ counted_by(raw_args.nfds.max(0)),
+      exceptfds: Option<FdSet> @ counted_by(raw_args.nfds.max(0)), timeout: Option<timeval> }
+    -> c_int + { readfds: Option<FdSet> @ counted_by(raw_args.nfds.max(0)), writefds: Option<FdSet> @ counted_by(raw_args.nfds.max(0)),
+      exceptfds: Option<FdSet> @ counted_by(raw_args.nfds.max(0)) }
     ~ [Desc] for [x86_64: 23],
-  // semctl: TODO: decode arg
-  semctl(semid: c_int, semnum: c_int, cmd: c_int, arg: *mut c_void) / { semid: c_int, semnum: c_int, cmd: c_int } -> c_int
+  semctl(semid: c_int, semnum: c_int, cmd: c_int, arg: *mut c_void) / { semid: c_int, cmd: SemctlCmd @ from_raw_args() } -> c_int
+    + { arg: InspectResult<SemctlResult> @ from_raw_args_and_result() }
     ~ [IPC] for [x86_64: 66, aarch64: 191, riscv64: 191],
   semget(key: key_t, nsems: c_int, semflg: c_int) / { key: key_t, nsems: c_int, semflg: c_int } -> c_int
     ~ [IPC] for [x86_64: 64, aarch64: 190, riscv64: 190],
@@ -753,9 +773,10 @@ gen_syscalls! {
   sendto(sockfd: RawFd, buf: *const c_void, len: size_t, flags: c_int, dest_addr: *const sockaddr, addrlen: socklen_t) /
     { sockfd: RawFd, buf: Vec<u8> @ counted_by(raw_args.len), flags: c_int, dest_addr: Option<sockaddr> } -> ssize_t
     ~ [Network] for [x86_64: 44, aarch64: 206, riscv64: 206],
+  // The kernel only uses the first maxnode - 1 bits of the nodemask
   set_mempolicy(mode: c_int, nodemask: *const c_ulong, maxnode: c_ulong) /
-    { mode: c_int,
-      nodemask: Vec<c_ulong> @ counted_by((raw_args.maxnode as usize + (8 * std::mem::size_of::<c_ulong>() - 1)) / (8 * std::mem::size_of::<c_ulong>())),
+    { mode: Mempolicy @ from_raw_args(),
+      nodemask: Option<NodeMask> @ counted_by(raw_args.maxnode.saturating_sub(1)),
       maxnode: c_ulong }
      -> c_int ~ [Memory] for [x86_64: 238, aarch64: 237, riscv64: 237],
   set_mempolicy_home_node(start: c_ulong, len: c_ulong, home_mode: c_ulong, flags: c_ulong) /
@@ -765,8 +786,7 @@ gen_syscalls! {
     ~ [] for [x86_64: 273, aarch64: 99, riscv64: 99],
   set_thread_area(u_info: *mut user_desc) / { u_info: user_desc } -> c_int ~ [] for [x86_64: 205],
   set_tid_address(tidptr: *mut c_int) / { tidptr: AddressType } -> pid_t ~ [] for [x86_64: 218, aarch64: 96, riscv64: 96],
-  // setdomainname: FIXME: name doesn't require terminating null.
-  setdomainname(name: *const c_char, len: size_t) / { name: CString } -> c_int ~ [] for [x86_64: 171, aarch64: 162, riscv64: 162],
+  setdomainname(name: *const c_char, len: size_t) / { name: OsString @ sized_string(raw_args.len), len: size_t } -> c_int ~ [] for [x86_64: 171, aarch64: 162, riscv64: 162],
   setfsgid(fsgid: gid_t) / { fsgid: gid_t } -> c_int ~ [Creds] for [x86_64: 123, aarch64: 152, riscv64: 152],
   // setfsgid32
   setfsuid(fsuid: uid_t) / { fsuid: uid_t } -> c_int ~ [Creds] for [x86_64: 122, aarch64: 151, riscv64: 151],
@@ -777,8 +797,7 @@ gen_syscalls! {
     ~ [Creds] for [x86_64: 116, aarch64: 159, riscv64: 159],
   // setgroups32
   // sethae
-  // sethostname: FIXME: name doesn't require terminating null.
-  sethostname(name: *const c_char, len: size_t) / { name: CString, len: size_t } -> c_int
+  sethostname(name: *const c_char, len: size_t) / { name: OsString @ sized_string(raw_args.len), len: size_t } -> c_int
     ~ [] for [x86_64: 170, aarch64: 161, riscv64: 161],
   setitimer(which: c_int, new_value: *const itimerval, old_value: *mut itimerval) /
     { which: c_int, new_value: itimerval, old_value: Option<itimerval> } -> c_int
@@ -802,22 +821,24 @@ gen_syscalls! {
     ~ [] for [x86_64: 160, aarch64: 164, riscv64: 164],
   setsid() / {} -> pid_t ~ [] for [x86_64: 112, aarch64: 157, riscv64: 157],
   setsockopt(sockfd: RawFd, level: c_int, optname: c_int, optval: *const c_void, optlen: socklen_t) /
-    { sockfd: RawFd, level: c_int, optname: c_int, optval: Vec<u8> @ counted_by(raw_args.optlen) } -> c_int
+    { sockfd: RawFd, level: c_int, optname: c_int, optval: SockoptValue @ from_raw_args() } -> c_int
     ~ [Network] for [x86_64: 54, aarch64: 208, riscv64: 208],
   settimeofday(tv: *const timeval, tz: *const timezone) / { tv: timeval, tz: Option<timezone> } -> c_int
     ~ [Clock] for [x86_64: 164, aarch64: 170, riscv64: 170],
   setuid(uid: uid_t) / { uid: uid_t } -> c_int ~ [Creds] for [x86_64: 105, aarch64: 146, riscv64: 146],
   // setuid32
   setxattr(path: *const c_char, name: *const c_char, value: *const c_void, size: size_t, flags: c_int) /
-    { path: PathBuf, name: CString, value: CString, size: size_t, flags: c_int } -> c_int ~ [File] for [x86_64: 188, aarch64: 5, riscv64: 5],
-  // TODO: same as getxattrat
+    { path: PathBuf, name: CString, value: InspectResult<XattrValue> @ from_raw_args(), size: size_t, flags: c_int } -> c_int
+    ~ [File] for [x86_64: 188, aarch64: 5, riscv64: 5],
   setxattrat(dfd: RawFd, pathname: *const c_char, at_flags: c_uint, name: *const c_char, uargs: *const c_void, r#usize: size_t) /
-    { dfd: RawFd, pathname: PathBuf, at_flags: c_uint, name: CString, uargs: Arc<xattr_args> @ sized_by(raw_args.r#usize) } -> c_int
+    { dfd: RawFd, pathname: PathBuf, at_flags: c_uint, name: CString, uargs: InspectResult<Extensible<XattrArgs>> @ from_raw_args(),
+      value: InspectResult<XattrValue> @ from_raw_args() } -> c_int
     ~ [File, Desc] for [x86_64: 463, aarch64: 463, riscv64: 463],
   // sgetmask
   shmat(shmid: c_int, shmaddr: *const c_void, shmflg: c_int) / { shmid: c_int, shmaddr: AddressType, shmflg: c_int } -> AddressType
     ~ [IPC, Memory] for [x86_64: 30, aarch64: 196, riscv64: 196],
-  shmctl(shmid: c_int, cmd: c_int, buf: *mut shmid_ds) / { shmid: c_int, cmd: c_int, buf: shmid_ds } -> c_int
+  shmctl(shmid: c_int, cmd: c_int, buf: *mut shmid_ds) / { shmid: c_int, cmd: ShmctlCmd @ from_raw_args() } -> c_int
+    + { buf: InspectResult<ShmctlResult> @ from_raw_args_and_result() }
     ~ [IPC] for [x86_64: 31, aarch64: 195, riscv64: 195],
   shmdt(shmaddr: *const c_void) / { shmaddr: AddressType } -> c_int ~ [IPC, Memory] for [x86_64: 67, aarch64: 197, riscv64: 197],
   shmget(key: key_t, size: size_t, shmflg: c_int) / { key: key_t, size: size_t, shmflg: c_int } -> c_int
@@ -853,9 +874,9 @@ gen_syscalls! {
   statfs(path: *const c_char, buf: *mut statfs) / { path: PathBuf } -> c_int + { buf: statfs }
     ~ [File, StatFs, StatFsLike] for [x86_64: 137, aarch64: 43, riscv64: 43],
   // statfs64
-  // statmount: TODO: the length is the first field of the struct
+  // statmount: the length of req is the first field of the struct
   statmount(req: *const mnt_id_req, buf: *mut c_void, bufsize: size_t, flags: c_uint) /
-    { req: mnt_id_req, bufsize: size_t, flags: c_uint } -> c_int + { buf: Arc<statmount> @ sized_by(raw_args.bufsize) }
+    { req: Extensible<mnt_id_req>, bufsize: size_t, flags: c_uint } -> c_int + { buf: Arc<statmount> @ sized_by(raw_args.bufsize) }
     ~ [] for [x86_64: 457, aarch64: 457, riscv64: 457],
   statx(dirfd: RawFd, pathname: *const c_char, flags: c_int, mask: c_uint, statxbuf: *mut statx) /
     { dirfd: RawFd, pathname: PathBuf, flags: c_int, mask: c_uint } -> c_int + { statxbuf: statx }
@@ -884,7 +905,8 @@ gen_syscalls! {
   sysfs(option: c_int, arg1: c_ulong, arg2: c_ulong) / { option: c_int, arg1: c_ulong, arg2: c_ulong } -> c_int
     ~ [] for [x86_64: 139],
   sysinfo(info: *mut sysinfo) / { } -> c_int + { info: sysinfo } ~ [] for [x86_64: 99, aarch64: 179, riscv64: 179],
-  syslog(typ: c_int, buf: *const c_char, len: c_int) / { typ: c_int, buf: CString, len: c_int } -> c_int
+  syslog(typ: c_int, buf: *mut c_char, len: c_int) / { typ: SyslogAction @ from_raw_args(), len: c_int } -> c_int
+    + { buf: InspectResult<Option<OsString>> @ from_raw_args_and_result() }
     ~ [] for [x86_64: 103, aarch64: 116, riscv64: 116],
   // sysmips
   tee(fd_in: RawFd, fd_out: RawFd, len: size_t, flags: c_uint) / { fd_in: RawFd, fd_out: RawFd, len: size_t, flags: c_uint } -> ssize_t
@@ -949,10 +971,13 @@ gen_syscalls! {
     { fd: RawFd, iov: Vec<iovec> @ counted_by(raw_args.nr_segs), flags: c_uint } -> ssize_t
     ~ [Desc] for [x86_64: 278, aarch64: 75, riscv64: 75],
   wait4(pid: pid_t, wstatus: *mut c_int, options: c_int, rusage: *mut rusage) /
-    { pid: pid_t, options: c_int } -> pid_t + { wstatus: InspectResult<c_int>, rusage: Option<rusage> }
+    { pid: pid_t, options: BitFlags<WaitOptions> @ from_raw_args() } -> pid_t
+    + { wstatus: InspectResult<Option<WaitStatus>> @ from_raw_args_and_result(), rusage: Option<rusage> }
     ~ [Process] for [x86_64: 61, aarch64: 260, riscv64: 260],
-  waitid(idtype: idtype_t, id: id_t, infop: *mut siginfo_t, options: c_int) /
-    { idtype: idtype_t, id: id_t, options: c_int } -> c_int + { infop: Option<siginfo_t> }
+  // The raw waitid syscall takes a rusage argument unlike the libc wrapper
+  waitid(idtype: idtype_t, id: id_t, infop: *mut siginfo_t, options: c_int, rusage: *mut rusage) /
+    { idtype: IdType @ from_raw_args(), id: id_t, options: BitFlags<WaitOptions> @ from_raw_args() } -> c_int
+    + { infop: InspectResult<Option<SignalInfo>> @ from_raw_args_and_result(), rusage: Option<rusage> }
     ~ [Process] for [x86_64: 247, aarch64: 95, riscv64: 95],
   // waitpid
   write(fd: RawFd, buf: *const c_void, count: size_t) / { fd: RawFd, buf: Vec<u8> @ counted_by(raw_args.count) } -> ssize_t
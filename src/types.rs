@@ -2,13 +2,104 @@
 
 #![allow(non_camel_case_types)]
 
-mod dst;
+/// Define an enum for a set of integer constants, with an `Unknown` variant for unrecognized values.
+macro_rules! int_enum {
+  (
+    $(#[$attr:meta])*
+    pub enum $name:ident: $repr:ty {
+      $($(#[$vattr:meta])* $variant:ident = $value:expr),* $(,)?
+    }
+  ) => {
+    $(#[$attr])*
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum $name {
+      $($(#[$vattr])* $variant,)*
+      Unknown($repr),
+    }
+
+    impl From<$repr> for $name {
+      fn from(value: $repr) -> Self {
+        match value {
+          $(v if v == $value => Self::$variant,)*
+          v => Self::Unknown(v),
+        }
+      }
+    }
+
+    impl From<$name> for $repr {
+      fn from(value: $name) -> Self {
+        match value {
+          $($name::$variant => $value,)*
+          $name::Unknown(v) => v,
+        }
+      }
+    }
+  };
+}
 
+mod aio;
+mod bitmask;
+mod bpf;
+mod bpf_prog;
+mod capability;
+mod clone;
+mod dirent;
+mod dst;
+mod extensible;
+mod fcntl;
+mod futex;
+mod io_uring;
+mod ipc;
+mod keyctl;
+mod landlock;
+mod mempolicy;
+mod mount;
+mod perf;
+mod prctl;
+mod ptrace;
+mod seccomp;
+mod select;
+mod siginfo;
+mod signal;
+mod socket;
+mod syslog;
+mod wait;
+mod xattr;
+
+pub use aio::*;
+pub use bitmask::*;
+pub use bpf::*;
+pub use bpf_prog::*;
+pub use capability::*;
+pub use clone::*;
+pub use dirent::*;
 pub use dst::*;
+pub use extensible::*;
+pub use fcntl::*;
+pub use futex::*;
+pub use io_uring::*;
+pub use ipc::*;
+pub use keyctl::*;
+pub use landlock::*;
+pub use mempolicy::*;
+pub use mount::*;
+pub use perf::*;
+pub use prctl::*;
+pub use ptrace::*;
+pub use seccomp::*;
+pub use select::*;
+pub use siginfo::*;
+pub use signal::*;
+pub use socket::*;
+pub use syslog::*;
+pub use wait::*;
+pub use xattr::*;
 
 use std::ffi::c_void;
 
-use nix::libc::{c_char, c_int, c_long, c_uint, c_ulong, c_ushort, ino64_t, off64_t, off_t, sigset_t, size_t};
+use nix::libc::{
+  c_char, c_int, c_long, c_uint, c_ulong, c_ushort, ino64_t, ipc_perm, off64_t, off_t, pid_t, sigset_t, size_t, time_t,
+};
 
 pub type key_serial_t = i32; // https://github.com/Distrotech/keyutils/blob/9d52b8ab86931fb5a66fa5b567ea01875f31016e/keyutils.h#L22
 
@@ -174,6 +265,15 @@ pub struct landlock_path_beneath_attr {
 #[repr(C)]
 pub struct landlock_ruleset_attr {
   pub handled_access_fs: u64,
+  pub handled_access_net: u64,
+  pub scoped: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[repr(C)]
+pub struct landlock_net_port_attr {
+  pub allowed_access: u64,
+  pub port: u64,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -204,6 +304,15 @@ pub struct riscv_hwprobe {
   pub value: u64,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+#[repr(C)]
+pub struct mount_attr {
+  pub attr_set: u64,
+  pub attr_clr: u64,
+  pub propagation: u64,
+  pub userns_fd: u64,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 #[repr(C)]
 pub struct mnt_id_req {
@@ -238,3 +347,93 @@ pub struct cachestat {
   pub nr_evicted: u64,
   pub nr_recently_evicted: u64,
 }
+
+#[derive(Debug, Clone, PartialEq)]
+#[repr(C)]
+pub struct prctl_mm_map {
+  pub start_code: u64,
+  pub end_code: u64,
+  pub start_data: u64,
+  pub end_data: u64,
+  pub start_brk: u64,
+  pub brk: u64,
+  pub start_stack: u64,
+  pub arg_start: u64,
+  pub arg_end: u64,
+  pub env_start: u64,
+  pub env_end: u64,
+  pub auxv: *mut u64,
+  pub auxv_size: u32,
+  pub exe_fd: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct bpf_insn {
+  pub code: u8,
+  /// `dst_reg:4` and `src_reg:4`
+  pub regs: u8,
+  pub off: i16,
+  pub imm: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[repr(C)]
+pub struct f_owner_ex {
+  /// F_OWNER_TID, F_OWNER_PID or F_OWNER_PGRP
+  pub type_: c_int,
+  pub pid: pid_t,
+}
+
+/// The 64-bit `struct semid64_ds` of the kernel, libc only defines it for glibc.
+#[derive(Debug, Clone, PartialEq)]
+#[repr(C)]
+pub struct semid_ds {
+  pub sem_perm: ipc_perm,
+  pub sem_otime: time_t,
+  #[cfg(target_arch = "x86_64")]
+  pub __unused1: c_ulong,
+  pub sem_ctime: time_t,
+  #[cfg(target_arch = "x86_64")]
+  pub __unused2: c_ulong,
+  pub sem_nsems: c_ulong,
+  pub __unused3: c_ulong,
+  pub __unused4: c_ulong,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[repr(C)]
+pub struct seminfo {
+  pub semmap: c_int,
+  pub semmni: c_int,
+  pub semmns: c_int,
+  pub semmnu: c_int,
+  pub semmsl: c_int,
+  pub semopm: c_int,
+  pub semume: c_int,
+  pub semusz: c_int,
+  pub semvmx: c_int,
+  pub semaem: c_int,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[repr(C)]
+pub struct shminfo {
+  pub shmmax: c_ulong,
+  pub shmmin: c_ulong,
+  pub shmmni: c_ulong,
+  pub shmseg: c_ulong,
+  pub shmall: c_ulong,
+  pub __unused: [c_ulong; 4],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[repr(C)]
+pub struct shm_info {
+  pub used_ids: c_int,
+  pub shm_tot: c_ulong,
+  pub shm_rss: c_ulong,
+  pub shm_swp: c_ulong,
+  pub swap_attempts: c_ulong,
+  pub swap_successes: c_ulong,
+}
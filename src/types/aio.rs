@@ -0,0 +1,174 @@
+use std::os::fd::RawFd;
+
+use nix::libc::{iocb, iovec};
+
+use crate::{
+  read_bytes, AddressType, InspectCountedFromPid, InspectFromPid, InspectFromRawArgs, InspectResult,
+  InspectResultFromRawArgs, IoGeteventsRawArgs, IoPgeteventsRawArgs, IoSubmitRawArgs, Pid, READ_SIZE_MAX, UIO_MAXIOV,
+};
+
+use super::io_event;
+
+/// IOCB_FLAG_RESFD, notify the eventfd in `aio_resfd` on completion.
+const IOCB_FLAG_RESFD: u32 = 1 << 0;
+
+int_enum! {
+  /// The `aio_lio_opcode` of `struct iocb`
+  pub enum IocbCmd: u16 {
+    Pread = 0,
+    Pwrite = 1,
+    Fsync = 2,
+    Fdsync = 3,
+    Poll = 5,
+    Noop = 6,
+    Preadv = 7,
+    Pwritev = 8,
+  }
+}
+
+/// The data carried by an AIO request, depending on its opcode.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum AioBuffer {
+  /// IOCB_CMD_PREAD, the buffer is filled on completion.
+  Read { buf: AddressType, nbytes: u64 },
+  /// IOCB_CMD_PWRITE, `data` is truncated to 1 MiB.
+  Write { nbytes: u64, data: InspectResult<Vec<u8>> },
+  /// IOCB_CMD_PREADV and IOCB_CMD_PWRITEV, the kernel rejects more than UIO_MAXIOV (1024) iovecs.
+  Vectored { iov: InspectResult<Vec<iovec>> },
+  /// IOCB_CMD_POLL, `events` is the poll event mask.
+  Poll { events: u64 },
+  /// The opcode doesn't use the buffer.
+  None,
+}
+
+/// A decoded `struct iocb` submitted by io_submit.
+///
+/// The completion of this request is the [`io_event`] for which [`AioRequest::matches`] returns true.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AioRequest {
+  /// The address of the `iocb` in the tracee, reported back in `io_event.obj`
+  pub iocb: AddressType,
+  /// `aio_data`, reported back in `io_event.data`
+  pub data: u64,
+  pub opcode: IocbCmd,
+  /// RWF_* flags
+  pub rw_flags: i32,
+  pub reqprio: i16,
+  pub fd: RawFd,
+  pub buffer: AioBuffer,
+  pub offset: i64,
+  /// IOCB_FLAG_* flags
+  pub flags: u32,
+  /// The eventfd to notify on completion if IOCB_FLAG_RESFD is set
+  pub resfd: Option<RawFd>,
+}
+
+impl AioRequest {
+  /// Decode a `struct iocb` at `address`, the write buffer and the iovecs are read from the tracee.
+  pub fn from_iocb(pid: Pid, address: AddressType, iocb: &iocb) -> Self {
+    let opcode = IocbCmd::from(iocb.aio_lio_opcode);
+    let buf = iocb.aio_buf as AddressType;
+    Self {
+      iocb: address,
+      data: iocb.aio_data,
+      opcode,
+      rw_flags: iocb.aio_rw_flags,
+      reqprio: iocb.aio_reqprio,
+      fd: iocb.aio_fildes as RawFd,
+      buffer: match opcode {
+        IocbCmd::Pread => AioBuffer::Read {
+          buf,
+          nbytes: iocb.aio_nbytes,
+        },
+        IocbCmd::Pwrite => AioBuffer::Write {
+          nbytes: iocb.aio_nbytes,
+          data: read_bytes(pid, buf, (iocb.aio_nbytes as usize).min(READ_SIZE_MAX)),
+        },
+        IocbCmd::Preadv | IocbCmd::Pwritev => AioBuffer::Vectored {
+          iov: InspectCountedFromPid::inspect_from(pid, buf, (iocb.aio_nbytes as usize).min(UIO_MAXIOV)),
+        },
+        IocbCmd::Poll => AioBuffer::Poll { events: iocb.aio_buf },
+        _ => AioBuffer::None,
+      },
+      offset: iocb.aio_offset,
+      flags: iocb.aio_flags,
+      resfd: (iocb.aio_flags & IOCB_FLAG_RESFD != 0).then_some(iocb.aio_resfd as RawFd),
+    }
+  }
+
+  /// Whether the completion event is for this request.
+  pub fn matches(&self, event: &io_event) -> bool {
+    event.obj == self.iocb as u64 && event.data == self.data
+  }
+}
+
+impl io_event {
+  /// The address of the `iocb` in the tracee that this event completes.
+  pub fn iocb(&self) -> AddressType {
+    self.obj as AddressType
+  }
+}
+
+/// A completion event returned by io_getevents or io_pgetevents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AioCompletion {
+  pub event: io_event,
+  /// The completed request, read back from the `iocb` at `event.obj`.
+  /// It is `None` if that `iocb` has already been reused for another request.
+  pub request: InspectResult<Option<AioRequest>>,
+}
+
+impl AioCompletion {
+  /// Link the event back to its request, the `iocb` is read from the tracee.
+  pub fn from_event(pid: Pid, event: io_event) -> Self {
+    let address = event.iocb();
+    let decode = |iocb: iocb| Some(AioRequest::from_iocb(pid, address, &iocb)).filter(|r| r.matches(&event));
+    let request = InspectResult::<iocb>::inspect_from(pid, address)
+      .map(decode)
+      .map_err(|e| e.map_ptrace_failure(decode));
+    Self { event, request }
+  }
+}
+
+fn inspect_completions(pid: Pid, events: AddressType, syscall_result: isize) -> InspectResult<Vec<AioCompletion>> {
+  let events = InspectResult::<Vec<io_event>>::inspect_from(pid, events, syscall_result.max(0) as usize)
+    .map_err(|e| e.map_ptrace_failure(|_| vec![]))?;
+  Ok(
+    events
+      .into_iter()
+      .map(|event| AioCompletion::from_event(pid, event))
+      .collect(),
+  )
+}
+
+impl InspectResultFromRawArgs<IoGeteventsRawArgs> for InspectResult<Vec<AioCompletion>> {
+  fn inspect_from(pid: Pid, raw_args: &IoGeteventsRawArgs, syscall_result: isize) -> Self {
+    inspect_completions(pid, raw_args.events as AddressType, syscall_result)
+  }
+}
+
+impl InspectResultFromRawArgs<IoPgeteventsRawArgs> for InspectResult<Vec<AioCompletion>> {
+  fn inspect_from(pid: Pid, raw_args: &IoPgeteventsRawArgs, syscall_result: isize) -> Self {
+    inspect_completions(pid, raw_args.events as AddressType, syscall_result)
+  }
+}
+
+/// The requests pointed to by the `iocbpp` array of io_submit.
+impl InspectFromRawArgs<IoSubmitRawArgs> for InspectResult<Vec<InspectResult<AioRequest>>> {
+  fn inspect_from(pid: Pid, raw_args: &IoSubmitRawArgs) -> Self {
+    let iocbs =
+      InspectResult::<Vec<AddressType>>::inspect_from(pid, raw_args.iocbs as AddressType, raw_args.nr.max(0) as usize)
+        .map_err(|e| e.map_ptrace_failure(|_| vec![]))?;
+    Ok(
+      iocbs
+        .into_iter()
+        .map(|address| {
+          InspectResult::<iocb>::inspect_from(pid, address)
+            .map(|iocb| AioRequest::from_iocb(pid, address, &iocb))
+            .map_err(|e| e.map_ptrace_failure(|iocb| AioRequest::from_iocb(pid, address, &iocb)))
+        })
+        .collect(),
+    )
+  }
+}
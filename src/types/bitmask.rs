@@ -0,0 +1,116 @@
+use std::fmt;
+
+use crate::{read_bytes, AddressType, InspectCountedFromPid, InspectResult, Pid};
+
+/// The kernel limits nodemasks to a page of bits and NR_CPUS is at most 8192,
+/// larger masks are capped to avoid reading huge buffers.
+const BITMASK_BITS_MAX: usize = 1 << 16;
+
+/// Define a set of indices that is passed to the kernel as a bitmap of longs.
+macro_rules! bitmask {
+  ($(#[$attr:meta])* pub struct $name:ident;) => {
+    $(#[$attr])*
+    #[derive(Debug, Clone, PartialEq, Eq, Default)]
+    pub struct $name(pub Vec<usize>);
+
+    impl $name {
+      /// Decode the first `nbits` bits of a bitmap.
+      pub fn from_bitmap(bitmap: &[u8], nbits: usize) -> Self {
+        Self(
+          (0..nbits.min(bitmap.len() * 8))
+            .filter(|i| bitmap[i / 8] & (1 << (i % 8)) != 0)
+            .collect(),
+        )
+      }
+
+      /// Encode the set as a bitmap of whole longs, the way the kernel expects it.
+      pub fn to_bitmap(&self) -> Vec<u8> {
+        let nbits = self.0.iter().max().map_or(0, |&max| max + 1);
+        let mut bitmap = vec![0u8; nbits.div_ceil(64) * 8];
+        for &i in &self.0 {
+          bitmap[i / 8] |= 1 << (i % 8);
+        }
+        bitmap
+      }
+
+      pub fn contains(&self, index: usize) -> bool {
+        self.0.binary_search(&index).is_ok()
+      }
+    }
+
+    impl From<Vec<usize>> for $name {
+      fn from(mut indices: Vec<usize>) -> Self {
+        indices.sort_unstable();
+        indices.dedup();
+        Self(indices)
+      }
+    }
+
+    impl FromIterator<usize> for $name {
+      fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        iter.into_iter().collect::<Vec<_>>().into()
+      }
+    }
+
+    /// Render the set as a list of ranges like `0-3,8`, the format of cpuset and /sys.
+    impl fmt::Display for $name {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_ranges(&self.0, f)
+      }
+    }
+
+    /// A bitmap of `nbits` bits.
+    impl InspectCountedFromPid for InspectResult<$name> {
+      fn inspect_from(pid: Pid, address: AddressType, nbits: usize) -> Self {
+        let nbits = nbits.min(BITMASK_BITS_MAX);
+        read_bytes(pid, address, nbits.div_ceil(64) * 8)
+          .map(|bitmap| $name::from_bitmap(&bitmap, nbits))
+          .map_err(|e| e.map_ptrace_failure(|bitmap| $name::from_bitmap(&bitmap, nbits)))
+      }
+    }
+
+    /// A bitmap of `nbits` bits, `None` if the pointer is NULL.
+    impl InspectCountedFromPid for InspectResult<Option<$name>> {
+      fn inspect_from(pid: Pid, address: AddressType, nbits: usize) -> Self {
+        if address.is_null() {
+          return Ok(None);
+        }
+        InspectResult::<$name>::inspect_from(pid, address, nbits)
+          .map(Some)
+          .map_err(|e| e.map_ptrace_failure(Some))
+      }
+    }
+  };
+}
+
+fn fmt_ranges(indices: &[usize], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+  let mut rest = indices;
+  let mut first = true;
+  while let Some(&start) = rest.first() {
+    let len = rest
+      .iter()
+      .enumerate()
+      .take_while(|&(i, &index)| index == start + i)
+      .count();
+    if !first {
+      f.write_str(",")?;
+    }
+    first = false;
+    match len {
+      1 => write!(f, "{start}")?,
+      _ => write!(f, "{start}-{}", start + len - 1)?,
+    }
+    rest = &rest[len..];
+  }
+  Ok(())
+}
+
+bitmask! {
+  /// The CPUs in a CPU affinity mask, in ascending order.
+  pub struct CpuSet;
+}
+
+bitmask! {
+  /// The NUMA nodes in a nodemask, in ascending order.
+  pub struct NodeMask;
+}
@@ -0,0 +1,454 @@
+use std::{ffi::CString, os::fd::RawFd, path::PathBuf};
+
+use crate::{
+  read_bytes, AddressType, BpfRawArgs, InspectError, InspectFromPid, InspectFromRawArgs, InspectResult,
+  InspectResultFromRawArgs, Pid, StructFields,
+};
+
+use super::{read_ebpf_program, read_extensible, EbpfInsn, Extensible, EXTENSIBLE_SIZE_MAX};
+
+/// https://elixir.bootlin.com/linux/v6.11/source/include/uapi/linux/bpf.h#L1433
+const BPF_OBJ_NAME_LEN: usize = 16;
+
+int_enum! {
+  /// The `cmd` argument of bpf
+  pub enum BpfCmd: i32 {
+    MapCreate = 0,
+    MapLookupElem = 1,
+    MapUpdateElem = 2,
+    MapDeleteElem = 3,
+    MapGetNextKey = 4,
+    ProgLoad = 5,
+    ObjPin = 6,
+    ObjGet = 7,
+    ProgAttach = 8,
+    ProgDetach = 9,
+    ProgTestRun = 10,
+    ProgGetNextId = 11,
+    MapGetNextId = 12,
+    ProgGetFdById = 13,
+    MapGetFdById = 14,
+    ObjGetInfoByFd = 15,
+    ProgQuery = 16,
+    RawTracepointOpen = 17,
+    BtfLoad = 18,
+    BtfGetFdById = 19,
+    TaskFdQuery = 20,
+    MapLookupAndDeleteElem = 21,
+    MapFreeze = 22,
+    BtfGetNextId = 23,
+    MapLookupBatch = 24,
+    MapLookupAndDeleteBatch = 25,
+    MapUpdateBatch = 26,
+    MapDeleteBatch = 27,
+    LinkCreate = 28,
+    LinkUpdate = 29,
+    LinkGetFdById = 30,
+    LinkGetNextId = 31,
+    EnableStats = 32,
+    IterCreate = 33,
+    LinkDetach = 34,
+    ProgBindMap = 35,
+    TokenCreate = 36,
+  }
+}
+
+int_enum! {
+  /// `enum bpf_map_type`
+  pub enum BpfMapType: u32 {
+    Unspec = 0,
+    Hash = 1,
+    Array = 2,
+    ProgArray = 3,
+    PerfEventArray = 4,
+    PercpuHash = 5,
+    PercpuArray = 6,
+    StackTrace = 7,
+    CgroupArray = 8,
+    LruHash = 9,
+    LruPercpuHash = 10,
+    LpmTrie = 11,
+    ArrayOfMaps = 12,
+    HashOfMaps = 13,
+    Devmap = 14,
+    Sockmap = 15,
+    Cpumap = 16,
+    Xskmap = 17,
+    Sockhash = 18,
+    CgroupStorage = 19,
+    ReuseportSockarray = 20,
+    PercpuCgroupStorage = 21,
+    Queue = 22,
+    Stack = 23,
+    SkStorage = 24,
+    DevmapHash = 25,
+    StructOps = 26,
+    Ringbuf = 27,
+    InodeStorage = 28,
+    TaskStorage = 29,
+    BloomFilter = 30,
+    UserRingbuf = 31,
+    CgrpStorage = 32,
+    Arena = 33,
+  }
+}
+
+impl BpfMapType {
+  /// Whether the values of this map are replicated for every possible CPU.
+  pub fn is_percpu(self) -> bool {
+    matches!(self, Self::PercpuHash | Self::PercpuArray | Self::LruPercpuHash | Self::PercpuCgroupStorage)
+  }
+}
+
+int_enum! {
+  /// `enum bpf_prog_type`
+  pub enum BpfProgType: u32 {
+    Unspec = 0,
+    SocketFilter = 1,
+    Kprobe = 2,
+    SchedCls = 3,
+    SchedAct = 4,
+    Tracepoint = 5,
+    Xdp = 6,
+    PerfEvent = 7,
+    CgroupSkb = 8,
+    CgroupSock = 9,
+    LwtIn = 10,
+    LwtOut = 11,
+    LwtXmit = 12,
+    SockOps = 13,
+    SkSkb = 14,
+    CgroupDevice = 15,
+    SkMsg = 16,
+    RawTracepoint = 17,
+    CgroupSockAddr = 18,
+    LwtSeg6local = 19,
+    LircMode2 = 20,
+    SkReuseport = 21,
+    FlowDissector = 22,
+    CgroupSysctl = 23,
+    RawTracepointWritable = 24,
+    CgroupSockopt = 25,
+    Tracing = 26,
+    StructOps = 27,
+    Ext = 28,
+    Lsm = 29,
+    SkLookup = 30,
+    Syscall = 31,
+    Netfilter = 32,
+  }
+}
+
+/// The member of `union bpf_attr` used by the command.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum BpfAttr {
+  /// BPF_MAP_CREATE
+  MapCreate {
+    map_type: BpfMapType,
+    key_size: u32,
+    value_size: u32,
+    max_entries: u32,
+    map_flags: u32,
+    inner_map_fd: RawFd,
+    numa_node: u32,
+    map_name: CString,
+    map_ifindex: u32,
+    btf_fd: RawFd,
+    map_extra: u64,
+  },
+  /// BPF_MAP_LOOKUP_ELEM, BPF_MAP_UPDATE_ELEM, BPF_MAP_DELETE_ELEM, BPF_MAP_LOOKUP_AND_DELETE_ELEM and
+  /// BPF_MAP_GET_NEXT_KEY
+  ///
+  /// `key` and `value` are only read if the map's key and value sizes are known and the command reads them.
+  /// The value looked up by the kernel is available in [`BpfResult`].
+  MapElem {
+    map_fd: RawFd,
+    key_addr: AddressType,
+    key: Option<InspectResult<Vec<u8>>>,
+    /// `value` or `next_key`
+    value_addr: AddressType,
+    value: Option<InspectResult<Vec<u8>>>,
+    flags: u64,
+  },
+  /// BPF_PROG_LOAD
+  ProgLoad {
+    prog_type: BpfProgType,
+    insn_cnt: u32,
+    insns: InspectResult<Vec<EbpfInsn>>,
+    license: InspectResult<CString>,
+    log_level: u32,
+    log_size: u32,
+    log_buf: AddressType,
+    kern_version: u32,
+    prog_flags: u32,
+    prog_name: CString,
+    prog_ifindex: u32,
+    expected_attach_type: u32,
+  },
+  /// BPF_OBJ_PIN and BPF_OBJ_GET
+  Obj {
+    pathname: InspectResult<PathBuf>,
+    bpf_fd: RawFd,
+    file_flags: u32,
+    path_fd: RawFd,
+  },
+  /// BPF_PROG_ATTACH and BPF_PROG_DETACH
+  ProgAttach {
+    target_fd: RawFd,
+    attach_bpf_fd: RawFd,
+    attach_type: u32,
+    attach_flags: u32,
+    replace_bpf_fd: RawFd,
+  },
+  /// BPF_LINK_CREATE
+  LinkCreate {
+    prog_fd: RawFd,
+    target_fd: RawFd,
+    attach_type: u32,
+    flags: u32,
+  },
+  /// The raw bytes of `attr` for commands that are not decoded yet.
+  Other(Vec<u8>),
+}
+
+/// The values retrieved by bpf through the pointers in `attr`.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum BpfResult {
+  /// BPF_MAP_LOOKUP_ELEM and BPF_MAP_LOOKUP_AND_DELETE_ELEM
+  MapValue(Vec<u8>),
+  /// BPF_MAP_GET_NEXT_KEY
+  MapNextKey(Vec<u8>),
+  /// The map's key or value size is unknown so the retrieved value is not read.
+  SizeUnknown,
+  /// The command returns its value, if any, as the syscall result.
+  InSyscallResult,
+}
+
+/// The key and value sizes of a bpf map, retrieved from `/proc/<pid>/fdinfo/<map_fd>`.
+///
+/// The value size is `None` for per-cpu maps because the user space value holds one element per possible CPU.
+fn map_sizes(pid: Pid, map_fd: RawFd) -> Option<(usize, Option<usize>)> {
+  let fdinfo = std::fs::read_to_string(format!("/proc/{pid}/fdinfo/{map_fd}")).ok()?;
+  let field = |name: &str| {
+    fdinfo
+      .lines()
+      .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+      .and_then(|value| value.trim().parse::<u32>().ok())
+  };
+  let map_type = BpfMapType::from(field("map_type")?);
+  let key_size = field("key_size")? as usize;
+  let value_size = field("value_size")? as usize;
+  Some((key_size, (!map_type.is_percpu()).then_some(value_size)))
+}
+
+impl BpfCmd {
+  /// The size of the fields of `union bpf_attr` decoded for the command, `None` if it is not decoded.
+  fn attr_size(self) -> Option<usize> {
+    match self {
+      Self::MapLookupElem
+      | Self::MapUpdateElem
+      | Self::MapDeleteElem
+      | Self::MapLookupAndDeleteElem
+      | Self::MapGetNextKey => Some(32),
+      Self::MapCreate | Self::ProgLoad => Some(72),
+      Self::ObjPin | Self::ObjGet | Self::ProgAttach | Self::ProgDetach => Some(20),
+      Self::LinkCreate => Some(16),
+      _ => None,
+    }
+  }
+}
+
+/// Read the `size` bytes of `attr`, the bytes of the commands that are not decoded are all known.
+fn read_attr<T: Clone + PartialEq>(
+  pid: Pid,
+  raw_args: &BpfRawArgs,
+  decode: impl Fn(&[u8]) -> T,
+) -> InspectResult<Extensible<T>> {
+  let size = raw_args.size as usize;
+  let known_size = BpfCmd::from(raw_args.cmd)
+    .attr_size()
+    .unwrap_or(size.min(EXTENSIBLE_SIZE_MAX));
+  read_extensible(pid, raw_args.attr as AddressType, size, known_size, decode)
+}
+
+fn read_map_data(pid: Pid, address: AddressType, size: Option<usize>) -> Option<InspectResult<Vec<u8>>> {
+  match size {
+    Some(size) if !address.is_null() => Some(read_bytes(pid, address, size)),
+    _ => None,
+  }
+}
+
+impl InspectFromRawArgs<BpfRawArgs> for BpfCmd {
+  fn inspect_from(_pid: Pid, raw_args: &BpfRawArgs) -> Self {
+    raw_args.cmd.into()
+  }
+}
+
+impl BpfAttr {
+  /// Decode the `attr` of the commands whose attributes don't point to tracee memory.
+  ///
+  /// The other commands are decoded as [`BpfAttr::Other`].
+  pub fn from_bytes(cmd: BpfCmd, attr: Vec<u8>) -> Self {
+    let fields = StructFields(&attr);
+    match cmd {
+      BpfCmd::MapCreate => Self::MapCreate {
+        map_type: fields.u32(0).into(),
+        key_size: fields.u32(4),
+        value_size: fields.u32(8),
+        max_entries: fields.u32(12),
+        map_flags: fields.u32(16),
+        inner_map_fd: fields.i32(20),
+        numa_node: fields.u32(24),
+        map_name: fields.cstring::<BPF_OBJ_NAME_LEN>(28),
+        map_ifindex: fields.u32(44),
+        btf_fd: fields.i32(48),
+        map_extra: fields.u64(64),
+      },
+      BpfCmd::ProgAttach | BpfCmd::ProgDetach => Self::ProgAttach {
+        target_fd: fields.i32(0),
+        attach_bpf_fd: fields.i32(4),
+        attach_type: fields.u32(8),
+        attach_flags: fields.u32(12),
+        replace_bpf_fd: fields.i32(16),
+      },
+      BpfCmd::LinkCreate => Self::LinkCreate {
+        prog_fd: fields.i32(0),
+        target_fd: fields.i32(4),
+        attach_type: fields.u32(8),
+        flags: fields.u32(12),
+      },
+      _ => Self::Other(attr),
+    }
+  }
+
+  /// Decode `attr`, reading the memory it points to.
+  fn inspect_from_bytes(pid: Pid, cmd: BpfCmd, attr: &[u8]) -> Self {
+    let fields = StructFields(attr);
+    match cmd {
+      BpfCmd::MapLookupElem
+      | BpfCmd::MapUpdateElem
+      | BpfCmd::MapDeleteElem
+      | BpfCmd::MapLookupAndDeleteElem
+      | BpfCmd::MapGetNextKey => {
+        let map_fd = fields.i32(0);
+        let (key_size, value_size) = match map_sizes(pid, map_fd) {
+          Some((key_size, value_size)) => (Some(key_size), value_size),
+          None => (None, None),
+        };
+        let key_addr = fields.ptr(8);
+        let value_addr = fields.ptr(16);
+        BpfAttr::MapElem {
+          map_fd,
+          key_addr,
+          key: read_map_data(pid, key_addr, key_size),
+          value_addr,
+          value: match cmd {
+            BpfCmd::MapUpdateElem => read_map_data(pid, value_addr, value_size),
+            _ => None,
+          },
+          flags: fields.u64(24),
+        }
+      }
+      BpfCmd::ProgLoad => BpfAttr::ProgLoad {
+        prog_type: fields.u32(0).into(),
+        insn_cnt: fields.u32(4),
+        insns: read_ebpf_program(pid, fields.ptr(8), fields.u32(4) as usize),
+        license: InspectFromPid::inspect_from(pid, fields.ptr(16)),
+        log_level: fields.u32(24),
+        log_size: fields.u32(28),
+        log_buf: fields.ptr(32),
+        kern_version: fields.u32(40),
+        prog_flags: fields.u32(44),
+        prog_name: fields.cstring::<BPF_OBJ_NAME_LEN>(48),
+        prog_ifindex: fields.u32(64),
+        expected_attach_type: fields.u32(68),
+      },
+      BpfCmd::ObjPin | BpfCmd::ObjGet => BpfAttr::Obj {
+        pathname: InspectFromPid::inspect_from(pid, fields.ptr(0)),
+        bpf_fd: fields.i32(8),
+        file_flags: fields.u32(12),
+        path_fd: fields.i32(16),
+      },
+      _ => BpfAttr::from_bytes(cmd, attr.to_vec()),
+    }
+  }
+}
+
+impl InspectFromRawArgs<BpfRawArgs> for InspectResult<Extensible<BpfAttr>> {
+  fn inspect_from(pid: Pid, raw_args: &BpfRawArgs) -> Self {
+    let cmd = BpfCmd::from(raw_args.cmd);
+    read_attr(pid, raw_args, |attr| BpfAttr::inspect_from_bytes(pid, cmd, attr))
+  }
+}
+
+impl InspectResultFromRawArgs<BpfRawArgs> for InspectResult<BpfResult> {
+  fn inspect_from(pid: Pid, raw_args: &BpfRawArgs, _syscall_result: isize) -> Self {
+    let cmd = BpfCmd::from(raw_args.cmd);
+    let (is_next_key, map_result): (bool, fn(Vec<u8>) -> BpfResult) = match cmd {
+      BpfCmd::MapLookupElem | BpfCmd::MapLookupAndDeleteElem => (false, BpfResult::MapValue),
+      BpfCmd::MapGetNextKey => (true, BpfResult::MapNextKey),
+      _ => return Ok(BpfResult::InSyscallResult),
+    };
+    let attr =
+      read_attr(pid, raw_args, <[u8]>::to_vec).map_err(|_| InspectError::DependencyInspectFailure { field: "attr" })?;
+    let fields = StructFields(&attr.value);
+    let size = map_sizes(pid, fields.i32(0)).and_then(|(key_size, value_size)| match is_next_key {
+      true => Some(key_size),
+      false => value_size,
+    });
+    match read_map_data(pid, fields.ptr(16), size) {
+      Some(data) => data.map(map_result).map_err(|e| e.map_ptrace_failure(map_result)),
+      None => Ok(BpfResult::SizeUnknown),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn attr(cmd: BpfCmd, attr: &[u8]) -> Extensible<BpfAttr> {
+    let raw_args = BpfRawArgs {
+      cmd: cmd.into(),
+      attr: attr.as_ptr() as AddressType,
+      size: attr.len() as u32,
+    };
+    InspectResult::<Extensible<BpfAttr>>::inspect_from(Pid::this(), &raw_args).unwrap()
+  }
+
+  #[test]
+  fn test_attr_trailing_bytes_are_kept() {
+    let mut bytes = vec![0u8; 24];
+    bytes[0..4].copy_from_slice(&3i32.to_ne_bytes());
+    bytes[4..8].copy_from_slice(&4i32.to_ne_bytes());
+    bytes[16..24].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(
+      attr(BpfCmd::LinkCreate, &bytes),
+      Extensible {
+        size: 24,
+        value: BpfAttr::LinkCreate {
+          prog_fd: 3,
+          target_fd: 4,
+          attach_type: 0,
+          flags: 0,
+        },
+        trailing: vec![1, 2, 3, 4, 5, 6, 7, 8],
+      }
+    );
+  }
+
+  #[test]
+  fn test_undecoded_attr_has_no_trailing_bytes() {
+    let bytes: Vec<u8> = (0..40).collect();
+    assert_eq!(
+      attr(BpfCmd::EnableStats, &bytes),
+      Extensible {
+        size: 40,
+        value: BpfAttr::Other(bytes),
+        trailing: vec![],
+      }
+    );
+  }
+}
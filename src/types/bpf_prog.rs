@@ -0,0 +1,903 @@
+use std::fmt::{self, Display, Formatter};
+
+use nix::libc::{self, sock_filter};
+
+use crate::{read_bytes, read_sock_fprog, syscall_name, AddressType, InspectResult, Pid};
+
+use super::bpf_insn;
+
+/// BPF_COMPLEXITY_LIMIT_INSNS, the maximum number of instructions the verifier accepts in a program
+const BPF_COMPLEXITY_LIMIT_INSNS: usize = 1_000_000;
+
+/// The size of a memory access
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BpfSize {
+  /// BPF_B
+  Byte,
+  /// BPF_H
+  Half,
+  /// BPF_W
+  Word,
+  /// BPF_DW, eBPF only
+  DoubleWord,
+}
+
+impl BpfSize {
+  fn from_code(code: u16) -> Self {
+    match code & 0x18 {
+      0x00 => Self::Word,
+      0x08 => Self::Half,
+      0x10 => Self::Byte,
+      _ => Self::DoubleWord,
+    }
+  }
+
+  fn bits(self) -> u32 {
+    match self {
+      Self::Byte => 8,
+      Self::Half => 16,
+      Self::Word => 32,
+      Self::DoubleWord => 64,
+    }
+  }
+}
+
+/// The arithmetic operations of BPF_ALU and BPF_ALU64
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BpfAluOp {
+  Add,
+  Sub,
+  Mul,
+  Div,
+  Or,
+  And,
+  Lsh,
+  Rsh,
+  Neg,
+  Mod,
+  Xor,
+  /// eBPF only
+  Mov,
+  /// eBPF only
+  Arsh,
+}
+
+impl BpfAluOp {
+  fn from_code(code: u16) -> Option<Self> {
+    Some(match code & 0xf0 {
+      0x00 => Self::Add,
+      0x10 => Self::Sub,
+      0x20 => Self::Mul,
+      0x30 => Self::Div,
+      0x40 => Self::Or,
+      0x50 => Self::And,
+      0x60 => Self::Lsh,
+      0x70 => Self::Rsh,
+      0x80 => Self::Neg,
+      0x90 => Self::Mod,
+      0xa0 => Self::Xor,
+      0xb0 => Self::Mov,
+      0xc0 => Self::Arsh,
+      _ => return None,
+    })
+  }
+
+  fn mnemonic(self) -> &'static str {
+    match self {
+      Self::Add => "add",
+      Self::Sub => "sub",
+      Self::Mul => "mul",
+      Self::Div => "div",
+      Self::Or => "or",
+      Self::And => "and",
+      Self::Lsh => "lsh",
+      Self::Rsh => "rsh",
+      Self::Neg => "neg",
+      Self::Mod => "mod",
+      Self::Xor => "xor",
+      Self::Mov => "mov",
+      Self::Arsh => "arsh",
+    }
+  }
+
+  fn operator(self, signed: bool) -> &'static str {
+    match (self, signed) {
+      (Self::Add, _) => "+=",
+      (Self::Sub, _) => "-=",
+      (Self::Mul, _) => "*=",
+      (Self::Div, false) => "/=",
+      (Self::Div, true) => "s/=",
+      (Self::Or, _) => "|=",
+      (Self::And, _) => "&=",
+      (Self::Lsh, _) => "<<=",
+      (Self::Rsh, _) => ">>=",
+      (Self::Neg, _) => "= -",
+      (Self::Mod, false) => "%=",
+      (Self::Mod, true) => "s%=",
+      (Self::Xor, _) => "^=",
+      (Self::Mov, _) => "=",
+      (Self::Arsh, _) => "s>>=",
+    }
+  }
+}
+
+/// The conditions of BPF_JMP and BPF_JMP32
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BpfJmpCond {
+  Eq,
+  Gt,
+  Ge,
+  Set,
+  /// eBPF only
+  Ne,
+  /// eBPF only
+  Sgt,
+  /// eBPF only
+  Sge,
+  /// eBPF only
+  Lt,
+  /// eBPF only
+  Le,
+  /// eBPF only
+  Slt,
+  /// eBPF only
+  Sle,
+}
+
+impl BpfJmpCond {
+  fn from_code(code: u16) -> Option<Self> {
+    Some(match code & 0xf0 {
+      0x10 => Self::Eq,
+      0x20 => Self::Gt,
+      0x30 => Self::Ge,
+      0x40 => Self::Set,
+      0x50 => Self::Ne,
+      0x60 => Self::Sgt,
+      0x70 => Self::Sge,
+      0xa0 => Self::Lt,
+      0xb0 => Self::Le,
+      0xc0 => Self::Slt,
+      0xd0 => Self::Sle,
+      _ => return None,
+    })
+  }
+
+  fn mnemonic(self) -> &'static str {
+    match self {
+      Self::Eq => "jeq",
+      Self::Gt => "jgt",
+      Self::Ge => "jge",
+      Self::Set => "jset",
+      Self::Ne => "jne",
+      Self::Sgt => "jsgt",
+      Self::Sge => "jsge",
+      Self::Lt => "jlt",
+      Self::Le => "jle",
+      Self::Slt => "jslt",
+      Self::Sle => "jsle",
+    }
+  }
+
+  fn operator(self) -> &'static str {
+    match self {
+      Self::Eq => "==",
+      Self::Gt => ">",
+      Self::Ge => ">=",
+      Self::Set => "&",
+      Self::Ne => "!=",
+      Self::Sgt => "s>",
+      Self::Sge => "s>=",
+      Self::Lt => "<",
+      Self::Le => "<=",
+      Self::Slt => "s<",
+      Self::Sle => "s<=",
+    }
+  }
+}
+
+/// The source operand of a classic BPF instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CbpfOperand {
+  /// The constant `k`
+  K(u32),
+  /// The index register
+  X,
+  /// The accumulator, only used by `ret`
+  A,
+}
+
+impl Display for CbpfOperand {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::K(k) => write!(f, "#{k:#x}"),
+      Self::X => f.write_str("x"),
+      Self::A => f.write_str("a"),
+    }
+  }
+}
+
+/// The source of a classic BPF load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CbpfLoadSrc {
+  /// BPF_IMM, the constant `k`
+  Imm(u32),
+  /// BPF_ABS, the packet (or `seccomp_data`) at offset `k`
+  Abs(u32),
+  /// BPF_IND, the packet at offset `x + k`
+  Ind(u32),
+  /// BPF_MEM, the scratch memory slot `k`
+  Mem(u32),
+  /// BPF_LEN, the packet length
+  Len,
+  /// BPF_MSH, the IP header length `4 * ([k] & 0xf)`
+  Msh(u32),
+}
+
+impl Display for CbpfLoadSrc {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Imm(k) => write!(f, "#{k:#x}"),
+      Self::Abs(k) => write!(f, "[{k}]"),
+      Self::Ind(k) => write!(f, "[x + {k}]"),
+      Self::Mem(k) => write!(f, "M[{k}]"),
+      Self::Len => f.write_str("#len"),
+      Self::Msh(k) => write!(f, "4*([{k}]&0xf)"),
+    }
+  }
+}
+
+/// A disassembled classic BPF instruction, as used by seccomp and socket filters.
+///
+/// Jump targets are absolute instruction indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CbpfInsn {
+  /// `a = src`
+  Ld { size: BpfSize, src: CbpfLoadSrc },
+  /// `x = src`
+  Ldx { size: BpfSize, src: CbpfLoadSrc },
+  /// `M[slot] = a`
+  St { slot: u32 },
+  /// `M[slot] = x`
+  Stx { slot: u32 },
+  /// `a = a op src`
+  Alu { op: BpfAluOp, src: CbpfOperand },
+  /// Unconditional jump
+  Ja { target: usize },
+  /// Jump to `jt` if `a cond src` is true, otherwise to `jf`.
+  Jmp {
+    cond: BpfJmpCond,
+    src: CbpfOperand,
+    jt: usize,
+    jf: usize,
+  },
+  /// Return `value` from the filter.
+  Ret { value: CbpfOperand },
+  /// `x = a`
+  Tax,
+  /// `a = x`
+  Txa,
+  /// An instruction that is rejected by the kernel.
+  Invalid(sock_filter),
+}
+
+impl CbpfInsn {
+  /// Decode the instruction at index `pc` of a classic BPF program.
+  pub fn decode(pc: usize, insn: &sock_filter) -> Self {
+    let sock_filter { code, jt, jf, k } = *insn;
+    let src = || match code as u32 & 0x08 {
+      libc::BPF_K => CbpfOperand::K(k),
+      _ => CbpfOperand::X,
+    };
+    let load_src = || {
+      Some(match code as u32 & 0xe0 {
+        libc::BPF_IMM => CbpfLoadSrc::Imm(k),
+        libc::BPF_ABS => CbpfLoadSrc::Abs(k),
+        libc::BPF_IND => CbpfLoadSrc::Ind(k),
+        libc::BPF_MEM => CbpfLoadSrc::Mem(k),
+        libc::BPF_LEN => CbpfLoadSrc::Len,
+        libc::BPF_MSH => CbpfLoadSrc::Msh(k),
+        _ => return None,
+      })
+    };
+    let decoded = match code as u32 & 0x07 {
+      libc::BPF_LD => load_src().map(|src| Self::Ld {
+        size: BpfSize::from_code(code),
+        src,
+      }),
+      libc::BPF_LDX => load_src().map(|src| Self::Ldx {
+        size: BpfSize::from_code(code),
+        src,
+      }),
+      libc::BPF_ST => Some(Self::St { slot: k }),
+      libc::BPF_STX => Some(Self::Stx { slot: k }),
+      libc::BPF_ALU => BpfAluOp::from_code(code)
+        .filter(|op| !matches!(op, BpfAluOp::Mov | BpfAluOp::Arsh))
+        .map(|op| Self::Alu { op, src: src() }),
+      libc::BPF_JMP if code as u32 & 0xf0 == libc::BPF_JA => Some(Self::Ja {
+        target: pc + 1 + k as usize,
+      }),
+      libc::BPF_JMP => BpfJmpCond::from_code(code)
+        .filter(|cond| matches!(cond, BpfJmpCond::Eq | BpfJmpCond::Gt | BpfJmpCond::Ge | BpfJmpCond::Set))
+        .map(|cond| Self::Jmp {
+          cond,
+          src: src(),
+          jt: pc + 1 + jt as usize,
+          jf: pc + 1 + jf as usize,
+        }),
+      libc::BPF_RET => match code as u32 & 0x18 {
+        libc::BPF_K => Some(Self::Ret {
+          value: CbpfOperand::K(k),
+        }),
+        // BPF_A
+        0x10 => Some(Self::Ret { value: CbpfOperand::A }),
+        _ => None,
+      },
+      libc::BPF_MISC => match code as u32 & 0xf8 {
+        // BPF_TAX
+        0x00 => Some(Self::Tax),
+        // BPF_TXA
+        0x80 => Some(Self::Txa),
+        _ => None,
+      },
+      _ => None,
+    };
+    decoded.unwrap_or(Self::Invalid(*insn))
+  }
+
+  fn load_mnemonic(size: BpfSize, src: CbpfLoadSrc) -> &'static str {
+    match (size, src) {
+      (_, CbpfLoadSrc::Imm(_) | CbpfLoadSrc::Mem(_) | CbpfLoadSrc::Len | CbpfLoadSrc::Msh(_)) => "",
+      (BpfSize::Half, _) => "h",
+      (BpfSize::Byte, _) => "b",
+      _ => "",
+    }
+  }
+}
+
+impl Display for CbpfInsn {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match *self {
+      Self::Ld { size, src } => write!(f, "ld{} {src}", Self::load_mnemonic(size, src)),
+      Self::Ldx { size, src } => write!(f, "ldx{} {src}", Self::load_mnemonic(size, src)),
+      Self::St { slot } => write!(f, "st M[{slot}]"),
+      Self::Stx { slot } => write!(f, "stx M[{slot}]"),
+      Self::Alu { op: BpfAluOp::Neg, .. } => f.write_str("neg"),
+      Self::Alu { op, src } => write!(f, "{} {src}", op.mnemonic()),
+      Self::Ja { target } => write!(f, "ja {target}"),
+      Self::Jmp { cond, src, jt, jf } => write!(f, "{} {src}, jt {jt}, jf {jf}", cond.mnemonic()),
+      Self::Ret { value } => write!(f, "ret {value}"),
+      Self::Tax => f.write_str("tax"),
+      Self::Txa => f.write_str("txa"),
+      Self::Invalid(sock_filter { code, jt, jf, k }) => {
+        write!(f, "invalid {{ code: {code:#06x}, jt: {jt}, jf: {jf}, k: {k:#x} }}")
+      }
+    }
+  }
+}
+
+/// Disassemble a classic BPF program.
+pub fn disassemble_cbpf(filter: &[sock_filter]) -> Vec<CbpfInsn> {
+  filter
+    .iter()
+    .enumerate()
+    .map(|(pc, insn)| CbpfInsn::decode(pc, insn))
+    .collect()
+}
+
+/// The action of a seccomp filter's return value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeccompAction {
+  KillProcess,
+  KillThread,
+  Trap { data: u16 },
+  Errno { errno: u16 },
+  UserNotif,
+  Trace { data: u16 },
+  Log,
+  Allow,
+  Unknown(u32),
+}
+
+impl From<u32> for SeccompAction {
+  fn from(value: u32) -> Self {
+    let data = (value & libc::SECCOMP_RET_DATA) as u16;
+    match value & libc::SECCOMP_RET_ACTION_FULL {
+      libc::SECCOMP_RET_KILL_PROCESS => Self::KillProcess,
+      libc::SECCOMP_RET_KILL_THREAD => Self::KillThread,
+      libc::SECCOMP_RET_TRAP => Self::Trap { data },
+      libc::SECCOMP_RET_ERRNO => Self::Errno { errno: data },
+      libc::SECCOMP_RET_USER_NOTIF => Self::UserNotif,
+      libc::SECCOMP_RET_TRACE => Self::Trace { data },
+      libc::SECCOMP_RET_LOG => Self::Log,
+      libc::SECCOMP_RET_ALLOW => Self::Allow,
+      _ => Self::Unknown(value),
+    }
+  }
+}
+
+/// What a seccomp filter instruction operates on, resolved from the preceding instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeccompAnnotation {
+  /// The instruction compares `seccomp_data.nr` with the number of this syscall.
+  Syscall(&'static str),
+  /// The instruction returns this action.
+  Action(SeccompAction),
+}
+
+/// A disassembled instruction of a seccomp filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeccompInsn {
+  pub insn: CbpfInsn,
+  pub annotation: Option<SeccompAnnotation>,
+}
+
+impl Display for SeccompInsn {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self.annotation {
+      Some(SeccompAnnotation::Syscall(name)) => write!(f, "{} ; {name}", self.insn),
+      Some(SeccompAnnotation::Action(action)) => write!(f, "{} ; {action:?}", self.insn),
+      None => write!(f, "{}", self.insn),
+    }
+  }
+}
+
+/// Disassemble a seccomp filter, resolving comparisons against `seccomp_data.nr` to syscall names
+/// of the current architecture.
+pub fn disassemble_seccomp_filter(filter: &[sock_filter]) -> Vec<SeccompInsn> {
+  // offsetof(struct seccomp_data, nr)
+  const SECCOMP_DATA_NR: u32 = 0;
+  let insns = disassemble_cbpf(filter);
+  // Whether the accumulator holds the syscall number when reaching each instruction.
+  // Classic BPF only jumps forward so a single pass over the program is enough.
+  let mut a_is_nr: Vec<Option<bool>> = vec![None; insns.len()];
+  if let Some(first) = a_is_nr.first_mut() {
+    *first = Some(false);
+  }
+  fn merge(a_is_nr: &mut [Option<bool>], target: usize, state: bool) {
+    if let Some(slot) = a_is_nr.get_mut(target) {
+      *slot = Some(slot.map_or(state, |s| s && state));
+    }
+  }
+  let mut result = Vec::with_capacity(insns.len());
+  for (pc, insn) in insns.into_iter().enumerate() {
+    let is_nr = a_is_nr[pc].unwrap_or(false);
+    let mut annotation = None;
+    match insn {
+      CbpfInsn::Ld {
+        size: BpfSize::Word,
+        src: CbpfLoadSrc::Abs(SECCOMP_DATA_NR),
+      } => merge(&mut a_is_nr, pc + 1, true),
+      CbpfInsn::Ld { .. } | CbpfInsn::Alu { .. } | CbpfInsn::Txa => merge(&mut a_is_nr, pc + 1, false),
+      CbpfInsn::Ja { target } => merge(&mut a_is_nr, target, is_nr),
+      CbpfInsn::Jmp { cond, src, jt, jf } => {
+        if let (true, BpfJmpCond::Eq | BpfJmpCond::Ge | BpfJmpCond::Gt, CbpfOperand::K(k)) = (is_nr, cond, src) {
+          annotation = syscall_name(k as isize).map(SeccompAnnotation::Syscall);
+        }
+        merge(&mut a_is_nr, jt, is_nr);
+        merge(&mut a_is_nr, jf, is_nr);
+      }
+      CbpfInsn::Ret {
+        value: CbpfOperand::K(k),
+      } => annotation = Some(SeccompAnnotation::Action(k.into())),
+      CbpfInsn::Ret { .. } | CbpfInsn::Invalid(_) => {}
+      CbpfInsn::Ldx { .. } | CbpfInsn::St { .. } | CbpfInsn::Stx { .. } | CbpfInsn::Tax => {
+        merge(&mut a_is_nr, pc + 1, is_nr)
+      }
+    }
+    result.push(SeccompInsn { insn, annotation });
+  }
+  result
+}
+
+/// Read and disassemble the `struct sock_fprog` of a classic BPF socket filter.
+pub(crate) fn read_cbpf_program(pid: Pid, address: AddressType) -> InspectResult<Vec<CbpfInsn>> {
+  read_sock_fprog(pid, address)
+    .map(|filter| disassemble_cbpf(&filter))
+    .map_err(|e| e.map_ptrace_failure(|filter| disassemble_cbpf(&filter)))
+}
+
+/// Read and disassemble the `struct sock_fprog` of a seccomp filter.
+pub(crate) fn read_seccomp_filter(pid: Pid, address: AddressType) -> InspectResult<Vec<SeccompInsn>> {
+  read_sock_fprog(pid, address)
+    .map(|filter| disassemble_seccomp_filter(&filter))
+    .map_err(|e| e.map_ptrace_failure(|filter| disassemble_seccomp_filter(&filter)))
+}
+
+/// The `src_reg` of a BPF_LD | BPF_IMM | BPF_DW instruction, telling what the 64-bit immediate refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EbpfImm64Kind {
+  /// A plain 64-bit constant
+  Imm,
+  /// BPF_PSEUDO_MAP_FD, a map file descriptor
+  MapFd,
+  /// BPF_PSEUDO_MAP_VALUE, an offset into the value of a map file descriptor
+  MapValue,
+  /// BPF_PSEUDO_BTF_ID, a kernel variable
+  BtfId,
+  /// BPF_PSEUDO_FUNC, a relative offset to a BPF function
+  Func,
+  /// BPF_PSEUDO_MAP_IDX, an index into the fd_array
+  MapIdx,
+  /// BPF_PSEUDO_MAP_IDX_VALUE
+  MapIdxValue,
+  Unknown(u8),
+}
+
+impl From<u8> for EbpfImm64Kind {
+  fn from(value: u8) -> Self {
+    match value {
+      0 => Self::Imm,
+      1 => Self::MapFd,
+      2 => Self::MapValue,
+      3 => Self::BtfId,
+      4 => Self::Func,
+      5 => Self::MapIdx,
+      6 => Self::MapIdxValue,
+      other => Self::Unknown(other),
+    }
+  }
+}
+
+/// The kind of function called by BPF_CALL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EbpfCallKind {
+  /// A BPF helper function by its id
+  Helper,
+  /// BPF_PSEUDO_CALL, a BPF function at a relative offset
+  Pseudo,
+  /// BPF_PSEUDO_KFUNC_CALL, a kernel function by its BTF id
+  Kfunc,
+  Unknown(u8),
+}
+
+/// The source operand of an eBPF instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EbpfOperand {
+  Imm(i32),
+  Reg(u8),
+}
+
+/// The byte swap of BPF_END
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EbpfEndian {
+  /// BPF_TO_LE
+  ToLe,
+  /// BPF_TO_BE
+  ToBe,
+  /// BPF_ALU64 | BPF_END, unconditional byte swap
+  Swap,
+}
+
+/// A disassembled eBPF instruction.
+///
+/// Jump offsets are relative to the next instruction, in units of 8-byte instruction slots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EbpfInsn {
+  /// `dst op= src` in 64-bit (`wide`) or 32-bit registers. `off` selects the signed division
+  /// and modulo and the sign extension width of `mov`.
+  Alu {
+    wide: bool,
+    op: BpfAluOp,
+    dst: u8,
+    src: EbpfOperand,
+    off: i16,
+  },
+  /// `dst = bswap(dst)` for the lowest `bits` bits
+  Endian { kind: EbpfEndian, dst: u8, bits: i32 },
+  /// `dst = imm`, this instruction takes two slots.
+  LdImm64 { dst: u8, kind: EbpfImm64Kind, imm: u64 },
+  /// Legacy packet access `r0 = *(size *)skb[src + imm]`
+  LdPacket { size: BpfSize, src: Option<u8>, imm: i32 },
+  /// `dst = *(size *)(src + off)`, sign extended if `signed`
+  Ldx {
+    size: BpfSize,
+    signed: bool,
+    dst: u8,
+    src: u8,
+    off: i16,
+  },
+  /// `*(size *)(dst + off) = imm`
+  St { size: BpfSize, dst: u8, off: i16, imm: i32 },
+  /// `*(size *)(dst + off) = src`
+  Stx { size: BpfSize, dst: u8, src: u8, off: i16 },
+  /// An atomic operation on `*(size *)(dst + off)` with `src`, `op` is the raw `imm` field.
+  Atomic {
+    size: BpfSize,
+    op: i32,
+    dst: u8,
+    src: u8,
+    off: i16,
+  },
+  /// `goto pc + offset`, `gotol` if `wide`
+  Ja { wide: bool, offset: i32 },
+  /// `if dst cond src goto pc + off` in 64-bit (`wide`) or 32-bit registers
+  Jmp {
+    wide: bool,
+    cond: BpfJmpCond,
+    dst: u8,
+    src: EbpfOperand,
+    off: i16,
+  },
+  /// Call a function, `imm` is the helper id, relative offset or BTF id depending on `kind`.
+  Call { kind: EbpfCallKind, imm: i32 },
+  /// Return `r0`.
+  Exit,
+  /// An instruction that is unknown or rejected by the kernel.
+  Invalid(bpf_insn),
+}
+
+impl EbpfInsn {
+  /// The number of 8-byte instruction slots taken by this instruction.
+  pub fn slots(&self) -> usize {
+    match self {
+      Self::LdImm64 { .. } => 2,
+      _ => 1,
+    }
+  }
+}
+
+// eBPF only opcodes, https://elixir.bootlin.com/linux/v6.11/source/include/uapi/linux/bpf.h#L14
+const BPF_JMP32: u8 = 0x06;
+const BPF_ALU64: u8 = 0x07;
+const BPF_DW: u8 = 0x18;
+const BPF_MEMSX: u8 = 0x80;
+const BPF_ATOMIC: u8 = 0xc0;
+const BPF_END: u8 = 0xd0;
+const BPF_CALL: u8 = 0x80;
+const BPF_EXIT: u8 = 0x90;
+
+/// Disassemble an eBPF program.
+pub fn disassemble_ebpf(insns: &[bpf_insn]) -> Vec<EbpfInsn> {
+  let mut result = Vec::with_capacity(insns.len());
+  let mut pc = 0;
+  while pc < insns.len() {
+    let insn = insns[pc];
+    let decoded = decode_ebpf(&insn, insns.get(pc + 1));
+    pc += decoded.slots();
+    result.push(decoded);
+  }
+  result
+}
+
+fn decode_ebpf(insn: &bpf_insn, next: Option<&bpf_insn>) -> EbpfInsn {
+  let bpf_insn { code, regs, off, imm } = *insn;
+  let (dst, src) = insn_regs(regs);
+  let class = code & 0x07;
+  let operand = || match code & 0x08 {
+    0 => EbpfOperand::Imm(imm),
+    _ => EbpfOperand::Reg(src),
+  };
+  let size = BpfSize::from_code(code as u16);
+  let mode = code & 0xe0;
+  let decoded = match class {
+    _ if code == libc::BPF_LD as u8 | BPF_DW | libc::BPF_IMM as u8 => next.map(|next| EbpfInsn::LdImm64 {
+      dst,
+      kind: src.into(),
+      imm: (imm as u32 as u64) | ((next.imm as u32 as u64) << 32),
+    }),
+    0x00 if (mode == libc::BPF_ABS as u8 || mode == libc::BPF_IND as u8) && size != BpfSize::DoubleWord => {
+      Some(EbpfInsn::LdPacket {
+        size,
+        src: (mode == libc::BPF_IND as u8).then_some(src),
+        imm,
+      })
+    }
+    0x01 if mode == libc::BPF_MEM as u8 || mode == BPF_MEMSX => Some(EbpfInsn::Ldx {
+      size,
+      signed: mode == BPF_MEMSX,
+      dst,
+      src,
+      off,
+    }),
+    0x02 if mode == libc::BPF_MEM as u8 => Some(EbpfInsn::St { size, dst, off, imm }),
+    0x03 if mode == libc::BPF_MEM as u8 => Some(EbpfInsn::Stx { size, dst, src, off }),
+    0x03 if mode == BPF_ATOMIC => Some(EbpfInsn::Atomic {
+      size,
+      op: imm,
+      dst,
+      src,
+      off,
+    }),
+    0x04 | BPF_ALU64 if code & 0xf0 == BPF_END => Some(EbpfInsn::Endian {
+      kind: match (class, code & 0x08) {
+        (BPF_ALU64, _) => EbpfEndian::Swap,
+        (_, 0) => EbpfEndian::ToLe,
+        _ => EbpfEndian::ToBe,
+      },
+      dst,
+      bits: imm,
+    }),
+    0x04 | BPF_ALU64 => BpfAluOp::from_code(code as u16).map(|op| EbpfInsn::Alu {
+      wide: class == BPF_ALU64,
+      op,
+      dst,
+      src: operand(),
+      off,
+    }),
+    0x05 | BPF_JMP32 => match code & 0xf0 {
+      0x00 => Some(EbpfInsn::Ja {
+        wide: class == BPF_JMP32,
+        offset: if class == BPF_JMP32 { imm } else { off as i32 },
+      }),
+      BPF_CALL if class == 0x05 => Some(EbpfInsn::Call {
+        kind: match src {
+          0 => EbpfCallKind::Helper,
+          1 => EbpfCallKind::Pseudo,
+          2 => EbpfCallKind::Kfunc,
+          other => EbpfCallKind::Unknown(other),
+        },
+        imm,
+      }),
+      BPF_EXIT if class == 0x05 => Some(EbpfInsn::Exit),
+      _ => BpfJmpCond::from_code(code as u16).map(|cond| EbpfInsn::Jmp {
+        wide: class == 0x05,
+        cond,
+        dst,
+        src: operand(),
+        off,
+      }),
+    },
+    _ => None,
+  };
+  decoded.unwrap_or(EbpfInsn::Invalid(*insn))
+}
+
+/// Split `dst_reg:4` and `src_reg:4` of `struct bpf_insn`.
+fn insn_regs(regs: u8) -> (u8, u8) {
+  if cfg!(target_endian = "little") {
+    (regs & 0xf, regs >> 4)
+  } else {
+    (regs >> 4, regs & 0xf)
+  }
+}
+
+fn size_name(size: BpfSize, signed: bool) -> String {
+  format!("{}{}", if signed { 's' } else { 'u' }, size.bits())
+}
+
+fn fmt_mem(f: &mut Formatter<'_>, size: BpfSize, signed: bool, reg: u8, off: i16) -> fmt::Result {
+  let sign = if off < 0 { '-' } else { '+' };
+  write!(f, "*({} *)(r{reg} {sign} {})", size_name(size, signed), off.unsigned_abs())
+}
+
+fn fmt_offset(f: &mut Formatter<'_>, offset: i32) -> fmt::Result {
+  write!(f, "goto pc{offset:+}")
+}
+
+impl Display for EbpfInsn {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let reg = |wide: bool, reg: u8| format!("{}{reg}", if wide { 'r' } else { 'w' });
+    match *self {
+      Self::Alu {
+        wide,
+        op: BpfAluOp::Neg,
+        dst,
+        ..
+      } => write!(f, "{0} = -{0}", reg(wide, dst)),
+      Self::Alu {
+        wide,
+        op: BpfAluOp::Mov,
+        dst,
+        src: EbpfOperand::Reg(src),
+        off: bits @ (8 | 16 | 32),
+      } => write!(f, "{} = (s{bits}){}", reg(wide, dst), reg(wide, src)),
+      Self::Alu {
+        wide,
+        op,
+        dst,
+        src,
+        off,
+      } => {
+        write!(f, "{} {} ", reg(wide, dst), op.operator(off == 1))?;
+        match src {
+          EbpfOperand::Imm(imm) => write!(f, "{imm:#x}"),
+          EbpfOperand::Reg(src) => f.write_str(&reg(wide, src)),
+        }
+      }
+      Self::Endian { kind, dst, bits } => {
+        let name = match kind {
+          EbpfEndian::ToLe => "le",
+          EbpfEndian::ToBe => "be",
+          EbpfEndian::Swap => "bswap",
+        };
+        write!(f, "r{dst} = {name}{bits} r{dst}")
+      }
+      Self::LdImm64 {
+        dst,
+        kind: EbpfImm64Kind::Imm,
+        imm,
+      } => write!(f, "r{dst} = {imm:#x} ll"),
+      Self::LdImm64 { dst, kind, imm } => write!(f, "r{dst} = {kind:?}({imm:#x}) ll"),
+      Self::LdPacket { size, src, imm } => match src {
+        Some(src) => write!(f, "r0 = *({} *)skb[r{src} + {imm}]", size_name(size, false)),
+        None => write!(f, "r0 = *({} *)skb[{imm}]", size_name(size, false)),
+      },
+      Self::Ldx {
+        size,
+        signed,
+        dst,
+        src,
+        off,
+      } => {
+        write!(f, "r{dst} = ")?;
+        fmt_mem(f, size, signed, src, off)
+      }
+      Self::St { size, dst, off, imm } => {
+        fmt_mem(f, size, false, dst, off)?;
+        write!(f, " = {imm:#x}")
+      }
+      Self::Stx { size, dst, src, off } => {
+        fmt_mem(f, size, false, dst, off)?;
+        write!(f, " = r{src}")
+      }
+      Self::Atomic {
+        size,
+        op,
+        dst,
+        src,
+        off,
+      } => {
+        const BPF_FETCH: i32 = 0x01;
+        let name = match op & !BPF_FETCH {
+          0x00 => "add",
+          0x40 => "or",
+          0x50 => "and",
+          0xa0 => "xor",
+          0xe0 => "xchg",
+          0xf0 => "cmpxchg",
+          _ => "unknown",
+        };
+        let fetch = if op & BPF_FETCH != 0 { "fetch_" } else { "" };
+        write!(f, "lock {fetch}{name} ")?;
+        fmt_mem(f, size, false, dst, off)?;
+        write!(f, ", r{src}")
+      }
+      Self::Ja { wide: true, offset } => write!(f, "gotol pc{offset:+}"),
+      Self::Ja { wide: false, offset } => fmt_offset(f, offset),
+      Self::Jmp {
+        wide,
+        cond,
+        dst,
+        src,
+        off,
+      } => {
+        write!(f, "if {} {} ", reg(wide, dst), cond.operator())?;
+        match src {
+          EbpfOperand::Imm(imm) => write!(f, "{imm:#x} ")?,
+          EbpfOperand::Reg(src) => write!(f, "{} ", reg(wide, src))?,
+        }
+        fmt_offset(f, off as i32)
+      }
+      Self::Call {
+        kind: EbpfCallKind::Pseudo,
+        imm,
+      } => write!(f, "call pc{imm:+}"),
+      Self::Call {
+        kind: EbpfCallKind::Kfunc,
+        imm,
+      } => write!(f, "call kfunc {imm}"),
+      Self::Call { imm, .. } => write!(f, "call {imm}"),
+      Self::Exit => f.write_str("exit"),
+      Self::Invalid(bpf_insn { code, regs, off, imm }) => {
+        write!(f, "invalid {{ code: {code:#04x}, regs: {regs:#04x}, off: {off}, imm: {imm:#x} }}")
+      }
+    }
+  }
+}
+
+/// Read and disassemble `count` eBPF instructions, at most [`BPF_COMPLEXITY_LIMIT_INSNS`] of them.
+pub(crate) fn read_ebpf_program(pid: Pid, address: AddressType, count: usize) -> InspectResult<Vec<EbpfInsn>> {
+  let count = count.min(BPF_COMPLEXITY_LIMIT_INSNS);
+  let bytes =
+    read_bytes(pid, address, count * std::mem::size_of::<bpf_insn>()).map_err(|e| e.map_ptrace_failure(|_| vec![]))?;
+  let insns: Vec<bpf_insn> = bytes
+    .chunks_exact(std::mem::size_of::<bpf_insn>())
+    .map(|chunk| bpf_insn {
+      code: chunk[0],
+      regs: chunk[1],
+      off: i16::from_ne_bytes([chunk[2], chunk[3]]),
+      imm: i32::from_ne_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]),
+    })
+    .collect();
+  Ok(disassemble_ebpf(&insns))
+}
@@ -0,0 +1,144 @@
+use std::fmt;
+
+use crate::{
+  read_bytes, AddressType, CapgetRawArgs, CapsetRawArgs, InspectError, InspectFromPid, InspectFromRawArgs,
+  InspectResult, InspectResultFromRawArgs, Pid, StructFields,
+};
+
+use super::cap_user_header;
+
+// https://elixir.bootlin.com/linux/v6.11/source/include/uapi/linux/capability.h#L31
+const LINUX_CAPABILITY_VERSION_1: u32 = 0x19980330;
+const LINUX_CAPABILITY_VERSION_2: u32 = 0x20071026;
+const LINUX_CAPABILITY_VERSION_3: u32 = 0x20080522;
+
+int_enum! {
+  /// The CAP_* capabilities
+  pub enum Capability: u32 {
+    Chown = 0,
+    DacOverride = 1,
+    DacReadSearch = 2,
+    Fowner = 3,
+    Fsetid = 4,
+    Kill = 5,
+    Setgid = 6,
+    Setuid = 7,
+    Setpcap = 8,
+    LinuxImmutable = 9,
+    NetBindService = 10,
+    NetBroadcast = 11,
+    NetAdmin = 12,
+    NetRaw = 13,
+    IpcLock = 14,
+    IpcOwner = 15,
+    SysModule = 16,
+    SysRawio = 17,
+    SysChroot = 18,
+    SysPtrace = 19,
+    SysPacct = 20,
+    SysAdmin = 21,
+    SysBoot = 22,
+    SysNice = 23,
+    SysResource = 24,
+    SysTime = 25,
+    SysTtyConfig = 26,
+    Mknod = 27,
+    Lease = 28,
+    AuditWrite = 29,
+    AuditControl = 30,
+    Setfcap = 31,
+    MacOverride = 32,
+    MacAdmin = 33,
+    Syslog = 34,
+    WakeAlarm = 35,
+    BlockSuspend = 36,
+    AuditRead = 37,
+    Perfmon = 38,
+    Bpf = 39,
+    CheckpointRestore = 40,
+  }
+}
+
+/// A set of capabilities, stored as the 64-bit mask used by the kernel.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct CapabilitySet(pub u64);
+
+impl CapabilitySet {
+  pub fn contains(&self, cap: Capability) -> bool {
+    let bit = u32::from(cap);
+    bit < 64 && self.0 & (1 << bit) != 0
+  }
+
+  /// The capabilities in the set, bits unknown to this crate are yielded as [`Capability::Unknown`].
+  pub fn iter(&self) -> impl Iterator<Item = Capability> + '_ {
+    (0..64u32).filter(|bit| self.0 & (1 << bit) != 0).map(Capability::from)
+  }
+}
+
+impl From<u64> for CapabilitySet {
+  fn from(mask: u64) -> Self {
+    Self(mask)
+  }
+}
+
+impl fmt::Debug for CapabilitySet {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_set().entries(self.iter()).finish()
+  }
+}
+
+/// The capability sets of a thread, combined from the `cap_user_data` elements of capget and capset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CapUserData {
+  pub effective: CapabilitySet,
+  pub permitted: CapabilitySet,
+  pub inheritable: CapabilitySet,
+}
+
+impl CapUserData {
+  /// Combine the `cap_user_data` elements, the second one holds the capabilities 32 and up.
+  pub fn from_bytes(bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    let set = |offset: usize| CapabilitySet(fields.u32(offset) as u64 | (fields.u32(12 + offset) as u64) << 32);
+    Self {
+      effective: set(0),
+      permitted: set(4),
+      inheritable: set(8),
+    }
+  }
+}
+
+/// The number of `cap_user_data` elements for a header version, `None` for unknown versions.
+fn cap_user_data_count(version: u32) -> Option<usize> {
+  match version {
+    LINUX_CAPABILITY_VERSION_1 => Some(1),
+    LINUX_CAPABILITY_VERSION_2 | LINUX_CAPABILITY_VERSION_3 => Some(2),
+    _ => None,
+  }
+}
+
+/// Read the data at `datap` as sized by the version in the header at `hdrp`, `None` if `datap` is NULL.
+fn read_cap_user_data(pid: Pid, hdrp: AddressType, datap: AddressType) -> InspectResult<Option<CapUserData>> {
+  if datap.is_null() {
+    return Ok(None);
+  }
+  let count = InspectResult::<cap_user_header>::inspect_from(pid, hdrp)
+    .ok()
+    .and_then(|header| cap_user_data_count(header.version))
+    .ok_or(InspectError::DependencyInspectFailure { field: "hdrp" })?;
+  read_bytes(pid, datap, count * 12)
+    .map(|bytes| Some(CapUserData::from_bytes(&bytes)))
+    .map_err(|e| e.map_ptrace_failure(|bytes| Some(CapUserData::from_bytes(&bytes))))
+}
+
+impl InspectResultFromRawArgs<CapgetRawArgs> for InspectResult<Option<CapUserData>> {
+  fn inspect_from(pid: Pid, raw_args: &CapgetRawArgs, _syscall_result: isize) -> Self {
+    read_cap_user_data(pid, raw_args.hdrp as AddressType, raw_args.datap as AddressType)
+  }
+}
+
+impl InspectFromRawArgs<CapsetRawArgs> for InspectResult<Option<CapUserData>> {
+  fn inspect_from(pid: Pid, raw_args: &CapsetRawArgs) -> Self {
+    read_cap_user_data(pid, raw_args.hdrp as AddressType, raw_args.datap as AddressType)
+  }
+}
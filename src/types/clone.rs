@@ -0,0 +1,135 @@
+use std::os::fd::RawFd;
+
+use enumflags2::{bitflags, BitFlags};
+use nix::libc::{c_int, c_ulong, pid_t};
+
+use crate::{
+  AddressType, Clone3RawArgs, CloneRawArgs, InspectCountedFromPid, InspectFromRawArgs, InspectResult, Pid, StructFields,
+};
+
+use super::{read_extensible, Extensible};
+
+/// CLONE_ARGS_SIZE_VER2, the size of the fields known to this crate
+const CLONE_ARGS_SIZE_VER2: usize = 88;
+/// MAX_PID_NS_LEVEL, the maximum length of `set_tid`
+const MAX_PID_NS_LEVEL: usize = 32;
+/// CSIGNAL, the exit signal in the low byte of the flags of clone
+const CSIGNAL: c_ulong = 0xff;
+
+/// The CLONE_* flags
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub enum CloneFlags {
+  /// CLONE_NEWTIME, only available to clone3 because it overlaps with the exit signal of clone.
+  NewTime = 0x80,
+  Vm = 0x100,
+  Fs = 0x200,
+  Files = 0x400,
+  Sighand = 0x800,
+  Pidfd = 0x1000,
+  Ptrace = 0x2000,
+  Vfork = 0x4000,
+  Parent = 0x8000,
+  Thread = 0x10000,
+  NewNs = 0x20000,
+  SysvSem = 0x40000,
+  SetTls = 0x80000,
+  ParentSetTid = 0x100000,
+  ChildClearTid = 0x200000,
+  Detached = 0x400000,
+  Untraced = 0x800000,
+  ChildSetTid = 0x1000000,
+  NewCgroup = 0x2000000,
+  NewUts = 0x4000000,
+  NewIpc = 0x8000000,
+  NewUser = 0x10000000,
+  NewPid = 0x20000000,
+  NewNet = 0x40000000,
+  Io = 0x80000000,
+  /// CLONE_CLEAR_SIGHAND, clone3 only
+  ClearSighand = 0x100000000,
+  /// CLONE_INTO_CGROUP, clone3 only
+  IntoCgroup = 0x200000000,
+}
+
+/// The arguments of clone and clone3 in the same shape, independent of the architecture.
+///
+/// The pointers are where the kernel stores the pidfd and the thread ids, the stored values are available
+/// on sysexit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CloneRequest {
+  pub flags: BitFlags<CloneFlags>,
+  /// The signal sent to the parent when the child exits, 0 for none.
+  pub exit_signal: c_int,
+  /// The stack of the child. For clone, it is the top of the stack and `stack_size` is 0.
+  pub stack: AddressType,
+  pub stack_size: u64,
+  pub tls: u64,
+  /// Where the pidfd is stored if CLONE_PIDFD is set
+  pub pidfd: AddressType,
+  /// Where the child thread id is stored in the parent if CLONE_PARENT_SETTID is set
+  pub parent_tid: AddressType,
+  /// Where the child thread id is stored in the child if CLONE_CHILD_SETTID is set
+  /// or cleared if CLONE_CHILD_CLEARTID is set
+  pub child_tid: AddressType,
+  /// The thread ids of the child in each of its pid namespaces, empty for clone.
+  pub set_tid: InspectResult<Vec<pid_t>>,
+  /// The cgroup to place the child in if CLONE_INTO_CGROUP is set
+  pub cgroup: Option<RawFd>,
+}
+
+impl InspectFromRawArgs<CloneRawArgs> for CloneRequest {
+  fn inspect_from(_pid: Pid, raw_args: &CloneRawArgs) -> Self {
+    let flags = BitFlags::from_bits_truncate(raw_args.flags & !CSIGNAL);
+    let parent_tid = raw_args.parent_tid as AddressType;
+    Self {
+      flags,
+      exit_signal: (raw_args.flags & CSIGNAL) as c_int,
+      stack: raw_args.stack,
+      stack_size: 0,
+      tls: raw_args.tls,
+      // clone stores the pidfd in parent_tid, so CLONE_PIDFD and CLONE_PARENT_SETTID can't be used together.
+      pidfd: if flags.contains(CloneFlags::Pidfd) {
+        parent_tid
+      } else {
+        std::ptr::null_mut()
+      },
+      parent_tid,
+      child_tid: raw_args.child_tid as AddressType,
+      set_tid: Ok(vec![]),
+      cgroup: None,
+    }
+  }
+}
+
+impl CloneRequest {
+  /// Decode a `struct clone_args`, `set_tid` is read from the tracee.
+  pub fn from_clone_args(pid: Pid, bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    let flags = BitFlags::from_bits_truncate(fields.u64(0));
+    let set_tid_size = (fields.u64(72) as usize).min(MAX_PID_NS_LEVEL);
+    Self {
+      flags,
+      exit_signal: fields.u64(32) as c_int,
+      stack: fields.ptr(40),
+      stack_size: fields.u64(48),
+      tls: fields.u64(56),
+      pidfd: fields.ptr(8),
+      parent_tid: fields.ptr(24),
+      child_tid: fields.ptr(16),
+      set_tid: InspectCountedFromPid::inspect_from(pid, fields.ptr(64), set_tid_size),
+      cgroup: flags.contains(CloneFlags::IntoCgroup).then(|| fields.u64(80) as RawFd),
+    }
+  }
+}
+
+/// The kernel rejects a `size` smaller than CLONE_ARGS_SIZE_VER0 (64) with EINVAL,
+/// such an argument is still decoded with the missing fields zero.
+impl InspectFromRawArgs<Clone3RawArgs> for InspectResult<Extensible<CloneRequest>> {
+  fn inspect_from(pid: Pid, raw_args: &Clone3RawArgs) -> Self {
+    read_extensible(pid, raw_args.cl_args as AddressType, raw_args.size, CLONE_ARGS_SIZE_VER2, |bytes| {
+      CloneRequest::from_clone_args(pid, bytes)
+    })
+  }
+}
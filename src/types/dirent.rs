@@ -0,0 +1,89 @@
+use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+use crate::{
+  read_bytes, AddressType, Getdents64RawArgs, GetdentsRawArgs, InspectResult, InspectResultFromRawArgs, Pid,
+  StructFields,
+};
+
+/// The offset of `d_name` in `struct linux_dirent`
+const LINUX_DIRENT_NAME_OFFSET: usize = 18;
+/// The offset of `d_name` in `struct linux_dirent64`
+const LINUX_DIRENT64_NAME_OFFSET: usize = 19;
+
+int_enum! {
+  /// The DT_* file types of a directory entry, DT_UNKNOWN is `Unknown(0)`.
+  pub enum FileType: u8 {
+    Fifo = 1,
+    Char = 2,
+    Dir = 4,
+    Block = 6,
+    Reg = 8,
+    Link = 10,
+    Sock = 12,
+    Whiteout = 14,
+  }
+}
+
+/// A directory entry returned by getdents or getdents64
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirEntry {
+  pub ino: u64,
+  /// The offset of the next entry, to be passed to lseek
+  pub off: i64,
+  pub kind: FileType,
+  pub name: OsString,
+}
+
+/// Walk the variable-length records of a dirent buffer.
+///
+/// `name_offset` is where the name starts in a record and `kind` gets the type from a record.
+/// The walk stops at the first record whose `d_reclen` is malformed.
+fn parse_records(buf: &[u8], name_offset: usize, kind: impl Fn(&[u8]) -> u8) -> Vec<DirEntry> {
+  let mut entries = Vec::new();
+  let mut rest = buf;
+  while rest.len() > name_offset {
+    let fields = StructFields(rest);
+    let reclen = fields.u16(16) as usize;
+    if reclen <= name_offset || reclen > rest.len() {
+      break;
+    }
+    let record = &rest[..reclen];
+    let name = &record[name_offset..];
+    let name = name.split(|&b| b == 0).next().unwrap_or(name);
+    entries.push(DirEntry {
+      ino: fields.u64(0),
+      off: fields.u64(8) as i64,
+      kind: kind(record).into(),
+      name: OsString::from_vec(name.to_vec()),
+    });
+    rest = &rest[reclen..];
+  }
+  entries
+}
+
+/// Parse the `struct linux_dirent` records filled by getdents.
+pub fn parse_dirents(buf: &[u8]) -> Vec<DirEntry> {
+  // The type is the last byte of the record, after the padding of the name.
+  parse_records(buf, LINUX_DIRENT_NAME_OFFSET, |record| record[record.len() - 1])
+}
+
+/// Parse the `struct linux_dirent64` records filled by getdents64.
+pub fn parse_dirents64(buf: &[u8]) -> Vec<DirEntry> {
+  parse_records(buf, LINUX_DIRENT64_NAME_OFFSET, |record| record[18])
+}
+
+impl InspectResultFromRawArgs<GetdentsRawArgs> for InspectResult<Vec<DirEntry>> {
+  fn inspect_from(pid: Pid, raw_args: &GetdentsRawArgs, syscall_result: isize) -> Self {
+    read_bytes(pid, raw_args.dirp as AddressType, syscall_result as usize)
+      .map(|buf| parse_dirents(&buf))
+      .map_err(|e| e.map_ptrace_failure(|buf| parse_dirents(&buf)))
+  }
+}
+
+impl InspectResultFromRawArgs<Getdents64RawArgs> for InspectResult<Vec<DirEntry>> {
+  fn inspect_from(pid: Pid, raw_args: &Getdents64RawArgs, syscall_result: isize) -> Self {
+    read_bytes(pid, raw_args.dirp as AddressType, syscall_result as usize)
+      .map(|buf| parse_dirents64(&buf))
+      .map_err(|e| e.map_ptrace_failure(|buf| parse_dirents64(&buf)))
+  }
+}
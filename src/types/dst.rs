@@ -48,8 +48,7 @@ impl_slice_dst! {
   statmount => 520, 8,
   msgbuf => std::mem::size_of::<c_long>(), std::mem::align_of::<c_long>(),
   file_handle => 8, 4,
-  xattr_args => 16, 8,
-  mount_attr => 32, 8
+  xattr_args => 16, 8
 }
 
 #[derive(Debug, PartialEq)]
@@ -114,13 +113,3 @@ pub struct xattr_args {
   pub flags: u32,
   pub __trailer: [c_char],
 }
-
-#[derive(Debug, PartialEq)]
-#[repr(C)]
-pub struct mount_attr {
-  pub attr_set: u64,
-  pub attr_clr: u64,
-  pub propagation: u64,
-  pub userns_fd: u64,
-  pub __trailer: [c_char],
-}
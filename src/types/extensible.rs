@@ -0,0 +1,97 @@
+use std::mem::{size_of, MaybeUninit};
+
+use nix::libc::sched_attr;
+
+use crate::{
+  read_bytes, AddressType, InspectDynSizedFromPid, InspectError, InspectFromPid, InspectResult, Pid, ReprCMarker,
+};
+
+use super::mnt_id_req;
+
+/// The kernel rejects extensible structs larger than a page.
+pub(crate) const EXTENSIBLE_SIZE_MAX: usize = 4096;
+
+/// SCHED_ATTR_SIZE_VER0, the size the kernel assumes when the `size` of `sched_attr` is 0
+const SCHED_ATTR_SIZE_VER0: usize = 48;
+
+/// A versioned struct read with the size declared by the caller, as copied by the kernel with `copy_struct_from_user`.
+///
+/// A caller built against older headers passes a smaller struct, so the fields it doesn't know are zero in `value`.
+/// A caller built against newer headers passes a larger struct, whose fields unknown to this crate
+/// are kept in `trailing`. The kernel rejects them unless they are zero or it knows them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extensible<T> {
+  /// The size declared by the caller
+  pub size: usize,
+  pub value: T,
+  /// The bytes past the fields known to this crate
+  pub trailing: Vec<u8>,
+}
+
+impl<T> Extensible<T> {
+  /// Split the bytes passed by the caller into the first `known_size` bytes, which are decoded with `decode`
+  /// after being zero-filled, and the trailing bytes.
+  pub fn decode(size: usize, bytes: &[u8], known_size: usize, decode: impl FnOnce(&[u8]) -> T) -> Self {
+    let (known, trailing) = bytes.split_at(bytes.len().min(known_size));
+    let mut known = known.to_vec();
+    known.resize(known_size, 0);
+    Self {
+      size,
+      value: decode(&known),
+      trailing: trailing.to_vec(),
+    }
+  }
+}
+
+/// Read the `size` bytes of an extensible struct, capped at a page.
+pub(crate) fn read_extensible<T: Clone + PartialEq>(
+  pid: Pid,
+  address: AddressType,
+  size: usize,
+  known_size: usize,
+  decode: impl Fn(&[u8]) -> T,
+) -> InspectResult<Extensible<T>> {
+  read_bytes(pid, address, size.min(EXTENSIBLE_SIZE_MAX))
+    .map(|bytes| Extensible::decode(size, &bytes, known_size, &decode))
+    .map_err(|e| e.map_ptrace_failure(|bytes| Extensible::decode(size, &bytes, known_size, &decode)))
+}
+
+fn from_repr_c_bytes<T: ReprCMarker>(bytes: &[u8]) -> T {
+  let mut value = MaybeUninit::<T>::zeroed();
+  unsafe {
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), value.as_mut_ptr() as *mut u8, bytes.len().min(size_of::<T>()));
+    value.assume_init()
+  }
+}
+
+/// A struct whose size is passed in another argument.
+impl<T: Clone + PartialEq + ReprCMarker> InspectDynSizedFromPid for InspectResult<Extensible<T>> {
+  fn inspect_from(pid: Pid, address: AddressType, size: usize) -> Self {
+    read_extensible(pid, address, size, size_of::<T>(), from_repr_c_bytes)
+  }
+}
+
+/// Extensible structs whose first field is their size
+pub(crate) trait SizePrefixed {
+  /// The size the kernel assumes when the size field is 0
+  const SIZE_IF_ZERO: usize = 0;
+}
+
+impl SizePrefixed for sched_attr {
+  const SIZE_IF_ZERO: usize = SCHED_ATTR_SIZE_VER0;
+}
+
+impl SizePrefixed for mnt_id_req {}
+
+/// A struct whose size is its first field.
+impl<T: Clone + PartialEq + ReprCMarker + SizePrefixed> InspectFromPid for InspectResult<Extensible<T>> {
+  fn inspect_from(pid: Pid, address: AddressType) -> Self {
+    let size = InspectResult::<u32>::inspect_from(pid, address)
+      .map_err(|_| InspectError::DependencyInspectFailure { field: "size" })?;
+    let size = match size {
+      0 => T::SIZE_IF_ZERO,
+      size => size as usize,
+    };
+    InspectDynSizedFromPid::inspect_from(pid, address, size)
+  }
+}
@@ -0,0 +1,186 @@
+use std::os::fd::RawFd;
+
+use nix::{
+  fcntl::{FdFlag, OFlag, SealFlag},
+  libc::{self, c_int, c_ulong, flock, pid_t},
+};
+
+use crate::{
+  AddressType, FcntlRawArgs, InspectFromPid, InspectFromRawArgs, InspectResult, InspectResultFromRawArgs, Pid,
+};
+
+use super::f_owner_ex;
+
+// https://elixir.bootlin.com/linux/v6.11/source/include/uapi/asm-generic/fcntl.h#L107
+const F_SETSIG: c_int = 10;
+const F_GETSIG: c_int = 11;
+const F_SETOWN_EX: c_int = 15;
+const F_GETOWN_EX: c_int = 16;
+
+/// The decoded `cmd` and `arg` of fcntl.
+///
+/// Commands that retrieve something only carry their inputs here.
+/// The retrieved value is available in [`FcntlResult`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum FcntlCmd {
+  /// F_DUPFD
+  DupFd { min_fd: RawFd },
+  /// F_DUPFD_CLOEXEC
+  DupFdCloexec { min_fd: RawFd },
+  /// F_GETFD
+  GetFd,
+  /// F_SETFD
+  SetFd { flags: FdFlag },
+  /// F_GETFL
+  GetFl,
+  /// F_SETFL
+  SetFl { flags: OFlag },
+  /// F_GETLK, the conflicting lock is available in [`FcntlResult::Lock`].
+  GetLk { lock: InspectResult<flock> },
+  /// F_SETLK
+  SetLk { lock: InspectResult<flock> },
+  /// F_SETLKW
+  SetLkw { lock: InspectResult<flock> },
+  /// F_OFD_GETLK, the conflicting lock is available in [`FcntlResult::Lock`].
+  OfdGetLk { lock: InspectResult<flock> },
+  /// F_OFD_SETLK
+  OfdSetLk { lock: InspectResult<flock> },
+  /// F_OFD_SETLKW
+  OfdSetLkw { lock: InspectResult<flock> },
+  /// F_GETOWN
+  GetOwn,
+  /// F_SETOWN, a negative owner is a process group.
+  SetOwn { owner: pid_t },
+  /// F_GETOWN_EX
+  GetOwnEx,
+  /// F_SETOWN_EX
+  SetOwnEx { owner: InspectResult<f_owner_ex> },
+  /// F_GETSIG
+  GetSig,
+  /// F_SETSIG
+  SetSig { signal: c_int },
+  /// F_GETLEASE
+  GetLease,
+  /// F_SETLEASE, the lease is one of F_RDLCK, F_WRLCK and F_UNLCK.
+  SetLease { lease: c_int },
+  /// F_NOTIFY, `events` is a mask of DN_* flags.
+  Notify { events: c_ulong },
+  /// F_GETPIPE_SZ
+  GetPipeSz,
+  /// F_SETPIPE_SZ
+  SetPipeSz { size: c_int },
+  /// F_ADD_SEALS
+  AddSeals { seals: SealFlag },
+  /// F_GET_SEALS
+  GetSeals,
+  /// Commands that are not decoded yet.
+  Other { cmd: c_int, arg: usize },
+}
+
+/// The values retrieved by fcntl, either through `arg` or as the syscall result.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum FcntlResult {
+  /// F_GETLK and F_OFD_GETLK
+  Lock(flock),
+  /// F_GETOWN_EX
+  OwnerEx(f_owner_ex),
+  /// F_GETFD
+  FdFlags(FdFlag),
+  /// F_GETFL
+  StatusFlags(OFlag),
+  /// F_GET_SEALS
+  Seals(SealFlag),
+  /// The command returns its value, if any, as the syscall result.
+  InSyscallResult,
+}
+
+impl FcntlCmd {
+  /// Decode the commands whose `arg` is a value, `None` for the commands whose `arg` points to tracee memory.
+  pub fn from_value_arg(cmd: c_int, arg: usize) -> Option<Self> {
+    Some(match cmd {
+      libc::F_GETLK
+      | libc::F_SETLK
+      | libc::F_SETLKW
+      | libc::F_OFD_GETLK
+      | libc::F_OFD_SETLK
+      | libc::F_OFD_SETLKW
+      | F_SETOWN_EX => return None,
+      libc::F_DUPFD => Self::DupFd { min_fd: arg as RawFd },
+      libc::F_DUPFD_CLOEXEC => Self::DupFdCloexec { min_fd: arg as RawFd },
+      libc::F_GETFD => Self::GetFd,
+      libc::F_SETFD => Self::SetFd {
+        flags: FdFlag::from_bits_retain(arg as c_int),
+      },
+      libc::F_GETFL => Self::GetFl,
+      libc::F_SETFL => Self::SetFl {
+        flags: OFlag::from_bits_retain(arg as c_int),
+      },
+      libc::F_GETOWN => Self::GetOwn,
+      libc::F_SETOWN => Self::SetOwn { owner: arg as pid_t },
+      F_GETOWN_EX => Self::GetOwnEx,
+      F_GETSIG => Self::GetSig,
+      F_SETSIG => Self::SetSig { signal: arg as c_int },
+      libc::F_GETLEASE => Self::GetLease,
+      libc::F_SETLEASE => Self::SetLease { lease: arg as c_int },
+      libc::F_NOTIFY => Self::Notify { events: arg as c_ulong },
+      libc::F_GETPIPE_SZ => Self::GetPipeSz,
+      libc::F_SETPIPE_SZ => Self::SetPipeSz { size: arg as c_int },
+      libc::F_ADD_SEALS => Self::AddSeals {
+        seals: SealFlag::from_bits_retain(arg as c_int),
+      },
+      libc::F_GET_SEALS => Self::GetSeals,
+      cmd => Self::Other { cmd, arg },
+    })
+  }
+}
+
+impl InspectFromRawArgs<FcntlRawArgs> for FcntlCmd {
+  fn inspect_from(pid: Pid, raw_args: &FcntlRawArgs) -> Self {
+    let FcntlRawArgs { cmd, arg, .. } = *raw_args;
+    if let Some(cmd) = Self::from_value_arg(cmd, arg) {
+      return cmd;
+    }
+    let lock = || InspectFromPid::inspect_from(pid, arg as AddressType);
+    match cmd {
+      libc::F_GETLK => Self::GetLk { lock: lock() },
+      libc::F_SETLK => Self::SetLk { lock: lock() },
+      libc::F_SETLKW => Self::SetLkw { lock: lock() },
+      libc::F_OFD_GETLK => Self::OfdGetLk { lock: lock() },
+      libc::F_OFD_SETLK => Self::OfdSetLk { lock: lock() },
+      libc::F_OFD_SETLKW => Self::OfdSetLkw { lock: lock() },
+      F_SETOWN_EX => Self::SetOwnEx {
+        owner: InspectFromPid::inspect_from(pid, arg as AddressType),
+      },
+      cmd => Self::Other { cmd, arg },
+    }
+  }
+}
+
+impl FcntlResult {
+  /// Decode the value returned as the syscall result by the commands that don't retrieve anything through `arg`.
+  pub fn from_syscall_result(cmd: c_int, syscall_result: isize) -> Self {
+    match cmd {
+      libc::F_GETFD => Self::FdFlags(FdFlag::from_bits_retain(syscall_result as c_int)),
+      libc::F_GETFL => Self::StatusFlags(OFlag::from_bits_retain(syscall_result as c_int)),
+      libc::F_GET_SEALS => Self::Seals(SealFlag::from_bits_retain(syscall_result as c_int)),
+      _ => Self::InSyscallResult,
+    }
+  }
+}
+
+impl InspectResultFromRawArgs<FcntlRawArgs> for InspectResult<FcntlResult> {
+  fn inspect_from(pid: Pid, raw_args: &FcntlRawArgs, syscall_result: isize) -> Self {
+    let address = raw_args.arg as AddressType;
+    match raw_args.cmd {
+      libc::F_GETLK | libc::F_OFD_GETLK => InspectResult::<flock>::inspect_from(pid, address)
+        .map(FcntlResult::Lock)
+        .map_err(|e| e.map_ptrace_failure(FcntlResult::Lock)),
+      F_GETOWN_EX => InspectResult::<f_owner_ex>::inspect_from(pid, address)
+        .map(FcntlResult::OwnerEx)
+        .map_err(|e| e.map_ptrace_failure(FcntlResult::OwnerEx)),
+      cmd => Ok(FcntlResult::from_syscall_result(cmd, syscall_result)),
+    }
+  }
+}
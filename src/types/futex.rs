@@ -0,0 +1,273 @@
+use enumflags2::{bitflags, BitFlags};
+use nix::libc::{self, c_int, timespec};
+
+use crate::{
+  AddressType, FutexRawArgs, InspectFromPid, InspectFromRawArgs, InspectResult, InspectResultFromRawArgs, Pid,
+};
+
+use super::futex_waitv;
+
+/// The flags that can be or-ed into the `futex_op` argument of futex.
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum FutexFlags {
+  /// FUTEX_PRIVATE_FLAG
+  Private = libc::FUTEX_PRIVATE_FLAG as u32,
+  /// FUTEX_CLOCK_REALTIME
+  ClockRealtime = libc::FUTEX_CLOCK_REALTIME as u32,
+}
+
+/// The decoded futex operation and the arguments it uses.
+///
+/// `val2` is only decoded as a timeout for operations that accept one,
+/// otherwise it is the number of waiters to requeue or wake.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum FutexOp {
+  /// FUTEX_WAIT, the timeout is relative even if FUTEX_CLOCK_REALTIME is set, unlike the other operations.
+  ///
+  /// FUTEX_CLOCK_REALTIME only selects the clock the timeout is measured against.
+  Wait {
+    val: u32,
+    timeout: InspectResult<Option<timespec>>,
+  },
+  /// FUTEX_WAKE
+  Wake { nr_wake: u32 },
+  /// FUTEX_FD, removed since Linux 2.6.26.
+  Fd { signal: u32 },
+  /// FUTEX_REQUEUE
+  Requeue {
+    nr_wake: u32,
+    nr_requeue: u32,
+    uaddr2: AddressType,
+  },
+  /// FUTEX_CMP_REQUEUE
+  CmpRequeue {
+    nr_wake: u32,
+    nr_requeue: u32,
+    uaddr2: AddressType,
+    val3: u32,
+  },
+  /// FUTEX_WAKE_OP
+  WakeOp {
+    nr_wake: u32,
+    nr_wake2: u32,
+    uaddr2: AddressType,
+    op: FutexWakeOp,
+  },
+  /// FUTEX_LOCK_PI, the timeout is absolute and measured against CLOCK_REALTIME.
+  LockPi { timeout: InspectResult<Option<timespec>> },
+  /// FUTEX_LOCK_PI2, the timeout is absolute and measured against CLOCK_REALTIME if FUTEX_CLOCK_REALTIME is set.
+  LockPi2 { timeout: InspectResult<Option<timespec>> },
+  /// FUTEX_TRYLOCK_PI
+  TrylockPi,
+  /// FUTEX_UNLOCK_PI
+  UnlockPi,
+  /// FUTEX_WAIT_BITSET, the timeout is absolute and measured against CLOCK_REALTIME if FUTEX_CLOCK_REALTIME is set.
+  WaitBitset {
+    val: u32,
+    timeout: InspectResult<Option<timespec>>,
+    bitset: u32,
+  },
+  /// FUTEX_WAKE_BITSET
+  WakeBitset { nr_wake: u32, bitset: u32 },
+  /// FUTEX_WAIT_REQUEUE_PI, the timeout is absolute and measured against CLOCK_REALTIME if FUTEX_CLOCK_REALTIME
+  /// is set.
+  WaitRequeuePi {
+    val: u32,
+    timeout: InspectResult<Option<timespec>>,
+    uaddr2: AddressType,
+  },
+  /// FUTEX_CMP_REQUEUE_PI
+  CmpRequeuePi {
+    nr_wake: u32,
+    nr_requeue: u32,
+    uaddr2: AddressType,
+    val3: u32,
+  },
+  /// Unknown futex command
+  Unknown {
+    cmd: c_int,
+    val: u32,
+    val2: usize,
+    uaddr2: AddressType,
+    val3: u32,
+  },
+}
+
+/// The operation encoded in `val3` of FUTEX_WAKE_OP.
+///
+/// The kernel performs `*uaddr2 = *uaddr2 <op> oparg` and wakes up to `nr_wake2` waiters on `uaddr2`
+/// if the old value compared with `cmparg` using `cmp` is true.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FutexWakeOp {
+  pub op: FutexAtomicOp,
+  /// FUTEX_OP_OPARG_SHIFT, use `1 << oparg` as the operand.
+  pub oparg_shift: bool,
+  pub cmp: FutexWakeOpCmp,
+  pub oparg: i32,
+  pub cmparg: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FutexAtomicOp {
+  /// FUTEX_OP_SET
+  Set,
+  /// FUTEX_OP_ADD
+  Add,
+  /// FUTEX_OP_OR
+  Or,
+  /// FUTEX_OP_ANDN
+  AndN,
+  /// FUTEX_OP_XOR
+  Xor,
+  Unknown(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FutexWakeOpCmp {
+  /// FUTEX_OP_CMP_EQ
+  Eq,
+  /// FUTEX_OP_CMP_NE
+  Ne,
+  /// FUTEX_OP_CMP_LT
+  Lt,
+  /// FUTEX_OP_CMP_LE
+  Le,
+  /// FUTEX_OP_CMP_GT
+  Gt,
+  /// FUTEX_OP_CMP_GE
+  Ge,
+  Unknown(u32),
+}
+
+impl From<u32> for FutexWakeOp {
+  fn from(val3: u32) -> Self {
+    let op = (val3 >> 28) & 0xf;
+    Self {
+      op: match (op & !(libc::FUTEX_OP_OPARG_SHIFT as u32)) as c_int {
+        libc::FUTEX_OP_SET => FutexAtomicOp::Set,
+        libc::FUTEX_OP_ADD => FutexAtomicOp::Add,
+        libc::FUTEX_OP_OR => FutexAtomicOp::Or,
+        libc::FUTEX_OP_ANDN => FutexAtomicOp::AndN,
+        libc::FUTEX_OP_XOR => FutexAtomicOp::Xor,
+        other => FutexAtomicOp::Unknown(other as u32),
+      },
+      oparg_shift: op & libc::FUTEX_OP_OPARG_SHIFT as u32 != 0,
+      cmp: match ((val3 >> 24) & 0xf) as c_int {
+        libc::FUTEX_OP_CMP_EQ => FutexWakeOpCmp::Eq,
+        libc::FUTEX_OP_CMP_NE => FutexWakeOpCmp::Ne,
+        libc::FUTEX_OP_CMP_LT => FutexWakeOpCmp::Lt,
+        libc::FUTEX_OP_CMP_LE => FutexWakeOpCmp::Le,
+        libc::FUTEX_OP_CMP_GT => FutexWakeOpCmp::Gt,
+        libc::FUTEX_OP_CMP_GE => FutexWakeOpCmp::Ge,
+        other => FutexWakeOpCmp::Unknown(other as u32),
+      },
+      // Both oparg and cmparg are sign-extended 12-bit values.
+      oparg: ((val3 << 8) as i32) >> 20,
+      cmparg: ((val3 << 20) as i32) >> 20,
+    }
+  }
+}
+
+/// The two futexes of futex_requeue.
+#[derive(Debug, Clone, PartialEq)]
+#[repr(C)]
+pub struct FutexRequeueWaiters {
+  /// The futex to wake or requeue waiters from.
+  pub source: futex_waitv,
+  /// The futex to requeue waiters to.
+  pub target: futex_waitv,
+}
+
+impl InspectFromRawArgs<FutexRawArgs> for FutexOp {
+  fn inspect_from(pid: Pid, raw_args: &FutexRawArgs) -> Self {
+    let FutexRawArgs {
+      futex_op,
+      val,
+      val2,
+      uaddr2,
+      val3,
+      ..
+    } = *raw_args;
+    let uaddr2 = uaddr2 as AddressType;
+    let timeout = || InspectFromPid::inspect_from(pid, val2 as AddressType);
+    match futex_op & libc::FUTEX_CMD_MASK {
+      libc::FUTEX_WAIT => Self::Wait {
+        val,
+        timeout: timeout(),
+      },
+      libc::FUTEX_WAKE => Self::Wake { nr_wake: val },
+      libc::FUTEX_FD => Self::Fd { signal: val },
+      libc::FUTEX_REQUEUE => Self::Requeue {
+        nr_wake: val,
+        nr_requeue: val2 as u32,
+        uaddr2,
+      },
+      libc::FUTEX_CMP_REQUEUE => Self::CmpRequeue {
+        nr_wake: val,
+        nr_requeue: val2 as u32,
+        uaddr2,
+        val3,
+      },
+      libc::FUTEX_WAKE_OP => Self::WakeOp {
+        nr_wake: val,
+        nr_wake2: val2 as u32,
+        uaddr2,
+        op: val3.into(),
+      },
+      libc::FUTEX_LOCK_PI => Self::LockPi { timeout: timeout() },
+      libc::FUTEX_LOCK_PI2 => Self::LockPi2 { timeout: timeout() },
+      libc::FUTEX_TRYLOCK_PI => Self::TrylockPi,
+      libc::FUTEX_UNLOCK_PI => Self::UnlockPi,
+      libc::FUTEX_WAIT_BITSET => Self::WaitBitset {
+        val,
+        timeout: timeout(),
+        bitset: val3,
+      },
+      libc::FUTEX_WAKE_BITSET => Self::WakeBitset {
+        nr_wake: val,
+        bitset: val3,
+      },
+      libc::FUTEX_WAIT_REQUEUE_PI => Self::WaitRequeuePi {
+        val,
+        timeout: timeout(),
+        uaddr2,
+      },
+      libc::FUTEX_CMP_REQUEUE_PI => Self::CmpRequeuePi {
+        nr_wake: val,
+        nr_requeue: val2 as u32,
+        uaddr2,
+        val3,
+      },
+      cmd => Self::Unknown {
+        cmd,
+        val,
+        val2,
+        uaddr2,
+        val3,
+      },
+    }
+  }
+}
+
+impl InspectFromRawArgs<FutexRawArgs> for BitFlags<FutexFlags> {
+  fn inspect_from(_pid: Pid, raw_args: &FutexRawArgs) -> Self {
+    BitFlags::from_bits_truncate(raw_args.futex_op as u32)
+  }
+}
+
+/// The futex word at `uaddr2`, only read for operations that use it.
+impl InspectResultFromRawArgs<FutexRawArgs> for InspectResult<Option<u32>> {
+  fn inspect_from(pid: Pid, raw_args: &FutexRawArgs, _syscall_result: isize) -> Self {
+    match raw_args.futex_op & libc::FUTEX_CMD_MASK {
+      libc::FUTEX_REQUEUE
+      | libc::FUTEX_CMP_REQUEUE
+      | libc::FUTEX_WAKE_OP
+      | libc::FUTEX_WAIT_REQUEUE_PI
+      | libc::FUTEX_CMP_REQUEUE_PI => InspectFromPid::inspect_from(pid, raw_args.uaddr2 as AddressType),
+      _ => Ok(None),
+    }
+  }
+}
@@ -0,0 +1,1081 @@
+use std::{collections::HashMap, os::fd::RawFd, path::PathBuf};
+
+use enumflags2::{bitflags, BitFlags};
+use nix::libc::{iovec, off_t, timespec};
+
+use crate::{
+  read_bytes, AddressType, InspectCountedFromPid, InspectError, InspectFromPid, InspectFromRawArgs, InspectResult,
+  InspectResultFromRawArgs, IoUringEnterRawArgs, IoUringRegisterRawArgs, Pid, StructFields, READ_SIZE_MAX, UIO_MAXIOV,
+};
+
+use super::{io_uring_params, read_sigset, SigSet};
+
+/// The mmap offset of the SQ ring
+const IORING_OFF_SQ_RING: off_t = 0;
+/// The mmap offset of the CQ ring
+const IORING_OFF_CQ_RING: off_t = 0x8000000;
+/// The mmap offset of the SQE array
+const IORING_OFF_SQES: off_t = 0x10000000;
+
+/// IORING_SETUP_SQE128, the SQEs are 128 bytes.
+const IORING_SETUP_SQE128: u32 = 1 << 10;
+/// IORING_SETUP_CQE32, the CQEs are 32 bytes.
+const IORING_SETUP_CQE32: u32 = 1 << 11;
+/// IORING_SETUP_NO_MMAP, the rings are allocated by the application.
+const IORING_SETUP_NO_MMAP: u32 = 1 << 14;
+/// IORING_SETUP_NO_SQARRAY, the SQ ring indexes the SQEs directly.
+const IORING_SETUP_NO_SQARRAY: u32 = 1 << 16;
+/// IORING_FEAT_SINGLE_MMAP, the SQ and CQ rings share the mapping at IORING_OFF_SQ_RING.
+const IORING_FEAT_SINGLE_MMAP: u32 = 1 << 0;
+
+int_enum! {
+  /// `enum io_uring_op`
+  pub enum IoUringOp: u8 {
+    Nop = 0,
+    Readv = 1,
+    Writev = 2,
+    Fsync = 3,
+    ReadFixed = 4,
+    WriteFixed = 5,
+    PollAdd = 6,
+    PollRemove = 7,
+    SyncFileRange = 8,
+    Sendmsg = 9,
+    Recvmsg = 10,
+    Timeout = 11,
+    TimeoutRemove = 12,
+    Accept = 13,
+    AsyncCancel = 14,
+    LinkTimeout = 15,
+    Connect = 16,
+    Fallocate = 17,
+    Openat = 18,
+    Close = 19,
+    FilesUpdate = 20,
+    Statx = 21,
+    Read = 22,
+    Write = 23,
+    Fadvise = 24,
+    Madvise = 25,
+    Send = 26,
+    Recv = 27,
+    Openat2 = 28,
+    EpollCtl = 29,
+    Splice = 30,
+    ProvideBuffers = 31,
+    RemoveBuffers = 32,
+    Tee = 33,
+    Shutdown = 34,
+    Renameat = 35,
+    Unlinkat = 36,
+    Mkdirat = 37,
+    Symlinkat = 38,
+    Linkat = 39,
+    MsgRing = 40,
+    Fsetxattr = 41,
+    Setxattr = 42,
+    Fgetxattr = 43,
+    Getxattr = 44,
+    Socket = 45,
+    UringCmd = 46,
+    SendZc = 47,
+    SendmsgZc = 48,
+    ReadMultishot = 49,
+    Waitid = 50,
+    FutexWait = 51,
+    FutexWake = 52,
+    FutexWaitv = 53,
+    FixedFdInstall = 54,
+    Ftruncate = 55,
+    Bind = 56,
+    Listen = 57,
+    RecvZc = 58,
+  }
+}
+
+/// The IOSQE_* flags of an SQE
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum IoUringSqeFlags {
+  /// IOSQE_FIXED_FILE, `fd` is an index into the registered files.
+  FixedFile = 1 << 0,
+  IoDrain = 1 << 1,
+  IoLink = 1 << 2,
+  IoHardlink = 1 << 3,
+  Async = 1 << 4,
+  BufferSelect = 1 << 5,
+  CqeSkipSuccess = 1 << 6,
+}
+
+/// The memory referenced by an SQE, depending on its opcode.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum IoUringSqeData {
+  /// The iovecs of IORING_OP_READV and IORING_OP_WRITEV, the kernel rejects more than UIO_MAXIOV (1024).
+  Iovecs(InspectResult<Vec<iovec>>),
+  /// The data written by IORING_OP_WRITE, IORING_OP_WRITE_FIXED, IORING_OP_SEND and IORING_OP_SEND_ZC,
+  /// truncated to 1 MiB.
+  Write(InspectResult<Vec<u8>>),
+  /// The path of IORING_OP_OPENAT, IORING_OP_OPENAT2, IORING_OP_STATX, IORING_OP_UNLINKAT and IORING_OP_MKDIRAT
+  Path(InspectResult<PathBuf>),
+  /// The opcode doesn't reference memory or it is not decoded yet.
+  None,
+}
+
+/// A decoded `struct io_uring_sqe`.
+///
+/// The fields shared by the unions of the SQE are named after their most common use.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoUringSqe {
+  pub opcode: IoUringOp,
+  pub flags: BitFlags<IoUringSqeFlags>,
+  pub ioprio: u16,
+  /// The fd, or the index of a registered file if IOSQE_FIXED_FILE is set
+  pub fd: RawFd,
+  /// `off`, or `addr2` for some opcodes
+  pub offset: u64,
+  /// `addr`, the buffer or the argument of the operation
+  pub addr: AddressType,
+  pub len: u32,
+  /// The per-opcode flags, e.g. `rw_flags`, `fsync_flags` or `poll32_events`
+  pub op_flags: u32,
+  /// Copied into the CQE of this request
+  pub user_data: u64,
+  /// `buf_index` or `buf_group`
+  pub buf_index: u16,
+  pub personality: u16,
+  /// `splice_fd_in`, `file_index` or `addr_len`
+  pub file_index: u32,
+  pub addr3: u64,
+  pub data: IoUringSqeData,
+}
+
+/// A decoded `struct io_uring_cqe`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoUringCqe {
+  /// The `user_data` of the SQE that completed
+  pub user_data: u64,
+  pub res: i32,
+  /// IORING_CQE_F_* flags
+  pub flags: u32,
+  /// The extra data of IORING_SETUP_CQE32 rings
+  pub big_cqe: Option<[u64; 2]>,
+}
+
+/// The SQEs submitted and the CQEs posted since the ring was last observed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoUringActivity {
+  pub submitted: Vec<IoUringSqe>,
+  pub completed: Vec<IoUringCqe>,
+  /// The entries that were overwritten before they could be observed
+  pub missed_submissions: u32,
+  pub missed_completions: u32,
+}
+
+impl IoUringSqe {
+  fn from_bytes(pid: Pid, bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    let opcode = IoUringOp::from(bytes[0]);
+    let offset = fields.u64(8);
+    let addr = fields.ptr(16);
+    let len = fields.u32(24);
+    Self {
+      opcode,
+      flags: BitFlags::from_bits_truncate(bytes[1]),
+      ioprio: fields.u16(2),
+      fd: fields.i32(4),
+      offset,
+      addr,
+      len,
+      op_flags: fields.u32(28),
+      user_data: fields.u64(32),
+      buf_index: fields.u16(40),
+      personality: fields.u16(42),
+      file_index: fields.u32(44),
+      addr3: fields.u64(48),
+      data: match opcode {
+        IoUringOp::Readv | IoUringOp::Writev => {
+          IoUringSqeData::Iovecs(InspectCountedFromPid::inspect_from(pid, addr, (len as usize).min(UIO_MAXIOV)))
+        }
+        IoUringOp::Write | IoUringOp::WriteFixed | IoUringOp::Send | IoUringOp::SendZc => {
+          IoUringSqeData::Write(read_bytes(pid, addr, (len as usize).min(READ_SIZE_MAX)))
+        }
+        IoUringOp::Openat | IoUringOp::Openat2 | IoUringOp::Statx | IoUringOp::Unlinkat | IoUringOp::Mkdirat => {
+          IoUringSqeData::Path(InspectFromPid::inspect_from(pid, addr))
+        }
+        _ => IoUringSqeData::None,
+      },
+    }
+  }
+}
+
+impl IoUringCqe {
+  fn from_bytes(bytes: &[u8], big: bool) -> Self {
+    let fields = StructFields(bytes);
+    Self {
+      user_data: fields.u64(0),
+      res: fields.i32(8),
+      flags: fields.u32(12),
+      big_cqe: big.then(|| [fields.u64(16), fields.u64(24)]),
+    }
+  }
+}
+
+/// The slots of the entries added to a ring of `entries` entries while its tail moved from `last_tail` to `tail`,
+/// oldest first, and the number of entries that were overwritten before they could be read.
+fn io_uring_ring_slots(last_tail: u32, tail: u32, entries: u32) -> (Vec<u32>, u32) {
+  let pending = tail.wrapping_sub(last_tail);
+  let missed = pending.saturating_sub(entries);
+  let first = tail.wrapping_sub(pending.min(entries));
+  let slots = (0..pending.min(entries))
+    .map(|i| first.wrapping_add(i) & (entries - 1))
+    .collect();
+  (slots, missed)
+}
+
+/// The state of a ring created by io_uring_setup
+#[derive(Debug, Clone)]
+struct IoUringRing {
+  params: io_uring_params,
+  sq_ring: Option<AddressType>,
+  cq_ring: Option<AddressType>,
+  sqes: Option<AddressType>,
+  /// The SQ tail at the last observation
+  sq_tail: u32,
+  /// The CQ tail at the last observation
+  cq_tail: u32,
+}
+
+impl IoUringRing {
+  /// Read a u32 of the ring, or the name of the field that couldn't be read.
+  fn read_u32(pid: Pid, ring: AddressType, offset: u32, field: &'static str) -> Result<u32, &'static str> {
+    InspectResult::<u32>::inspect_from(pid, unsafe { ring.byte_add(offset as usize) }).map_err(|_| field)
+  }
+
+  fn read_submissions(&mut self, pid: Pid) -> Result<(Vec<IoUringSqe>, u32), &'static str> {
+    let (Some(ring), Some(sqes)) = (self.sq_ring, self.sqes) else {
+      return Err("sq_ring");
+    };
+    let off = &self.params.sq_off;
+    let tail = Self::read_u32(pid, ring, off.tail, "tail")?;
+    let entries = self.params.sq_entries;
+    let (slots, missed) = io_uring_ring_slots(self.sq_tail, tail, entries);
+    let sqe_size = if self.params.flags & IORING_SETUP_SQE128 != 0 {
+      128
+    } else {
+      64
+    };
+    let mut submitted = Vec::with_capacity(slots.len());
+    for slot in slots {
+      let index = if self.params.flags & IORING_SETUP_NO_SQARRAY != 0 {
+        slot
+      } else {
+        Self::read_u32(pid, ring, off.array + slot * 4, "array")?
+      };
+      let bytes = read_bytes(pid, unsafe { sqes.byte_add((index & (entries - 1)) as usize * sqe_size) }, sqe_size)
+        .map_err(|_| "sqes")?;
+      submitted.push(IoUringSqe::from_bytes(pid, &bytes));
+    }
+    self.sq_tail = tail;
+    Ok((submitted, missed))
+  }
+
+  fn read_completions(&mut self, pid: Pid) -> Result<(Vec<IoUringCqe>, u32), &'static str> {
+    let Some(ring) = self.cq_ring else {
+      return Err("cq_ring");
+    };
+    let off = &self.params.cq_off;
+    let tail = Self::read_u32(pid, ring, off.tail, "tail")?;
+    let (slots, missed) = io_uring_ring_slots(self.cq_tail, tail, self.params.cq_entries);
+    let big = self.params.flags & IORING_SETUP_CQE32 != 0;
+    let cqe_size = if big { 32 } else { 16 };
+    let cqes = unsafe { ring.byte_add(off.cqes as usize) };
+    let mut completed = Vec::with_capacity(slots.len());
+    for slot in slots {
+      let bytes = read_bytes(pid, unsafe { cqes.byte_add(slot as usize * cqe_size) }, cqe_size).map_err(|_| "cqes")?;
+      completed.push(IoUringCqe::from_bytes(&bytes, big));
+    }
+    self.cq_tail = tail;
+    Ok((completed, missed))
+  }
+}
+
+/// Observes the operations submitted through io_uring, which bypass syscalls.
+///
+/// The observer tracks the rings created by io_uring_setup and where they are mapped in the tracee.
+/// It needs to be fed with the results of io_uring_setup, mmap and close of the tracees.
+/// The rings are keyed by the process that owns the fd table, so pass the thread group id as `pid`.
+#[derive(Debug, Default)]
+pub struct IoUringObserver {
+  rings: HashMap<(Pid, RawFd), IoUringRing>,
+}
+
+impl IoUringObserver {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Track a ring after io_uring_setup returns `fd` and fills `params`.
+  pub fn on_setup(&mut self, pid: Pid, fd: RawFd, params: &io_uring_params) {
+    let no_mmap = params.flags & IORING_SETUP_NO_MMAP != 0;
+    let user_addr = |addr: u64| no_mmap.then_some(addr as AddressType);
+    self.rings.insert(
+      (pid, fd),
+      IoUringRing {
+        params: params.clone(),
+        sq_ring: user_addr(params.cq_off.user_addr),
+        cq_ring: user_addr(params.cq_off.user_addr),
+        sqes: user_addr(params.sq_off.user_addr),
+        sq_tail: 0,
+        cq_tail: 0,
+      },
+    );
+  }
+
+  /// Record where a region of a ring is mapped after mmap of `fd` at `offset` returns `addr`.
+  pub fn on_mmap(&mut self, pid: Pid, fd: RawFd, offset: off_t, addr: AddressType) {
+    let Some(ring) = self.rings.get_mut(&(pid, fd)) else {
+      return;
+    };
+    match offset {
+      IORING_OFF_SQ_RING => {
+        ring.sq_ring = Some(addr);
+        if ring.params.features & IORING_FEAT_SINGLE_MMAP != 0 {
+          ring.cq_ring = Some(addr);
+        }
+      }
+      IORING_OFF_CQ_RING => ring.cq_ring = Some(addr),
+      IORING_OFF_SQES => ring.sqes = Some(addr),
+      _ => {}
+    }
+  }
+
+  /// Stop tracking a ring after its fd is closed.
+  pub fn on_close(&mut self, pid: Pid, fd: RawFd) {
+    self.rings.remove(&(pid, fd));
+  }
+
+  /// Stop tracking all rings of a process, e.g. after it exits or execs.
+  pub fn forget_process(&mut self, pid: Pid) {
+    self.rings.retain(|&(p, _), _| p != pid);
+  }
+
+  /// Read the SQEs submitted and the CQEs posted since the last observation of the ring `fd`.
+  ///
+  /// Call it on the exit of io_uring_enter so that the completions of this call are included.
+  /// Returns `None` if `fd` is not a tracked ring.
+  pub fn on_enter(&mut self, pid: Pid, fd: RawFd) -> Option<InspectResult<IoUringActivity>> {
+    let ring = self.rings.get_mut(&(pid, fd))?;
+    let activity = ring.read_submissions(pid).and_then(|(submitted, missed_submissions)| {
+      let (completed, missed_completions) = ring.read_completions(pid)?;
+      Ok(IoUringActivity {
+        submitted,
+        completed,
+        missed_submissions,
+        missed_completions,
+      })
+    });
+    Some(activity.map_err(|field| InspectError::DependencyInspectFailure { field }))
+  }
+}
+
+/// The IORING_ENTER_* flags of io_uring_enter
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum IoUringEnterFlags {
+  GetEvents = 1 << 0,
+  SqWakeup = 1 << 1,
+  SqWait = 1 << 2,
+  /// IORING_ENTER_EXT_ARG, `arg` is a `struct io_uring_getevents_arg`.
+  ExtArg = 1 << 3,
+  /// IORING_ENTER_REGISTERED_RING, `fd` is the index of a registered ring fd.
+  RegisteredRing = 1 << 4,
+  AbsTimer = 1 << 5,
+  /// IORING_ENTER_EXT_ARG_REG, `arg` is an offset into the registered wait regions.
+  ExtArgReg = 1 << 6,
+  NoIowait = 1 << 7,
+}
+
+/// The `arg` of io_uring_enter, decoded according to its flags.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum IoUringEnterArg {
+  /// `arg` is NULL.
+  None,
+  /// Without IORING_ENTER_EXT_ARG, `arg` is the signal mask of `argsz` bytes to use while waiting.
+  Sigmask(InspectResult<SigSet>),
+  /// `struct io_uring_getevents_arg` of IORING_ENTER_EXT_ARG
+  GetEvents {
+    /// The signal mask of `sigmask_sz` bytes
+    sigmask: InspectResult<Option<SigSet>>,
+    sigmask_sz: u32,
+    min_wait_usec: u32,
+    ts: Option<InspectResult<timespec>>,
+  },
+  /// IORING_ENTER_EXT_ARG_REG, the offset of a `struct io_uring_reg_wait` in the registered region
+  RegisteredWait { offset: usize },
+}
+
+impl InspectFromRawArgs<IoUringEnterRawArgs> for BitFlags<IoUringEnterFlags> {
+  fn inspect_from(_pid: Pid, raw_args: &IoUringEnterRawArgs) -> Self {
+    BitFlags::from_bits_truncate(raw_args.flags)
+  }
+}
+
+impl IoUringEnterArg {
+  fn from_getevents_arg(pid: Pid, bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    let sigmask = fields.ptr(0);
+    let ts = fields.ptr(16);
+    let sigmask_sz = fields.u32(8);
+    Self::GetEvents {
+      sigmask: read_sigset(pid, sigmask, sigmask_sz as usize),
+      sigmask_sz,
+      min_wait_usec: fields.u32(12),
+      ts: (!ts.is_null()).then(|| InspectFromPid::inspect_from(pid, ts)),
+    }
+  }
+}
+
+impl InspectFromRawArgs<IoUringEnterRawArgs> for InspectResult<IoUringEnterArg> {
+  fn inspect_from(pid: Pid, raw_args: &IoUringEnterRawArgs) -> Self {
+    let flags = BitFlags::<IoUringEnterFlags>::from_bits_truncate(raw_args.flags);
+    let arg = raw_args.arg;
+    if flags.contains(IoUringEnterFlags::ExtArgReg) {
+      return Ok(IoUringEnterArg::RegisteredWait { offset: arg as usize });
+    }
+    if arg.is_null() {
+      return Ok(IoUringEnterArg::None);
+    }
+    if flags.contains(IoUringEnterFlags::ExtArg) {
+      // struct io_uring_getevents_arg
+      read_bytes(pid, arg, 24)
+        .map(|bytes| IoUringEnterArg::from_getevents_arg(pid, &bytes))
+        .map_err(|e| e.map_ptrace_failure(|bytes| IoUringEnterArg::from_getevents_arg(pid, &bytes)))
+    } else {
+      Ok(IoUringEnterArg::Sigmask(
+        read_sigset(pid, arg, raw_args.argsz)
+          .map(Option::unwrap_or_default)
+          .map_err(|e| e.map_ptrace_failure(Option::unwrap_or_default)),
+      ))
+    }
+  }
+}
+
+/// IORING_REGISTER_USE_REGISTERED_RING, `fd` of io_uring_register is the index of a registered ring fd.
+const IORING_REGISTER_USE_REGISTERED_RING: u32 = 1 << 31;
+/// IO_URING_OP_SUPPORTED, the flag of a supported opcode in `struct io_uring_probe_op`
+const IO_URING_OP_SUPPORTED: u16 = 1 << 0;
+
+int_enum! {
+  /// The opcodes of io_uring_register
+  pub enum IoUringRegisterOpcode: u32 {
+    RegisterBuffers = 0,
+    UnregisterBuffers = 1,
+    RegisterFiles = 2,
+    UnregisterFiles = 3,
+    RegisterEventfd = 4,
+    UnregisterEventfd = 5,
+    RegisterFilesUpdate = 6,
+    RegisterEventfdAsync = 7,
+    RegisterProbe = 8,
+    RegisterPersonality = 9,
+    UnregisterPersonality = 10,
+    RegisterRestrictions = 11,
+    RegisterEnableRings = 12,
+    RegisterFiles2 = 13,
+    RegisterFilesUpdate2 = 14,
+    RegisterBuffers2 = 15,
+    RegisterBuffersUpdate = 16,
+    RegisterIowqAff = 17,
+    UnregisterIowqAff = 18,
+    RegisterIowqMaxWorkers = 19,
+    RegisterRingFds = 20,
+    UnregisterRingFds = 21,
+    RegisterPbufRing = 22,
+    UnregisterPbufRing = 23,
+    RegisterSyncCancel = 24,
+    RegisterFileAllocRange = 25,
+    RegisterPbufStatus = 26,
+    RegisterNapi = 27,
+    UnregisterNapi = 28,
+    RegisterClock = 29,
+    RegisterCloneBuffers = 30,
+    RegisterSendMsgRing = 31,
+    RegisterZcrxIfq = 32,
+    RegisterResizeRings = 33,
+    RegisterMemRegion = 34,
+  }
+}
+
+/// A decoded `struct io_uring_restriction`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum IoUringRestriction {
+  /// IORING_RESTRICTION_REGISTER_OP
+  RegisterOp(IoUringRegisterOpcode),
+  /// IORING_RESTRICTION_SQE_OP
+  SqeOp(IoUringOp),
+  /// IORING_RESTRICTION_SQE_FLAGS_ALLOWED
+  SqeFlagsAllowed(BitFlags<IoUringSqeFlags>),
+  /// IORING_RESTRICTION_SQE_FLAGS_REQUIRED
+  SqeFlagsRequired(BitFlags<IoUringSqeFlags>),
+  Unknown {
+    opcode: u16,
+    value: u8,
+  },
+}
+
+impl IoUringRestriction {
+  fn from_bytes(bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    let value = bytes[2];
+    match fields.u16(0) {
+      0 => Self::RegisterOp((value as u32).into()),
+      1 => Self::SqeOp(value.into()),
+      2 => Self::SqeFlagsAllowed(BitFlags::from_bits_truncate(value)),
+      3 => Self::SqeFlagsRequired(BitFlags::from_bits_truncate(value)),
+      opcode => Self::Unknown { opcode, value },
+    }
+  }
+}
+
+/// A decoded `struct io_uring_buf_reg`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IoUringBufReg {
+  pub ring_addr: AddressType,
+  pub ring_entries: u32,
+  pub bgid: u16,
+  /// IOU_PBUF_RING_* flags
+  pub flags: u16,
+}
+
+impl IoUringBufReg {
+  fn from_bytes(bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    Self {
+      ring_addr: fields.ptr(0),
+      ring_entries: fields.u32(8),
+      bgid: fields.u16(12),
+      flags: fields.u16(14),
+    }
+  }
+}
+
+/// The `arg` of io_uring_register, decoded according to its opcode.
+///
+/// IORING_REGISTER_PROBE fills its argument, the result is available in [`IoUringRegisterResult`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum IoUringRegisterOp {
+  /// IORING_REGISTER_BUFFERS
+  RegisterBuffers { iovecs: InspectResult<Vec<iovec>> },
+  /// IORING_UNREGISTER_BUFFERS
+  UnregisterBuffers,
+  /// IORING_REGISTER_FILES, -1 is a sparse slot.
+  RegisterFiles { fds: InspectResult<Vec<RawFd>> },
+  /// IORING_UNREGISTER_FILES
+  UnregisterFiles,
+  /// IORING_REGISTER_FILES_UPDATE, the registered files from `offset` are replaced by `fds`.
+  FilesUpdate {
+    offset: u32,
+    fds: InspectResult<Vec<RawFd>>,
+  },
+  /// IORING_REGISTER_EVENTFD
+  RegisterEventfd { fd: InspectResult<RawFd> },
+  /// IORING_REGISTER_EVENTFD_ASYNC
+  RegisterEventfdAsync { fd: InspectResult<RawFd> },
+  /// IORING_UNREGISTER_EVENTFD
+  UnregisterEventfd,
+  /// IORING_REGISTER_PROBE, `nr_ops` is the capacity of the probe.
+  Probe { nr_ops: u32 },
+  /// IORING_REGISTER_PERSONALITY, the id of the personality is the syscall result.
+  RegisterPersonality,
+  /// IORING_UNREGISTER_PERSONALITY
+  UnregisterPersonality { id: u32 },
+  /// IORING_REGISTER_RESTRICTIONS
+  RegisterRestrictions {
+    restrictions: InspectResult<Vec<IoUringRestriction>>,
+  },
+  /// IORING_REGISTER_ENABLE_RINGS
+  EnableRings,
+  /// IORING_REGISTER_PBUF_RING
+  RegisterPbufRing { reg: InspectResult<IoUringBufReg> },
+  /// IORING_UNREGISTER_PBUF_RING
+  UnregisterPbufRing { reg: InspectResult<IoUringBufReg> },
+  /// IORING_REGISTER_RING_FDS, the `offset` and `data` of each `struct io_uring_rsrc_update`
+  RegisterRingFds { updates: InspectResult<Vec<(u32, u32)>> },
+  /// IORING_REGISTER_IOWQ_MAX_WORKERS, the bounded and unbounded worker limits
+  IowqMaxWorkers { counts: InspectResult<Vec<u32>> },
+  /// The opcodes that are not decoded yet.
+  Other {
+    opcode: IoUringRegisterOpcode,
+    arg: AddressType,
+    nr_args: u32,
+  },
+}
+
+/// The decoded opcode and arg of io_uring_register
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoUringRegister {
+  pub opcode: IoUringRegisterOpcode,
+  /// IORING_REGISTER_USE_REGISTERED_RING, `fd` is the index of a registered ring fd.
+  pub use_registered_ring: bool,
+  pub op: IoUringRegisterOp,
+}
+
+/// A decoded `struct io_uring_probe`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoUringProbe {
+  /// The last opcode supported by the kernel
+  pub last_op: IoUringOp,
+  /// The opcodes and whether they are supported
+  pub ops: Vec<(IoUringOp, bool)>,
+}
+
+impl IoUringProbe {
+  fn from_bytes(bytes: &[u8]) -> Self {
+    let ops_len = bytes[1] as usize;
+    Self {
+      last_op: bytes[0].into(),
+      ops: bytes[16..]
+        .chunks_exact(8)
+        .take(ops_len)
+        .map(|op| (op[0].into(), StructFields(op).u16(2) & IO_URING_OP_SUPPORTED != 0))
+        .collect(),
+    }
+  }
+}
+
+/// The values retrieved by io_uring_register
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum IoUringRegisterResult {
+  /// IORING_REGISTER_PROBE
+  Probe(IoUringProbe),
+  /// IORING_REGISTER_RING_FDS, the `offset` of each registered ring fd
+  RingFds(Vec<u32>),
+  /// IORING_REGISTER_IOWQ_MAX_WORKERS, the previous limits
+  IowqMaxWorkers(Vec<u32>),
+  /// The opcode returns its value, if any, as the syscall result.
+  InSyscallResult,
+}
+
+/// Read `nr` structs of `size` bytes at `address` and decode them.
+fn read_struct_array<T: Clone + PartialEq>(
+  pid: Pid,
+  address: AddressType,
+  nr: usize,
+  size: usize,
+  decode: impl Fn(&[u8]) -> T,
+) -> InspectResult<Vec<T>> {
+  let decode_all = |bytes: Vec<u8>| bytes.chunks_exact(size).map(&decode).collect::<Vec<_>>();
+  read_bytes(pid, address, nr * size)
+    .map(decode_all)
+    .map_err(|e| e.map_ptrace_failure(decode_all))
+}
+
+/// The upper bound of the entries read for an opcode, the kernel rejects more than this.
+const IORING_MAX_REG_ENTRIES: u32 = 1 << 16;
+
+impl InspectFromRawArgs<IoUringRegisterRawArgs> for IoUringRegister {
+  fn inspect_from(pid: Pid, raw_args: &IoUringRegisterRawArgs) -> Self {
+    let IoUringRegisterRawArgs { arg, nr_args, .. } = *raw_args;
+    let opcode = IoUringRegisterOpcode::from(raw_args.op & !IORING_REGISTER_USE_REGISTERED_RING);
+    let nr = nr_args.min(IORING_MAX_REG_ENTRIES) as usize;
+    let buf_reg = || {
+      read_bytes(pid, arg, 40)
+        .map(|bytes| IoUringBufReg::from_bytes(&bytes))
+        .map_err(|e| e.map_ptrace_failure(|bytes| IoUringBufReg::from_bytes(&bytes)))
+    };
+    let op = match opcode {
+      IoUringRegisterOpcode::RegisterBuffers => IoUringRegisterOp::RegisterBuffers {
+        iovecs: InspectCountedFromPid::inspect_from(pid, arg, nr),
+      },
+      IoUringRegisterOpcode::UnregisterBuffers => IoUringRegisterOp::UnregisterBuffers,
+      IoUringRegisterOpcode::RegisterFiles => IoUringRegisterOp::RegisterFiles {
+        fds: InspectCountedFromPid::inspect_from(pid, arg, nr),
+      },
+      IoUringRegisterOpcode::UnregisterFiles => IoUringRegisterOp::UnregisterFiles,
+      IoUringRegisterOpcode::RegisterFilesUpdate => match read_bytes(pid, arg, 16) {
+        // struct io_uring_files_update
+        Ok(bytes) => IoUringRegisterOp::FilesUpdate {
+          offset: StructFields(&bytes).u32(0),
+          fds: InspectCountedFromPid::inspect_from(pid, StructFields(&bytes).ptr(8), nr),
+        },
+        Err(_) => IoUringRegisterOp::FilesUpdate {
+          offset: 0,
+          fds: Err(InspectError::DependencyInspectFailure { field: "arg" }),
+        },
+      },
+      IoUringRegisterOpcode::RegisterEventfd => IoUringRegisterOp::RegisterEventfd {
+        fd: InspectFromPid::inspect_from(pid, arg),
+      },
+      IoUringRegisterOpcode::RegisterEventfdAsync => IoUringRegisterOp::RegisterEventfdAsync {
+        fd: InspectFromPid::inspect_from(pid, arg),
+      },
+      IoUringRegisterOpcode::UnregisterEventfd => IoUringRegisterOp::UnregisterEventfd,
+      IoUringRegisterOpcode::RegisterProbe => IoUringRegisterOp::Probe { nr_ops: nr_args },
+      IoUringRegisterOpcode::RegisterPersonality => IoUringRegisterOp::RegisterPersonality,
+      IoUringRegisterOpcode::UnregisterPersonality => IoUringRegisterOp::UnregisterPersonality { id: nr_args },
+      IoUringRegisterOpcode::RegisterRestrictions => IoUringRegisterOp::RegisterRestrictions {
+        restrictions: read_struct_array(pid, arg, nr, 16, IoUringRestriction::from_bytes),
+      },
+      IoUringRegisterOpcode::RegisterEnableRings => IoUringRegisterOp::EnableRings,
+      IoUringRegisterOpcode::RegisterPbufRing => IoUringRegisterOp::RegisterPbufRing { reg: buf_reg() },
+      IoUringRegisterOpcode::UnregisterPbufRing => IoUringRegisterOp::UnregisterPbufRing { reg: buf_reg() },
+      IoUringRegisterOpcode::RegisterRingFds => IoUringRegisterOp::RegisterRingFds {
+        updates: read_struct_array(pid, arg, nr, 16, |bytes| {
+          let fields = StructFields(bytes);
+          (fields.u32(0), fields.u64(8) as u32)
+        }),
+      },
+      IoUringRegisterOpcode::RegisterIowqMaxWorkers => IoUringRegisterOp::IowqMaxWorkers {
+        counts: InspectCountedFromPid::inspect_from(pid, arg, 2),
+      },
+      opcode => IoUringRegisterOp::Other { opcode, arg, nr_args },
+    };
+    Self {
+      opcode,
+      use_registered_ring: raw_args.op & IORING_REGISTER_USE_REGISTERED_RING != 0,
+      op,
+    }
+  }
+}
+
+impl InspectResultFromRawArgs<IoUringRegisterRawArgs> for InspectResult<IoUringRegisterResult> {
+  fn inspect_from(pid: Pid, raw_args: &IoUringRegisterRawArgs, _syscall_result: isize) -> Self {
+    let IoUringRegisterRawArgs { arg, nr_args, .. } = *raw_args;
+    let nr = nr_args.min(IORING_MAX_REG_ENTRIES) as usize;
+    match IoUringRegisterOpcode::from(raw_args.op & !IORING_REGISTER_USE_REGISTERED_RING) {
+      IoUringRegisterOpcode::RegisterProbe => {
+        let nr_ops = nr_args.min(u8::MAX as u32 + 1) as usize;
+        read_bytes(pid, arg, 16 + nr_ops * 8)
+          .map(|bytes| IoUringRegisterResult::Probe(IoUringProbe::from_bytes(&bytes)))
+          .map_err(|e| e.map_ptrace_failure(|bytes| IoUringRegisterResult::Probe(IoUringProbe::from_bytes(&bytes))))
+      }
+      IoUringRegisterOpcode::RegisterRingFds => read_struct_array(pid, arg, nr, 16, |bytes| StructFields(bytes).u32(0))
+        .map(IoUringRegisterResult::RingFds)
+        .map_err(|e| e.map_ptrace_failure(IoUringRegisterResult::RingFds)),
+      IoUringRegisterOpcode::RegisterIowqMaxWorkers => InspectResult::<Vec<u32>>::inspect_from(pid, arg, 2)
+        .map(IoUringRegisterResult::IowqMaxWorkers)
+        .map_err(|e| e.map_ptrace_failure(IoUringRegisterResult::IowqMaxWorkers)),
+      _ => Ok(IoUringRegisterResult::InSyscallResult),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A CQ ring of `entries` 16-byte CQEs whose `user_data` is the position last written to each slot.
+  fn cq_ring(entries: u32, tail: u32) -> Vec<u8> {
+    let mut bytes = vec![0u8; entries as usize * 16];
+    for position in (0..entries).map(|i| tail.wrapping_sub(entries).wrapping_add(i)) {
+      let slot = (position & (entries - 1)) as usize;
+      bytes[slot * 16..slot * 16 + 8].copy_from_slice(&(position as u64).to_ne_bytes());
+      bytes[slot * 16 + 8..slot * 16 + 12].copy_from_slice(&(position as i32).to_ne_bytes());
+    }
+    bytes
+  }
+
+  fn read_cqes(ring: &[u8], slots: &[u32]) -> Vec<u64> {
+    slots
+      .iter()
+      .map(|&slot| IoUringCqe::from_bytes(&ring[slot as usize * 16..][..16], false).user_data)
+      .collect()
+  }
+
+  #[test]
+  fn test_ring_slots_nothing_new() {
+    assert_eq!(io_uring_ring_slots(5, 5, 8), (vec![], 0));
+  }
+
+  #[test]
+  fn test_ring_slots_wrap_around_the_ring() {
+    let (slots, missed) = io_uring_ring_slots(6, 11, 8);
+    assert_eq!((slots.as_slice(), missed), ([6, 7, 0, 1, 2].as_slice(), 0));
+    assert_eq!(read_cqes(&cq_ring(8, 11), &slots), vec![6, 7, 8, 9, 10]);
+  }
+
+  #[test]
+  fn test_ring_slots_missed_entries() {
+    // 13 entries were posted to a ring of 8, the oldest 5 were overwritten.
+    let (slots, missed) = io_uring_ring_slots(2, 15, 8);
+    assert_eq!(missed, 5);
+    assert_eq!(slots, vec![7, 0, 1, 2, 3, 4, 5, 6]);
+    assert_eq!(read_cqes(&cq_ring(8, 15), &slots), (7..15).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn test_ring_slots_tail_wraps_u32() {
+    let (slots, missed) = io_uring_ring_slots(u32::MAX - 1, 2, 4);
+    assert_eq!(missed, 0);
+    assert_eq!(slots, vec![2, 3, 0, 1]);
+    assert_eq!(read_cqes(&cq_ring(4, 2), &slots), vec![u32::MAX as u64 - 1, u32::MAX as u64, 0, 1]);
+  }
+
+  #[test]
+  fn test_cqe_decoding() {
+    let mut bytes = vec![0u8; 32];
+    bytes[0..8].copy_from_slice(&42u64.to_ne_bytes());
+    bytes[8..12].copy_from_slice(&(-11i32).to_ne_bytes());
+    bytes[12..16].copy_from_slice(&2u32.to_ne_bytes());
+    bytes[16..24].copy_from_slice(&7u64.to_ne_bytes());
+    assert_eq!(
+      IoUringCqe::from_bytes(&bytes, true),
+      IoUringCqe {
+        user_data: 42,
+        res: -11,
+        flags: 2,
+        big_cqe: Some([7, 0]),
+      }
+    );
+    assert_eq!(IoUringCqe::from_bytes(&bytes[..16], false).big_cqe, None);
+  }
+
+  #[test]
+  fn test_sqe_buffers_are_capped() {
+    let iov = vec![
+      iovec {
+        iov_base: std::ptr::null_mut(),
+        iov_len: 0,
+      };
+      2048
+    ];
+    let mut bytes = vec![0u8; 64];
+    bytes[0] = IoUringOp::Writev.into();
+    bytes[16..24].copy_from_slice(&(iov.as_ptr() as u64).to_ne_bytes());
+    bytes[24..28].copy_from_slice(&u32::MAX.to_ne_bytes());
+    let sqe = IoUringSqe::from_bytes(Pid::this(), &bytes);
+    let IoUringSqeData::Iovecs(Ok(iovecs)) = sqe.data else {
+      panic!("iovecs not read: {:?}", sqe.data);
+    };
+    assert_eq!(iovecs.len(), UIO_MAXIOV);
+    assert_eq!(sqe.len, u32::MAX);
+  }
+
+  fn enter_arg(flags: u32, arg: &[u8], argsz: usize) -> InspectResult<IoUringEnterArg> {
+    let raw_args = IoUringEnterRawArgs {
+      fd: 3,
+      to_submit: 0,
+      min_complete: 1,
+      flags,
+      arg: arg.as_ptr() as AddressType,
+      argsz,
+    };
+    InspectFromRawArgs::inspect_from(Pid::this(), &raw_args)
+  }
+
+  fn register_raw_args(op: u32, arg: &[u8], nr_args: u32) -> IoUringRegisterRawArgs {
+    IoUringRegisterRawArgs {
+      fd: 3,
+      op,
+      arg: arg.as_ptr() as AddressType,
+      nr_args,
+    }
+  }
+
+  fn register(op: u32, arg: &[u8], nr_args: u32) -> IoUringRegister {
+    InspectFromRawArgs::inspect_from(Pid::this(), &register_raw_args(op, arg, nr_args))
+  }
+
+  #[test]
+  fn test_enter_arg_sigmask() {
+    let mask = [0xa5u8; 16];
+    assert_eq!(
+      enter_arg(IoUringEnterFlags::GetEvents as u32, &mask, 16),
+      Ok(IoUringEnterArg::Sigmask(Ok(SigSet(mask.to_vec()))))
+    );
+  }
+
+  #[test]
+  fn test_enter_arg_null() {
+    let raw_args = IoUringEnterRawArgs {
+      fd: 3,
+      to_submit: 0,
+      min_complete: 0,
+      flags: IoUringEnterFlags::ExtArg as u32,
+      arg: std::ptr::null_mut(),
+      argsz: 24,
+    };
+    assert_eq!(InspectResult::<IoUringEnterArg>::inspect_from(Pid::this(), &raw_args), Ok(IoUringEnterArg::None));
+  }
+
+  #[test]
+  fn test_enter_arg_getevents() {
+    let mask = [0x5au8; 16];
+    let ts = timespec {
+      tv_sec: 2,
+      tv_nsec: 500,
+    };
+    let mut bytes = vec![0u8; 24];
+    bytes[0..8].copy_from_slice(&(mask.as_ptr() as u64).to_ne_bytes());
+    bytes[8..12].copy_from_slice(&16u32.to_ne_bytes());
+    bytes[12..16].copy_from_slice(&100u32.to_ne_bytes());
+    bytes[16..24].copy_from_slice(&(&ts as *const timespec as u64).to_ne_bytes());
+    assert_eq!(
+      enter_arg(IoUringEnterFlags::ExtArg as u32, &bytes, 24),
+      Ok(IoUringEnterArg::GetEvents {
+        sigmask: Ok(Some(SigSet(mask.to_vec()))),
+        sigmask_sz: 16,
+        min_wait_usec: 100,
+        ts: Some(Ok(ts)),
+      })
+    );
+  }
+
+  #[test]
+  fn test_enter_arg_getevents_without_sigmask_and_timeout() {
+    let mut bytes = vec![0u8; 24];
+    bytes[8..12].copy_from_slice(&8u32.to_ne_bytes());
+    assert_eq!(
+      IoUringEnterArg::from_getevents_arg(Pid::this(), &bytes),
+      IoUringEnterArg::GetEvents {
+        sigmask: Ok(None),
+        sigmask_sz: 8,
+        min_wait_usec: 0,
+        ts: None,
+      }
+    );
+  }
+
+  #[test]
+  fn test_enter_arg_registered_wait() {
+    let raw_args = IoUringEnterRawArgs {
+      fd: 0,
+      to_submit: 0,
+      min_complete: 1,
+      flags: (IoUringEnterFlags::GetEvents | IoUringEnterFlags::ExtArgReg).bits(),
+      arg: 64 as AddressType,
+      argsz: 0,
+    };
+    assert_eq!(
+      InspectResult::<IoUringEnterArg>::inspect_from(Pid::this(), &raw_args),
+      Ok(IoUringEnterArg::RegisteredWait { offset: 64 })
+    );
+  }
+
+  #[test]
+  fn test_restriction_decoding() {
+    let mut bytes = vec![0u8; 5 * 16];
+    for (i, (opcode, value)) in [(0u16, 6u8), (1, 2), (2, 0b101), (3, 1), (9, 4)]
+      .into_iter()
+      .enumerate()
+    {
+      bytes[i * 16..i * 16 + 2].copy_from_slice(&opcode.to_ne_bytes());
+      bytes[i * 16 + 2] = value;
+    }
+    let reg = register(IoUringRegisterOpcode::RegisterRestrictions.into(), &bytes, 5);
+    assert_eq!(reg.opcode, IoUringRegisterOpcode::RegisterRestrictions);
+    assert!(!reg.use_registered_ring);
+    assert_eq!(
+      reg.op,
+      IoUringRegisterOp::RegisterRestrictions {
+        restrictions: Ok(vec![
+          IoUringRestriction::RegisterOp(IoUringRegisterOpcode::RegisterFilesUpdate),
+          IoUringRestriction::SqeOp(IoUringOp::Writev),
+          IoUringRestriction::SqeFlagsAllowed(IoUringSqeFlags::FixedFile | IoUringSqeFlags::IoLink),
+          IoUringRestriction::SqeFlagsRequired(IoUringSqeFlags::FixedFile.into()),
+          IoUringRestriction::Unknown { opcode: 9, value: 4 },
+        ])
+      }
+    );
+  }
+
+  #[test]
+  fn test_buf_reg_decoding() {
+    let mut bytes = vec![0u8; 40];
+    bytes[0..8].copy_from_slice(&0x7000u64.to_ne_bytes());
+    bytes[8..12].copy_from_slice(&32u32.to_ne_bytes());
+    bytes[12..14].copy_from_slice(&5u16.to_ne_bytes());
+    bytes[14..16].copy_from_slice(&1u16.to_ne_bytes());
+    let op = u32::from(IoUringRegisterOpcode::RegisterPbufRing) | IORING_REGISTER_USE_REGISTERED_RING;
+    let reg = register(op, &bytes, 1);
+    assert_eq!(reg.opcode, IoUringRegisterOpcode::RegisterPbufRing);
+    assert!(reg.use_registered_ring);
+    assert_eq!(
+      reg.op,
+      IoUringRegisterOp::RegisterPbufRing {
+        reg: Ok(IoUringBufReg {
+          ring_addr: 0x7000 as AddressType,
+          ring_entries: 32,
+          bgid: 5,
+          flags: 1,
+        })
+      }
+    );
+  }
+
+  #[test]
+  fn test_files_update_decoding() {
+    let mut bytes = vec![0u8; 16];
+    bytes[0..4].copy_from_slice(&7u32.to_ne_bytes());
+    bytes[8..16].copy_from_slice(&0xdead000u64.to_ne_bytes());
+    // No fds are read for an empty update, only the offset is decoded.
+    assert_eq!(
+      register(IoUringRegisterOpcode::RegisterFilesUpdate.into(), &bytes, 0).op,
+      IoUringRegisterOp::FilesUpdate {
+        offset: 7,
+        fds: Ok(vec![]),
+      }
+    );
+  }
+
+  #[test]
+  fn test_ring_fds_decoding() {
+    let mut bytes = vec![0u8; 2 * 16];
+    bytes[0..4].copy_from_slice(&u32::MAX.to_ne_bytes());
+    bytes[8..16].copy_from_slice(&4u64.to_ne_bytes());
+    bytes[16..20].copy_from_slice(&3u32.to_ne_bytes());
+    bytes[24..32].copy_from_slice(&9u64.to_ne_bytes());
+    let op = IoUringRegisterOpcode::RegisterRingFds.into();
+    assert_eq!(
+      register(op, &bytes, 2).op,
+      IoUringRegisterOp::RegisterRingFds {
+        updates: Ok(vec![(u32::MAX, 4), (3, 9)])
+      }
+    );
+    // The kernel writes the allocated offsets back.
+    assert_eq!(
+      InspectResult::<IoUringRegisterResult>::inspect_from(Pid::this(), &register_raw_args(op, &bytes, 2), 2),
+      Ok(IoUringRegisterResult::RingFds(vec![u32::MAX, 3]))
+    );
+  }
+
+  #[test]
+  fn test_probe_decoding() {
+    let mut bytes = vec![0u8; 16 + 4 * 8];
+    bytes[0] = IoUringOp::Timeout.into();
+    bytes[1] = 3;
+    for (i, (op, flags)) in [
+      (IoUringOp::Nop, 1u16),
+      (IoUringOp::Readv, 0),
+      (IoUringOp::Writev, 1),
+      (IoUringOp::Fsync, 1),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+      bytes[16 + i * 8] = op.into();
+      bytes[16 + i * 8 + 2..16 + i * 8 + 4].copy_from_slice(&flags.to_ne_bytes());
+    }
+    let op = IoUringRegisterOpcode::RegisterProbe.into();
+    assert_eq!(register(op, &bytes, 4).op, IoUringRegisterOp::Probe { nr_ops: 4 });
+    // Only `ops_len` ops are filled by the kernel.
+    assert_eq!(
+      InspectResult::<IoUringRegisterResult>::inspect_from(Pid::this(), &register_raw_args(op, &bytes, 4), 0),
+      Ok(IoUringRegisterResult::Probe(IoUringProbe {
+        last_op: IoUringOp::Timeout,
+        ops: vec![
+          (IoUringOp::Nop, true),
+          (IoUringOp::Readv, false),
+          (IoUringOp::Writev, true)
+        ],
+      }))
+    );
+  }
+}
@@ -0,0 +1,340 @@
+use std::os::unix::fs::MetadataExt;
+
+use nix::libc::{self, c_int, c_ushort, msginfo, msqid_ds, shmid_ds};
+
+use crate::{
+  read_bytes, AddressType, InspectError, InspectFromPid, InspectFromRawArgs, InspectResult, InspectResultFromRawArgs,
+  MsgctlRawArgs, Pid, SemctlRawArgs, ShmctlRawArgs,
+};
+
+use super::{semid_ds, seminfo, shm_info, shminfo};
+
+/// IPC_64, the flag of the 64-bit ABI which is the only one on the supported architectures
+const IPC_64: c_int = 0x0100;
+// https://elixir.bootlin.com/linux/v6.11/source/include/uapi/linux/sem.h#L38
+const GETPID: c_int = 11;
+const GETVAL: c_int = 12;
+const GETALL: c_int = 13;
+const GETNCNT: c_int = 14;
+const GETZCNT: c_int = 15;
+const SETVAL: c_int = 16;
+const SETALL: c_int = 17;
+const SEM_STAT: c_int = 18;
+const SEM_INFO: c_int = 19;
+const SEM_STAT_ANY: c_int = 20;
+// https://elixir.bootlin.com/linux/v6.11/source/include/uapi/linux/shm.h#L62
+const SHM_LOCK: c_int = 11;
+const SHM_UNLOCK: c_int = 12;
+const SHM_STAT: c_int = 13;
+const SHM_INFO: c_int = 14;
+const SHM_STAT_ANY: c_int = 15;
+// https://elixir.bootlin.com/linux/v6.11/source/include/uapi/linux/msg.h#L10
+const MSG_STAT: c_int = 11;
+const MSG_INFO: c_int = 12;
+const MSG_STAT_ANY: c_int = 13;
+/// SEMMSL, the maximum number of semaphores in a set
+const SEMMSL_MAX: usize = 32000;
+
+/// The decoded `cmd` and `arg` of semctl.
+///
+/// Commands that retrieve something only carry their inputs here.
+/// The retrieved value is available in [`SemctlResult`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SemctlCmd {
+  /// IPC_RMID
+  Rmid,
+  /// IPC_SET
+  Set { buf: InspectResult<semid_ds> },
+  /// IPC_STAT
+  Stat,
+  /// IPC_INFO
+  Info,
+  /// SEM_INFO
+  SemInfo,
+  /// SEM_STAT, `semid` is an index into the kernel's array of sets.
+  SemStat,
+  /// SEM_STAT_ANY
+  SemStatAny,
+  /// GETPID
+  GetPid { semnum: c_int },
+  /// GETVAL
+  GetVal { semnum: c_int },
+  /// GETNCNT
+  GetNcnt { semnum: c_int },
+  /// GETZCNT
+  GetZcnt { semnum: c_int },
+  /// SETVAL
+  SetVal { semnum: c_int, val: c_int },
+  /// GETALL
+  GetAll,
+  /// SETALL
+  SetAll { values: InspectResult<Vec<c_ushort>> },
+  /// Commands that are not decoded yet.
+  Other { cmd: c_int, arg: usize },
+}
+
+/// The values retrieved by semctl through `arg`
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SemctlResult {
+  /// IPC_STAT, SEM_STAT and SEM_STAT_ANY
+  Stat(semid_ds),
+  /// IPC_INFO and SEM_INFO
+  Info(seminfo),
+  /// GETALL
+  Values(Vec<c_ushort>),
+  /// The command returns its value, if any, as the syscall result.
+  InSyscallResult,
+}
+
+/// The inode of the IPC namespace of `pid`, which semids are local to.
+fn ipc_namespace(pid: Pid) -> Option<u64> {
+  std::fs::metadata(format!("/proc/{pid}/ns/ipc")).ok().map(|ns| ns.ino())
+}
+
+/// The number of semaphores in the set `semid`, found in `/proc/sysvipc/sem`.
+///
+/// The file shows the sets of the IPC namespace of the tracer,
+/// so this fails for tracees in another IPC namespace.
+fn semaphore_count(pid: Pid, semid: c_int) -> Option<usize> {
+  if ipc_namespace(pid)? != ipc_namespace(Pid::this())? {
+    return None;
+  }
+  let sets = std::fs::read_to_string("/proc/sysvipc/sem").ok()?;
+  sets.lines().skip(1).find_map(|line| {
+    // key semid perms nsems uid gid ...
+    let mut columns = line.split_whitespace().skip(1);
+    (columns.next()?.parse::<c_int>().ok()? == semid).then(|| columns.nth(1)?.parse().ok())?
+  })
+}
+
+/// Read the semaphore values of the `array` of `union semun`.
+fn read_semaphore_values(pid: Pid, semid: c_int, array: AddressType) -> InspectResult<Vec<c_ushort>> {
+  let nsems = semaphore_count(pid, semid)
+    .ok_or(InspectError::DependencyInspectFailure { field: "nsems" })?
+    .min(SEMMSL_MAX);
+  let decode = |bytes: Vec<u8>| {
+    bytes
+      .chunks_exact(2)
+      .map(|value| c_ushort::from_ne_bytes([value[0], value[1]]))
+      .collect::<Vec<_>>()
+  };
+  read_bytes(pid, array, nsems * 2)
+    .map(decode)
+    .map_err(|e| e.map_ptrace_failure(decode))
+}
+
+impl InspectFromRawArgs<SemctlRawArgs> for SemctlCmd {
+  fn inspect_from(pid: Pid, raw_args: &SemctlRawArgs) -> Self {
+    let SemctlRawArgs { semid, semnum, .. } = *raw_args;
+    // `arg` is a `union semun` passed by value.
+    let arg = raw_args.arg as usize;
+    match raw_args.cmd & !IPC_64 {
+      libc::IPC_RMID => Self::Rmid,
+      libc::IPC_SET => Self::Set {
+        buf: InspectFromPid::inspect_from(pid, arg as AddressType),
+      },
+      libc::IPC_STAT => Self::Stat,
+      libc::IPC_INFO => Self::Info,
+      SEM_INFO => Self::SemInfo,
+      SEM_STAT => Self::SemStat,
+      SEM_STAT_ANY => Self::SemStatAny,
+      GETPID => Self::GetPid { semnum },
+      GETVAL => Self::GetVal { semnum },
+      GETNCNT => Self::GetNcnt { semnum },
+      GETZCNT => Self::GetZcnt { semnum },
+      SETVAL => Self::SetVal {
+        semnum,
+        val: arg as c_int,
+      },
+      GETALL => Self::GetAll,
+      SETALL => Self::SetAll {
+        values: read_semaphore_values(pid, semid, arg as AddressType),
+      },
+      cmd => Self::Other { cmd, arg },
+    }
+  }
+}
+
+impl InspectResultFromRawArgs<SemctlRawArgs> for InspectResult<SemctlResult> {
+  fn inspect_from(pid: Pid, raw_args: &SemctlRawArgs, _syscall_result: isize) -> Self {
+    let address = raw_args.arg as AddressType;
+    match raw_args.cmd & !IPC_64 {
+      libc::IPC_STAT | SEM_STAT | SEM_STAT_ANY => InspectResult::<semid_ds>::inspect_from(pid, address)
+        .map(SemctlResult::Stat)
+        .map_err(|e| e.map_ptrace_failure(SemctlResult::Stat)),
+      libc::IPC_INFO | SEM_INFO => InspectResult::<seminfo>::inspect_from(pid, address)
+        .map(SemctlResult::Info)
+        .map_err(|e| e.map_ptrace_failure(SemctlResult::Info)),
+      GETALL => read_semaphore_values(pid, raw_args.semid, address)
+        .map(SemctlResult::Values)
+        .map_err(|e| e.map_ptrace_failure(SemctlResult::Values)),
+      _ => Ok(SemctlResult::InSyscallResult),
+    }
+  }
+}
+
+/// The decoded `cmd` and `buf` of shmctl.
+///
+/// The values retrieved into `buf` are available in [`ShmctlResult`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ShmctlCmd {
+  /// IPC_RMID
+  Rmid,
+  /// IPC_SET
+  Set { buf: InspectResult<shmid_ds> },
+  /// IPC_STAT
+  Stat,
+  /// IPC_INFO
+  Info,
+  /// SHM_INFO
+  ShmInfo,
+  /// SHM_STAT, `shmid` is an index into the kernel's array of segments.
+  ShmStat,
+  /// SHM_STAT_ANY
+  ShmStatAny,
+  /// SHM_LOCK
+  Lock,
+  /// SHM_UNLOCK
+  Unlock,
+  /// Commands that are not decoded yet.
+  Other { cmd: c_int, buf: AddressType },
+}
+
+/// The values retrieved by shmctl through `buf`
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ShmctlResult {
+  /// IPC_STAT, SHM_STAT and SHM_STAT_ANY
+  Stat(shmid_ds),
+  /// IPC_INFO
+  Info(shminfo),
+  /// SHM_INFO
+  ShmInfo(shm_info),
+  /// The command returns its value, if any, as the syscall result.
+  InSyscallResult,
+}
+
+impl InspectFromRawArgs<ShmctlRawArgs> for ShmctlCmd {
+  fn inspect_from(pid: Pid, raw_args: &ShmctlRawArgs) -> Self {
+    let buf = raw_args.buf as AddressType;
+    match raw_args.cmd & !IPC_64 {
+      libc::IPC_RMID => Self::Rmid,
+      libc::IPC_SET => Self::Set {
+        buf: InspectFromPid::inspect_from(pid, buf),
+      },
+      libc::IPC_STAT => Self::Stat,
+      libc::IPC_INFO => Self::Info,
+      SHM_INFO => Self::ShmInfo,
+      SHM_STAT => Self::ShmStat,
+      SHM_STAT_ANY => Self::ShmStatAny,
+      SHM_LOCK => Self::Lock,
+      SHM_UNLOCK => Self::Unlock,
+      cmd => Self::Other { cmd, buf },
+    }
+  }
+}
+
+impl InspectResultFromRawArgs<ShmctlRawArgs> for InspectResult<ShmctlResult> {
+  fn inspect_from(pid: Pid, raw_args: &ShmctlRawArgs, _syscall_result: isize) -> Self {
+    let buf = raw_args.buf as AddressType;
+    match raw_args.cmd & !IPC_64 {
+      libc::IPC_STAT | SHM_STAT | SHM_STAT_ANY => InspectResult::<shmid_ds>::inspect_from(pid, buf)
+        .map(ShmctlResult::Stat)
+        .map_err(|e| e.map_ptrace_failure(ShmctlResult::Stat)),
+      libc::IPC_INFO => InspectResult::<shminfo>::inspect_from(pid, buf)
+        .map(ShmctlResult::Info)
+        .map_err(|e| e.map_ptrace_failure(ShmctlResult::Info)),
+      SHM_INFO => InspectResult::<shm_info>::inspect_from(pid, buf)
+        .map(ShmctlResult::ShmInfo)
+        .map_err(|e| e.map_ptrace_failure(ShmctlResult::ShmInfo)),
+      _ => Ok(ShmctlResult::InSyscallResult),
+    }
+  }
+}
+
+/// The decoded `cmd` and `buf` of msgctl.
+///
+/// The values retrieved into `buf` are available in [`MsgctlResult`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum MsgctlCmd {
+  /// IPC_RMID
+  Rmid,
+  /// IPC_SET
+  Set { buf: InspectResult<msqid_ds> },
+  /// IPC_STAT
+  Stat,
+  /// IPC_INFO
+  Info,
+  /// MSG_INFO
+  MsgInfo,
+  /// MSG_STAT, `msqid` is an index into the kernel's array of queues.
+  MsgStat,
+  /// MSG_STAT_ANY
+  MsgStatAny,
+  /// Commands that are not decoded yet.
+  Other { cmd: c_int, buf: AddressType },
+}
+
+/// The values retrieved by msgctl through `buf`
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum MsgctlResult {
+  /// IPC_STAT, MSG_STAT and MSG_STAT_ANY
+  Stat(msqid_ds),
+  /// IPC_INFO and MSG_INFO
+  Info(msginfo),
+  /// The command returns its value, if any, as the syscall result.
+  InSyscallResult,
+}
+
+impl InspectFromRawArgs<MsgctlRawArgs> for MsgctlCmd {
+  fn inspect_from(pid: Pid, raw_args: &MsgctlRawArgs) -> Self {
+    let buf = raw_args.buf as AddressType;
+    match raw_args.cmd & !IPC_64 {
+      libc::IPC_RMID => Self::Rmid,
+      libc::IPC_SET => Self::Set {
+        buf: InspectFromPid::inspect_from(pid, buf),
+      },
+      libc::IPC_STAT => Self::Stat,
+      libc::IPC_INFO => Self::Info,
+      MSG_INFO => Self::MsgInfo,
+      MSG_STAT => Self::MsgStat,
+      MSG_STAT_ANY => Self::MsgStatAny,
+      cmd => Self::Other { cmd, buf },
+    }
+  }
+}
+
+impl InspectResultFromRawArgs<MsgctlRawArgs> for InspectResult<MsgctlResult> {
+  fn inspect_from(pid: Pid, raw_args: &MsgctlRawArgs, _syscall_result: isize) -> Self {
+    let buf = raw_args.buf as AddressType;
+    match raw_args.cmd & !IPC_64 {
+      libc::IPC_STAT | MSG_STAT | MSG_STAT_ANY => InspectResult::<msqid_ds>::inspect_from(pid, buf)
+        .map(MsgctlResult::Stat)
+        .map_err(|e| e.map_ptrace_failure(MsgctlResult::Stat)),
+      libc::IPC_INFO | MSG_INFO => InspectResult::<msginfo>::inspect_from(pid, buf)
+        .map(MsgctlResult::Info)
+        .map_err(|e| e.map_ptrace_failure(MsgctlResult::Info)),
+      _ => Ok(MsgctlResult::InSyscallResult),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_semaphore_count_from_proc() {
+    let semid = unsafe { libc::semget(libc::IPC_PRIVATE, 5, libc::IPC_CREAT | 0o600) };
+    assert!(semid >= 0);
+    let nsems = semaphore_count(Pid::this(), semid);
+    unsafe { libc::semctl(semid, 0, libc::IPC_RMID) };
+    assert_eq!(nsems, Some(5));
+    assert_eq!(semaphore_count(Pid::this(), semid), None);
+  }
+}
@@ -0,0 +1,655 @@
+use std::ffi::CString;
+
+use enumflags2::{bitflags, BitFlags};
+use nix::libc::{c_int, c_uint, c_ulong, gid_t, uid_t};
+
+use crate::{
+  read_bounded_cstring, read_bytes, AddKeyRawArgs, AddressType, InspectError, InspectFromPid, InspectFromRawArgs,
+  InspectResult, InspectResultFromRawArgs, KeyctlRawArgs, Pid, RequestKeyRawArgs, StructFields, READ_SIZE_MAX,
+};
+
+use super::key_serial_t;
+
+/// A key serial number, with symbolic names for the special keyrings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySerial {
+  /// KEY_SPEC_THREAD_KEYRING
+  ThreadKeyring,
+  /// KEY_SPEC_PROCESS_KEYRING
+  ProcessKeyring,
+  /// KEY_SPEC_SESSION_KEYRING
+  SessionKeyring,
+  /// KEY_SPEC_USER_KEYRING
+  UserKeyring,
+  /// KEY_SPEC_USER_SESSION_KEYRING
+  UserSessionKeyring,
+  /// KEY_SPEC_GROUP_KEYRING
+  GroupKeyring,
+  /// KEY_SPEC_REQKEY_AUTH_KEY
+  ReqkeyAuthKey,
+  /// KEY_SPEC_REQUESTOR_KEYRING
+  RequestorKeyring,
+  /// A key by its serial number, 0 for none.
+  Serial(key_serial_t),
+}
+
+impl From<key_serial_t> for KeySerial {
+  fn from(serial: key_serial_t) -> Self {
+    match serial {
+      -1 => Self::ThreadKeyring,
+      -2 => Self::ProcessKeyring,
+      -3 => Self::SessionKeyring,
+      -4 => Self::UserKeyring,
+      -5 => Self::UserSessionKeyring,
+      -6 => Self::GroupKeyring,
+      -7 => Self::ReqkeyAuthKey,
+      -8 => Self::RequestorKeyring,
+      serial => Self::Serial(serial),
+    }
+  }
+}
+
+impl From<c_ulong> for KeySerial {
+  fn from(serial: c_ulong) -> Self {
+    (serial as key_serial_t).into()
+  }
+}
+
+/// The KEY_*_* permissions of a key, for the possessor, the owner, the group and others.
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum KeyPerm {
+  OtherView = 0x00000001,
+  OtherRead = 0x00000002,
+  OtherWrite = 0x00000004,
+  OtherSearch = 0x00000008,
+  OtherLink = 0x00000010,
+  OtherSetattr = 0x00000020,
+  GroupView = 0x00000100,
+  GroupRead = 0x00000200,
+  GroupWrite = 0x00000400,
+  GroupSearch = 0x00000800,
+  GroupLink = 0x00001000,
+  GroupSetattr = 0x00002000,
+  UserView = 0x00010000,
+  UserRead = 0x00020000,
+  UserWrite = 0x00040000,
+  UserSearch = 0x00080000,
+  UserLink = 0x00100000,
+  UserSetattr = 0x00200000,
+  PossessorView = 0x01000000,
+  PossessorRead = 0x02000000,
+  PossessorWrite = 0x04000000,
+  PossessorSearch = 0x08000000,
+  PossessorLink = 0x10000000,
+  PossessorSetattr = 0x20000000,
+}
+
+/// `struct keyctl_dh_params` of KEYCTL_DH_COMPUTE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyctlDhParams {
+  pub private: KeySerial,
+  pub prime: KeySerial,
+  pub base: KeySerial,
+}
+
+/// `struct keyctl_kdf_params` of KEYCTL_DH_COMPUTE
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyctlKdfParams {
+  pub hashname: InspectResult<CString>,
+  pub otherinfo: InspectResult<Vec<u8>>,
+}
+
+/// `struct keyctl_pkey_params` of the KEYCTL_PKEY_* operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyctlPkeyParams {
+  pub key: KeySerial,
+  pub in_len: u32,
+  /// `out_len`, or `in2_len` for KEYCTL_PKEY_VERIFY
+  pub out_len: u32,
+}
+
+/// The decoded operation and arguments of keyctl.
+///
+/// Operations that retrieve something into a buffer only carry their inputs here.
+/// The retrieved value is available in [`KeyctlResult`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum KeyctlOp {
+  /// KEYCTL_GET_KEYRING_ID
+  GetKeyringId { key: KeySerial, create: bool },
+  /// KEYCTL_JOIN_SESSION_KEYRING, an anonymous keyring is joined if `name` is NULL.
+  JoinSessionKeyring { name: Option<InspectResult<CString>> },
+  /// KEYCTL_UPDATE
+  Update {
+    key: KeySerial,
+    payload: InspectResult<Vec<u8>>,
+  },
+  /// KEYCTL_REVOKE
+  Revoke { key: KeySerial },
+  /// KEYCTL_CHOWN, -1 leaves the id unchanged.
+  Chown { key: KeySerial, uid: uid_t, gid: gid_t },
+  /// KEYCTL_SETPERM
+  SetPerm { key: KeySerial, perm: BitFlags<KeyPerm> },
+  /// KEYCTL_DESCRIBE
+  Describe { key: KeySerial, buflen: usize },
+  /// KEYCTL_CLEAR
+  Clear { keyring: KeySerial },
+  /// KEYCTL_LINK
+  Link { key: KeySerial, keyring: KeySerial },
+  /// KEYCTL_UNLINK
+  Unlink { key: KeySerial, keyring: KeySerial },
+  /// KEYCTL_SEARCH
+  Search {
+    keyring: KeySerial,
+    r#type: InspectResult<CString>,
+    description: InspectResult<CString>,
+    dest_keyring: KeySerial,
+  },
+  /// KEYCTL_READ
+  Read { key: KeySerial, buflen: usize },
+  /// KEYCTL_INSTANTIATE
+  Instantiate {
+    key: KeySerial,
+    payload: InspectResult<Vec<u8>>,
+    keyring: KeySerial,
+  },
+  /// KEYCTL_NEGATE
+  Negate {
+    key: KeySerial,
+    timeout: c_uint,
+    keyring: KeySerial,
+  },
+  /// KEYCTL_SET_REQKEY_KEYRING, `reqkey_defl` is one of the KEY_REQKEY_DEFL_* values.
+  SetReqkeyKeyring { reqkey_defl: c_int },
+  /// KEYCTL_SET_TIMEOUT
+  SetTimeout { key: KeySerial, timeout: c_uint },
+  /// KEYCTL_ASSUME_AUTHORITY
+  AssumeAuthority { key: KeySerial },
+  /// KEYCTL_GET_SECURITY
+  GetSecurity { key: KeySerial, buflen: usize },
+  /// KEYCTL_SESSION_TO_PARENT
+  SessionToParent,
+  /// KEYCTL_REJECT
+  Reject {
+    key: KeySerial,
+    timeout: c_uint,
+    error: c_int,
+    keyring: KeySerial,
+  },
+  /// KEYCTL_INVALIDATE
+  Invalidate { key: KeySerial },
+  /// KEYCTL_GET_PERSISTENT
+  GetPersistent { uid: uid_t, keyring: KeySerial },
+  /// KEYCTL_DH_COMPUTE
+  DhCompute {
+    params: InspectResult<KeyctlDhParams>,
+    buflen: usize,
+    kdf: Option<InspectResult<KeyctlKdfParams>>,
+  },
+  /// KEYCTL_PKEY_QUERY
+  PkeyQuery {
+    key: KeySerial,
+    params: InspectResult<CString>,
+  },
+  /// KEYCTL_PKEY_ENCRYPT, KEYCTL_PKEY_DECRYPT, KEYCTL_PKEY_SIGN and KEYCTL_PKEY_VERIFY
+  Pkey {
+    option: c_int,
+    params: InspectResult<KeyctlPkeyParams>,
+    info: InspectResult<CString>,
+    /// The input data, `in_len` bytes
+    input: InspectResult<Vec<u8>>,
+  },
+  /// KEYCTL_RESTRICT_KEYRING
+  RestrictKeyring {
+    keyring: KeySerial,
+    r#type: Option<InspectResult<CString>>,
+    restriction: Option<InspectResult<CString>>,
+  },
+  /// KEYCTL_MOVE
+  Move {
+    key: KeySerial,
+    from_keyring: KeySerial,
+    to_keyring: KeySerial,
+    flags: c_uint,
+  },
+  /// KEYCTL_CAPABILITIES
+  Capabilities { buflen: usize },
+  /// KEYCTL_WATCH_KEY
+  WatchKey {
+    key: KeySerial,
+    watch_queue_fd: c_int,
+    watch_id: c_int,
+  },
+  /// Operations that are not decoded yet.
+  Other { option: c_int, args: [c_ulong; 4] },
+}
+
+/// The values retrieved by keyctl into its buffer
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum KeyctlResult {
+  /// KEYCTL_DESCRIBE and KEYCTL_GET_SECURITY
+  Description(CString),
+  /// KEYCTL_READ, KEYCTL_DH_COMPUTE and the output of KEYCTL_PKEY_*
+  Payload(Vec<u8>),
+  /// KEYCTL_CAPABILITIES
+  Capabilities(Vec<u8>),
+  /// The operation returns its value, if any, as the syscall result.
+  InSyscallResult,
+}
+
+// https://elixir.bootlin.com/linux/v6.11/source/include/uapi/linux/keyctl.h#L40
+const KEYCTL_GET_KEYRING_ID: c_int = 0;
+const KEYCTL_JOIN_SESSION_KEYRING: c_int = 1;
+const KEYCTL_UPDATE: c_int = 2;
+const KEYCTL_REVOKE: c_int = 3;
+const KEYCTL_CHOWN: c_int = 4;
+const KEYCTL_SETPERM: c_int = 5;
+const KEYCTL_DESCRIBE: c_int = 6;
+const KEYCTL_CLEAR: c_int = 7;
+const KEYCTL_LINK: c_int = 8;
+const KEYCTL_UNLINK: c_int = 9;
+const KEYCTL_SEARCH: c_int = 10;
+const KEYCTL_READ: c_int = 11;
+const KEYCTL_INSTANTIATE: c_int = 12;
+const KEYCTL_NEGATE: c_int = 13;
+const KEYCTL_SET_REQKEY_KEYRING: c_int = 14;
+const KEYCTL_SET_TIMEOUT: c_int = 15;
+const KEYCTL_ASSUME_AUTHORITY: c_int = 16;
+const KEYCTL_GET_SECURITY: c_int = 17;
+const KEYCTL_SESSION_TO_PARENT: c_int = 18;
+const KEYCTL_REJECT: c_int = 19;
+const KEYCTL_INVALIDATE: c_int = 21;
+const KEYCTL_GET_PERSISTENT: c_int = 22;
+const KEYCTL_DH_COMPUTE: c_int = 23;
+const KEYCTL_PKEY_QUERY: c_int = 24;
+const KEYCTL_PKEY_ENCRYPT: c_int = 25;
+const KEYCTL_PKEY_DECRYPT: c_int = 26;
+const KEYCTL_PKEY_SIGN: c_int = 27;
+const KEYCTL_PKEY_VERIFY: c_int = 28;
+const KEYCTL_RESTRICT_KEYRING: c_int = 29;
+const KEYCTL_MOVE: c_int = 30;
+const KEYCTL_CAPABILITIES: c_int = 31;
+const KEYCTL_WATCH_KEY: c_int = 32;
+
+fn read_payload(pid: Pid, address: c_ulong, len: c_ulong) -> InspectResult<Vec<u8>> {
+  read_bytes(pid, address as AddressType, (len as usize).min(READ_SIZE_MAX))
+}
+
+fn read_optional_cstring(pid: Pid, address: c_ulong) -> Option<InspectResult<CString>> {
+  (address != 0).then(|| InspectFromPid::inspect_from(pid, address as AddressType))
+}
+
+fn read_dh_params(pid: Pid, address: AddressType) -> InspectResult<KeyctlDhParams> {
+  let decode = |bytes: Vec<u8>| {
+    let fields = StructFields(&bytes);
+    KeyctlDhParams {
+      private: fields.i32(0).into(),
+      prime: fields.i32(4).into(),
+      base: fields.i32(8).into(),
+    }
+  };
+  read_bytes(pid, address, 12)
+    .map(decode)
+    .map_err(|e| e.map_ptrace_failure(decode))
+}
+
+fn read_kdf_params(pid: Pid, address: AddressType) -> InspectResult<KeyctlKdfParams> {
+  let decode = |bytes: Vec<u8>| {
+    let fields = StructFields(&bytes);
+    KeyctlKdfParams {
+      hashname: InspectFromPid::inspect_from(pid, fields.ptr(0)),
+      otherinfo: read_bytes(pid, fields.ptr(8), (fields.u32(16) as usize).min(READ_SIZE_MAX)),
+    }
+  };
+  read_bytes(pid, address, 20)
+    .map(decode)
+    .map_err(|e| e.map_ptrace_failure(decode))
+}
+
+impl KeyctlPkeyParams {
+  fn from_bytes(bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    Self {
+      key: fields.i32(0).into(),
+      in_len: fields.u32(4),
+      out_len: fields.u32(8),
+    }
+  }
+}
+
+fn read_pkey_params(pid: Pid, address: AddressType) -> InspectResult<KeyctlPkeyParams> {
+  read_bytes(pid, address, 12)
+    .map(|bytes| KeyctlPkeyParams::from_bytes(&bytes))
+    .map_err(|e| e.map_ptrace_failure(|bytes| KeyctlPkeyParams::from_bytes(&bytes)))
+}
+
+impl InspectFromRawArgs<KeyctlRawArgs> for KeyctlOp {
+  fn inspect_from(pid: Pid, raw_args: &KeyctlRawArgs) -> Self {
+    let KeyctlRawArgs {
+      option,
+      arg2,
+      arg3,
+      arg4,
+      arg5,
+    } = *raw_args;
+    let cstring = |address: c_ulong| InspectFromPid::inspect_from(pid, address as AddressType);
+    match option {
+      KEYCTL_GET_KEYRING_ID => Self::GetKeyringId {
+        key: arg2.into(),
+        create: arg3 != 0,
+      },
+      KEYCTL_JOIN_SESSION_KEYRING => Self::JoinSessionKeyring {
+        name: read_optional_cstring(pid, arg2),
+      },
+      KEYCTL_UPDATE => Self::Update {
+        key: arg2.into(),
+        payload: read_payload(pid, arg3, arg4),
+      },
+      KEYCTL_REVOKE => Self::Revoke { key: arg2.into() },
+      KEYCTL_CHOWN => Self::Chown {
+        key: arg2.into(),
+        uid: arg3 as uid_t,
+        gid: arg4 as gid_t,
+      },
+      KEYCTL_SETPERM => Self::SetPerm {
+        key: arg2.into(),
+        perm: BitFlags::from_bits_truncate(arg3 as u32),
+      },
+      KEYCTL_DESCRIBE => Self::Describe {
+        key: arg2.into(),
+        buflen: arg4 as usize,
+      },
+      KEYCTL_CLEAR => Self::Clear { keyring: arg2.into() },
+      KEYCTL_LINK => Self::Link {
+        key: arg2.into(),
+        keyring: arg3.into(),
+      },
+      KEYCTL_UNLINK => Self::Unlink {
+        key: arg2.into(),
+        keyring: arg3.into(),
+      },
+      KEYCTL_SEARCH => Self::Search {
+        keyring: arg2.into(),
+        r#type: cstring(arg3),
+        description: cstring(arg4),
+        dest_keyring: arg5.into(),
+      },
+      KEYCTL_READ => Self::Read {
+        key: arg2.into(),
+        buflen: arg4 as usize,
+      },
+      KEYCTL_INSTANTIATE => Self::Instantiate {
+        key: arg2.into(),
+        payload: read_payload(pid, arg3, arg4),
+        keyring: arg5.into(),
+      },
+      KEYCTL_NEGATE => Self::Negate {
+        key: arg2.into(),
+        timeout: arg3 as c_uint,
+        keyring: arg4.into(),
+      },
+      KEYCTL_SET_REQKEY_KEYRING => Self::SetReqkeyKeyring {
+        reqkey_defl: arg2 as c_int,
+      },
+      KEYCTL_SET_TIMEOUT => Self::SetTimeout {
+        key: arg2.into(),
+        timeout: arg3 as c_uint,
+      },
+      KEYCTL_ASSUME_AUTHORITY => Self::AssumeAuthority { key: arg2.into() },
+      KEYCTL_GET_SECURITY => Self::GetSecurity {
+        key: arg2.into(),
+        buflen: arg4 as usize,
+      },
+      KEYCTL_SESSION_TO_PARENT => Self::SessionToParent,
+      KEYCTL_REJECT => Self::Reject {
+        key: arg2.into(),
+        timeout: arg3 as c_uint,
+        error: arg4 as c_int,
+        keyring: arg5.into(),
+      },
+      KEYCTL_INVALIDATE => Self::Invalidate { key: arg2.into() },
+      KEYCTL_GET_PERSISTENT => Self::GetPersistent {
+        uid: arg2 as uid_t,
+        keyring: arg3.into(),
+      },
+      KEYCTL_DH_COMPUTE => Self::DhCompute {
+        params: read_dh_params(pid, arg2 as AddressType),
+        buflen: arg4 as usize,
+        kdf: (arg5 != 0).then(|| read_kdf_params(pid, arg5 as AddressType)),
+      },
+      KEYCTL_PKEY_QUERY => Self::PkeyQuery {
+        key: arg2.into(),
+        params: cstring(arg4),
+      },
+      KEYCTL_PKEY_ENCRYPT | KEYCTL_PKEY_DECRYPT | KEYCTL_PKEY_SIGN | KEYCTL_PKEY_VERIFY => {
+        let params = read_pkey_params(pid, arg2 as AddressType);
+        let input = match &params {
+          Ok(params) => read_payload(pid, arg4, params.in_len as c_ulong),
+          Err(_) => Err(InspectError::DependencyInspectFailure { field: "params" }),
+        };
+        Self::Pkey {
+          option,
+          params,
+          info: cstring(arg3),
+          input,
+        }
+      }
+      KEYCTL_RESTRICT_KEYRING => Self::RestrictKeyring {
+        keyring: arg2.into(),
+        r#type: read_optional_cstring(pid, arg3),
+        restriction: read_optional_cstring(pid, arg4),
+      },
+      KEYCTL_MOVE => Self::Move {
+        key: arg2.into(),
+        from_keyring: arg3.into(),
+        to_keyring: arg4.into(),
+        flags: arg5 as c_uint,
+      },
+      KEYCTL_CAPABILITIES => Self::Capabilities { buflen: arg3 as usize },
+      KEYCTL_WATCH_KEY => Self::WatchKey {
+        key: arg2.into(),
+        watch_queue_fd: arg3 as c_int,
+        watch_id: arg4 as c_int,
+      },
+      option => Self::Other {
+        option,
+        args: [arg2, arg3, arg4, arg5],
+      },
+    }
+  }
+}
+
+/// The number of bytes keyctl filled in `buffer`.
+///
+/// The buffers are filled up to their length, while the result is the full length of the value.
+fn filled_len(buffer: c_ulong, buflen: c_ulong, syscall_result: isize) -> usize {
+  if buffer == 0 || syscall_result < 0 {
+    return 0;
+  }
+  (syscall_result as usize).min(buflen as usize).min(READ_SIZE_MAX)
+}
+
+impl InspectResultFromRawArgs<KeyctlRawArgs> for InspectResult<KeyctlResult> {
+  fn inspect_from(pid: Pid, raw_args: &KeyctlRawArgs, syscall_result: isize) -> Self {
+    let KeyctlRawArgs { arg2, arg3, arg4, .. } = *raw_args;
+    let filled = |buffer: c_ulong, buflen: c_ulong| (buffer as AddressType, filled_len(buffer, buflen, syscall_result));
+    match raw_args.option {
+      KEYCTL_DESCRIBE | KEYCTL_GET_SECURITY => {
+        let (buffer, len) = filled(arg3, arg4);
+        read_bounded_cstring(pid, buffer, len)
+          .map(KeyctlResult::Description)
+          .map_err(|e| e.map_ptrace_failure(KeyctlResult::Description))
+      }
+      KEYCTL_READ | KEYCTL_DH_COMPUTE => {
+        let (buffer, len) = filled(arg3, arg4);
+        read_bytes(pid, buffer, len)
+          .map(KeyctlResult::Payload)
+          .map_err(|e| e.map_ptrace_failure(KeyctlResult::Payload))
+      }
+      KEYCTL_PKEY_ENCRYPT | KEYCTL_PKEY_DECRYPT | KEYCTL_PKEY_SIGN => {
+        // The result is the length of the output, which is at most `out_len`.
+        let (buffer, len) = filled(raw_args.arg5, c_ulong::MAX);
+        read_bytes(pid, buffer, len)
+          .map(KeyctlResult::Payload)
+          .map_err(|e| e.map_ptrace_failure(KeyctlResult::Payload))
+      }
+      KEYCTL_CAPABILITIES => {
+        let (buffer, len) = filled(arg2, arg3);
+        read_bytes(pid, buffer, len)
+          .map(KeyctlResult::Capabilities)
+          .map_err(|e| e.map_ptrace_failure(KeyctlResult::Capabilities))
+      }
+      _ => Ok(KeyctlResult::InSyscallResult),
+    }
+  }
+}
+
+impl InspectFromRawArgs<AddKeyRawArgs> for KeySerial {
+  fn inspect_from(_pid: Pid, raw_args: &AddKeyRawArgs) -> Self {
+    raw_args.keyring.into()
+  }
+}
+
+impl InspectFromRawArgs<RequestKeyRawArgs> for KeySerial {
+  fn inspect_from(_pid: Pid, raw_args: &RequestKeyRawArgs) -> Self {
+    raw_args.dest_keyring.into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn raw_args(option: c_int, args: [c_ulong; 4]) -> KeyctlRawArgs {
+    let [arg2, arg3, arg4, arg5] = args;
+    KeyctlRawArgs {
+      option,
+      arg2,
+      arg3,
+      arg4,
+      arg5,
+    }
+  }
+
+  fn op(option: c_int, args: [c_ulong; 4]) -> KeyctlOp {
+    InspectFromRawArgs::inspect_from(Pid::this(), &raw_args(option, args))
+  }
+
+  fn result(option: c_int, args: [c_ulong; 4], syscall_result: isize) -> InspectResult<KeyctlResult> {
+    InspectResultFromRawArgs::inspect_from(Pid::this(), &raw_args(option, args), syscall_result)
+  }
+
+  #[test]
+  fn test_op_dispatch() {
+    assert_eq!(
+      op(KEYCTL_GET_KEYRING_ID, [-4i64 as c_ulong, 1, 0, 0]),
+      KeyctlOp::GetKeyringId {
+        key: KeySerial::UserKeyring,
+        create: true,
+      }
+    );
+    assert_eq!(
+      op(KEYCTL_DESCRIBE, [42, 0x1000, 64, 0]),
+      KeyctlOp::Describe {
+        key: KeySerial::Serial(42),
+        buflen: 64,
+      }
+    );
+    assert_eq!(
+      op(KEYCTL_CHOWN, [42, 1000, -1i64 as c_ulong, 0]),
+      KeyctlOp::Chown {
+        key: KeySerial::Serial(42),
+        uid: 1000,
+        gid: gid_t::MAX,
+      }
+    );
+    assert_eq!(
+      op(KEYCTL_SETPERM, [42, 0x3f010000, 0, 0]),
+      KeyctlOp::SetPerm {
+        key: KeySerial::Serial(42),
+        perm: BitFlags::from_bits_truncate(0x3f010000),
+      }
+    );
+    assert_eq!(
+      op(KEYCTL_MOVE, [42, -3i64 as c_ulong, -2i64 as c_ulong, 1]),
+      KeyctlOp::Move {
+        key: KeySerial::Serial(42),
+        from_keyring: KeySerial::SessionKeyring,
+        to_keyring: KeySerial::ProcessKeyring,
+        flags: 1,
+      }
+    );
+    assert_eq!(op(KEYCTL_CAPABILITIES, [0, 16, 0, 0]), KeyctlOp::Capabilities { buflen: 16 });
+    assert_eq!(
+      op(99, [1, 2, 3, 4]),
+      KeyctlOp::Other {
+        option: 99,
+        args: [1, 2, 3, 4],
+      }
+    );
+  }
+
+  #[test]
+  fn test_update_payload() {
+    let payload = *b"a payload of 24 bytes...";
+    assert_eq!(
+      op(KEYCTL_UPDATE, [42, payload.as_ptr() as c_ulong, payload.len() as c_ulong, 0]),
+      KeyctlOp::Update {
+        key: KeySerial::Serial(42),
+        payload: Ok(payload.to_vec()),
+      }
+    );
+  }
+
+  #[test]
+  fn test_filled_len() {
+    // The description is longer than the buffer.
+    assert_eq!(filled_len(0x1000, 16, 100), 16);
+    assert_eq!(filled_len(0x1000, 64, 10), 10);
+    // A NULL buffer only queries the length.
+    assert_eq!(filled_len(0, 64, 10), 0);
+    assert_eq!(filled_len(0x1000, 64, -(nix::libc::EACCES as isize)), 0);
+    assert_eq!(filled_len(0x1000, c_ulong::MAX, isize::MAX), READ_SIZE_MAX);
+  }
+
+  #[test]
+  fn test_read_result_is_clamped_to_buflen() {
+    let buffer: Vec<u8> = (0..32).collect();
+    let address = buffer.as_ptr() as c_ulong;
+    assert_eq!(result(KEYCTL_READ, [42, address, 16, 0], 40), Ok(KeyctlResult::Payload(buffer[..16].to_vec())));
+    assert_eq!(result(KEYCTL_READ, [42, address, 32, 0], 24), Ok(KeyctlResult::Payload(buffer[..24].to_vec())));
+    assert_eq!(result(KEYCTL_READ, [42, 0, 0, 0], 40), Ok(KeyctlResult::Payload(vec![])));
+  }
+
+  #[test]
+  fn test_describe_with_null_buffer() {
+    assert_eq!(result(KEYCTL_DESCRIBE, [42, 0, 0, 0], 30), Ok(KeyctlResult::Description(CString::default())));
+  }
+
+  #[test]
+  fn test_pkey_params_decoding() {
+    let mut bytes = [0u8; 12];
+    bytes[0..4].copy_from_slice(&(-3i32).to_ne_bytes());
+    bytes[4..8].copy_from_slice(&32u32.to_ne_bytes());
+    bytes[8..12].copy_from_slice(&256u32.to_ne_bytes());
+    assert_eq!(
+      KeyctlPkeyParams::from_bytes(&bytes),
+      KeyctlPkeyParams {
+        key: KeySerial::SessionKeyring,
+        in_len: 32,
+        out_len: 256,
+      }
+    );
+  }
+
+  #[test]
+  fn test_pkey_output_is_the_result_length() {
+    let output: Vec<u8> = (0..64).collect();
+    let args = [0, 0, 0, output.as_ptr() as c_ulong];
+    assert_eq!(result(KEYCTL_PKEY_SIGN, args, 48), Ok(KeyctlResult::Payload(output[..48].to_vec())));
+    assert_eq!(result(KEYCTL_PKEY_VERIFY, args, 0), Ok(KeyctlResult::InSyscallResult));
+  }
+}
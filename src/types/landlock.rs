@@ -0,0 +1,187 @@
+use std::os::fd::RawFd;
+
+use enumflags2::{bitflags, BitFlag, BitFlags};
+use nix::libc::c_long;
+
+use crate::{
+  AddressType, InspectFromPid, InspectFromRawArgs, InspectResult, LandlockAddRuleRawArgs, LandlockCreateRulesetRawArgs,
+  Pid, StructFields,
+};
+
+use super::{landlock_net_port_attr, landlock_path_beneath_attr, landlock_rule_type, read_extensible, Extensible};
+
+/// The size of the `struct landlock_ruleset_attr` fields known to this crate
+const LANDLOCK_RULESET_ATTR_SIZE: usize = 24;
+
+/// The LANDLOCK_ACCESS_FS_* access rights
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub enum LandlockAccessFs {
+  Execute = 1 << 0,
+  WriteFile = 1 << 1,
+  ReadFile = 1 << 2,
+  ReadDir = 1 << 3,
+  RemoveDir = 1 << 4,
+  RemoveFile = 1 << 5,
+  MakeChar = 1 << 6,
+  MakeDir = 1 << 7,
+  MakeReg = 1 << 8,
+  MakeSock = 1 << 9,
+  MakeFifo = 1 << 10,
+  MakeBlock = 1 << 11,
+  MakeSym = 1 << 12,
+  Refer = 1 << 13,
+  Truncate = 1 << 14,
+  IoctlDev = 1 << 15,
+}
+
+/// The LANDLOCK_ACCESS_NET_* access rights
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub enum LandlockAccessNet {
+  BindTcp = 1 << 0,
+  ConnectTcp = 1 << 1,
+}
+
+/// The LANDLOCK_SCOPE_* scopes
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub enum LandlockScope {
+  AbstractUnixSocket = 1 << 0,
+  Signal = 1 << 1,
+}
+
+/// Access rights or scopes, split into the bits known to this crate and the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LandlockBits<T: BitFlag<Numeric = u64>> {
+  pub known: BitFlags<T>,
+  /// The bits unknown to this crate, the kernel rejects those it doesn't know either.
+  pub unknown: u64,
+}
+
+impl<T: BitFlag<Numeric = u64>> From<u64> for LandlockBits<T> {
+  fn from(bits: u64) -> Self {
+    Self {
+      known: BitFlags::from_bits_truncate(bits),
+      unknown: bits & !BitFlags::<T>::all().bits(),
+    }
+  }
+}
+
+/// The decoded `struct landlock_ruleset_attr`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LandlockRulesetAttr {
+  pub handled_access_fs: LandlockBits<LandlockAccessFs>,
+  pub handled_access_net: LandlockBits<LandlockAccessNet>,
+  pub scoped: LandlockBits<LandlockScope>,
+}
+
+impl LandlockRulesetAttr {
+  /// Decode the first `size` bytes of a `struct landlock_ruleset_attr`.
+  pub fn from_bytes(bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    Self {
+      handled_access_fs: fields.u64(0).into(),
+      handled_access_net: fields.u64(8).into(),
+      scoped: fields.u64(16).into(),
+    }
+  }
+}
+
+/// The `rule_attr` of landlock_add_rule, decoded according to `rule_type`.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum LandlockRule {
+  /// LANDLOCK_RULE_PATH_BENEATH
+  PathBeneath {
+    allowed_access: LandlockBits<LandlockAccessFs>,
+    parent_fd: RawFd,
+  },
+  /// LANDLOCK_RULE_NET_PORT
+  NetPort {
+    allowed_access: LandlockBits<LandlockAccessNet>,
+    port: u64,
+  },
+  Unknown {
+    rule_type: c_long,
+    rule_attr: AddressType,
+  },
+}
+
+impl From<landlock_path_beneath_attr> for LandlockRule {
+  fn from(attr: landlock_path_beneath_attr) -> Self {
+    Self::PathBeneath {
+      allowed_access: attr.allowed_access.into(),
+      parent_fd: attr.parent_fd,
+    }
+  }
+}
+
+impl From<landlock_net_port_attr> for LandlockRule {
+  fn from(attr: landlock_net_port_attr) -> Self {
+    Self::NetPort {
+      allowed_access: attr.allowed_access.into(),
+      port: attr.port,
+    }
+  }
+}
+
+impl InspectFromRawArgs<LandlockAddRuleRawArgs> for InspectResult<LandlockRule> {
+  fn inspect_from(pid: Pid, raw_args: &LandlockAddRuleRawArgs) -> Self {
+    let rule_attr = raw_args.rule_attr as AddressType;
+    match raw_args.rule_type {
+      t if t == landlock_rule_type::LANDLOCK_RULE_PATH_BENEATH as c_long => {
+        InspectResult::<landlock_path_beneath_attr>::inspect_from(pid, rule_attr)
+          .map(LandlockRule::from)
+          .map_err(|e| e.map_ptrace_failure(LandlockRule::from))
+      }
+      t if t == landlock_rule_type::LANDLOCK_RULE_NET_PORT as c_long => {
+        InspectResult::<landlock_net_port_attr>::inspect_from(pid, rule_attr)
+          .map(LandlockRule::from)
+          .map_err(|e| e.map_ptrace_failure(LandlockRule::from))
+      }
+      rule_type => Ok(LandlockRule::Unknown { rule_type, rule_attr }),
+    }
+  }
+}
+
+/// The ruleset attributes, `None` if `attr` is NULL as it is for LANDLOCK_CREATE_RULESET_VERSION.
+impl InspectFromRawArgs<LandlockCreateRulesetRawArgs> for InspectResult<Option<Extensible<LandlockRulesetAttr>>> {
+  fn inspect_from(pid: Pid, raw_args: &LandlockCreateRulesetRawArgs) -> Self {
+    let address = raw_args.ruleset_attr as AddressType;
+    if address.is_null() {
+      return Ok(None);
+    }
+    read_extensible(pid, address, raw_args.size, LANDLOCK_RULESET_ATTR_SIZE, LandlockRulesetAttr::from_bytes)
+      .map(Some)
+      .map_err(|e| e.map_ptrace_failure(Some))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_ruleset_attr_trailing_bytes_are_kept() {
+    let mut bytes = [0u8; 32];
+    bytes[0..8].copy_from_slice(&(LandlockAccessFs::Execute as u64).to_ne_bytes());
+    bytes[24..32].copy_from_slice(&u64::MAX.to_ne_bytes());
+    let raw_args = LandlockCreateRulesetRawArgs {
+      ruleset_attr: bytes.as_ptr() as _,
+      size: bytes.len(),
+      flags: 0,
+    };
+    assert_eq!(
+      InspectResult::<Option<Extensible<LandlockRulesetAttr>>>::inspect_from(Pid::this(), &raw_args),
+      Ok(Some(Extensible {
+        size: 32,
+        value: LandlockRulesetAttr::from_bytes(&bytes[..24]),
+        trailing: vec![0xff; 8],
+      }))
+    );
+  }
+}
@@ -0,0 +1,123 @@
+use enumflags2::{bitflags, BitFlags};
+use nix::libc::c_int;
+
+use crate::{
+  AddressType, GetMempolicyRawArgs, InspectFromPid, InspectFromRawArgs, InspectResult, InspectResultFromRawArgs,
+  MbindRawArgs, Pid, SetMempolicyRawArgs,
+};
+
+/// MPOL_MODE_FLAGS, the flags that are or-ed into the mode
+const MPOL_MODE_FLAGS: c_int = 0x7 << 13;
+
+int_enum! {
+  /// The MPOL_* memory policy modes
+  pub enum MempolicyMode: c_int {
+    Default = 0,
+    Preferred = 1,
+    Bind = 2,
+    Interleave = 3,
+    Local = 4,
+    PreferredMany = 5,
+    WeightedInterleave = 6,
+  }
+}
+
+/// The MPOL_F_* mode flags of set_mempolicy and mbind
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum MempolicyModeFlags {
+  NumaBalancing = 1 << 13,
+  RelativeNodes = 1 << 14,
+  StaticNodes = 1 << 15,
+}
+
+/// A memory policy mode together with its mode flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mempolicy {
+  pub mode: MempolicyMode,
+  pub flags: BitFlags<MempolicyModeFlags>,
+}
+
+impl From<c_int> for Mempolicy {
+  fn from(mode: c_int) -> Self {
+    Self {
+      mode: (mode & !MPOL_MODE_FLAGS).into(),
+      flags: BitFlags::from_bits_truncate((mode & MPOL_MODE_FLAGS) as u32),
+    }
+  }
+}
+
+/// The MPOL_MF_* flags of mbind
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum MbindFlags {
+  Strict = 1 << 0,
+  Move = 1 << 1,
+  MoveAll = 1 << 2,
+  Lazy = 1 << 3,
+}
+
+/// The flags of get_mempolicy
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum GetMempolicyFlags {
+  /// MPOL_F_NODE, return the node id instead of the policy
+  Node = 1 << 0,
+  /// MPOL_F_ADDR, look up the policy of `addr`
+  Addr = 1 << 1,
+  /// MPOL_F_MEMS_ALLOWED, return the nodes allowed by the cpuset
+  MemsAllowed = 1 << 2,
+}
+
+/// The value stored in the `mode` of get_mempolicy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GetMempolicyMode {
+  Policy(Mempolicy),
+  /// The node id returned with MPOL_F_NODE
+  Node(c_int),
+}
+
+impl InspectFromRawArgs<MbindRawArgs> for Mempolicy {
+  fn inspect_from(_pid: Pid, raw_args: &MbindRawArgs) -> Self {
+    raw_args.mode.into()
+  }
+}
+
+impl InspectFromRawArgs<MbindRawArgs> for BitFlags<MbindFlags> {
+  fn inspect_from(_pid: Pid, raw_args: &MbindRawArgs) -> Self {
+    BitFlags::from_bits_truncate(raw_args.flags)
+  }
+}
+
+impl InspectFromRawArgs<SetMempolicyRawArgs> for Mempolicy {
+  fn inspect_from(_pid: Pid, raw_args: &SetMempolicyRawArgs) -> Self {
+    raw_args.mode.into()
+  }
+}
+
+impl InspectFromRawArgs<GetMempolicyRawArgs> for BitFlags<GetMempolicyFlags> {
+  fn inspect_from(_pid: Pid, raw_args: &GetMempolicyRawArgs) -> Self {
+    BitFlags::from_bits_truncate(raw_args.flags as u32)
+  }
+}
+
+/// The mode, `None` if the pointer is NULL.
+impl InspectResultFromRawArgs<GetMempolicyRawArgs> for InspectResult<Option<GetMempolicyMode>> {
+  fn inspect_from(pid: Pid, raw_args: &GetMempolicyRawArgs, _syscall_result: isize) -> Self {
+    let decode = |mode: Option<c_int>| {
+      mode.map(|mode| {
+        if raw_args.flags & GetMempolicyFlags::Node as u64 != 0 {
+          GetMempolicyMode::Node(mode)
+        } else {
+          GetMempolicyMode::Policy(mode.into())
+        }
+      })
+    };
+    InspectResult::<Option<c_int>>::inspect_from(pid, raw_args.mode as AddressType)
+      .map(decode)
+      .map_err(|e| e.map_ptrace_failure(decode))
+  }
+}
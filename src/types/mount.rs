@@ -0,0 +1,216 @@
+use std::{ffi::CString, os::fd::RawFd, path::PathBuf};
+
+use enumflags2::{bitflags, BitFlags};
+use nix::libc::{c_int, c_uint};
+
+use crate::{
+  read_bytes, AddressType, FsconfigRawArgs, FsmountRawArgs, FsopenRawArgs, FspickRawArgs, InspectFromPid,
+  InspectFromRawArgs, InspectResult, MoveMountRawArgs, OpenTreeAttrRawArgs, OpenTreeRawArgs, Pid, READ_SIZE_MAX,
+};
+
+/// The flags of fsopen
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum FsopenFlags {
+  Cloexec = 0x00000001,
+}
+
+/// The flags of fsmount
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum FsmountFlags {
+  Cloexec = 0x00000001,
+}
+
+/// The MOUNT_ATTR_* attributes of fsmount, MOUNT_ATTR_RELATIME is the absence of the other atime flags.
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum MountAttrFlags {
+  Rdonly = 0x00000001,
+  Nosuid = 0x00000002,
+  Nodev = 0x00000004,
+  Noexec = 0x00000008,
+  Noatime = 0x00000010,
+  Strictatime = 0x00000020,
+  Nodiratime = 0x00000080,
+  Idmap = 0x00100000,
+  Nosymfollow = 0x00200000,
+}
+
+/// The flags of fspick
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum FspickFlags {
+  Cloexec = 0x00000001,
+  SymlinkNofollow = 0x00000002,
+  NoAutomount = 0x00000004,
+  EmptyPath = 0x00000008,
+}
+
+/// The MOVE_MOUNT_* flags of move_mount
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum MoveMountFlags {
+  FSymlinks = 0x00000001,
+  FAutomounts = 0x00000002,
+  FEmptyPath = 0x00000004,
+  TSymlinks = 0x00000010,
+  TAutomounts = 0x00000020,
+  TEmptyPath = 0x00000040,
+  SetGroup = 0x00000100,
+  Beneath = 0x00000200,
+}
+
+/// The flags of open_tree and open_tree_attr
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum OpenTreeFlags {
+  /// OPEN_TREE_CLONE
+  Clone = 0x00000001,
+  /// AT_SYMLINK_NOFOLLOW
+  SymlinkNofollow = 0x00000100,
+  /// AT_NO_AUTOMOUNT
+  NoAutomount = 0x00000800,
+  /// AT_EMPTY_PATH
+  EmptyPath = 0x00001000,
+  /// AT_RECURSIVE
+  Recursive = 0x00008000,
+  /// OPEN_TREE_CLOEXEC
+  Cloexec = 0x00080000,
+}
+
+/// The decoded `cmd`, `key`, `value` and `aux` of fsconfig
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum FsconfigCommand {
+  /// FSCONFIG_SET_FLAG
+  SetFlag { key: InspectResult<CString> },
+  /// FSCONFIG_SET_STRING
+  SetString {
+    key: InspectResult<CString>,
+    value: InspectResult<CString>,
+  },
+  /// FSCONFIG_SET_BINARY, the value has `aux` bytes.
+  SetBinary {
+    key: InspectResult<CString>,
+    value: InspectResult<Vec<u8>>,
+  },
+  /// FSCONFIG_SET_PATH, the path is relative to `dirfd`, which is passed in `aux`.
+  SetPath {
+    key: InspectResult<CString>,
+    dirfd: RawFd,
+    path: InspectResult<PathBuf>,
+  },
+  /// FSCONFIG_SET_PATH_EMPTY, like FSCONFIG_SET_PATH but the path may be empty to refer to `dirfd`.
+  SetPathEmpty {
+    key: InspectResult<CString>,
+    dirfd: RawFd,
+    path: InspectResult<PathBuf>,
+  },
+  /// FSCONFIG_SET_FD, the fd is passed in `aux`.
+  SetFd { key: InspectResult<CString>, fd: RawFd },
+  /// FSCONFIG_CMD_CREATE
+  CmdCreate,
+  /// FSCONFIG_CMD_RECONFIGURE
+  CmdReconfigure,
+  /// FSCONFIG_CMD_CREATE_EXCL
+  CmdCreateExcl,
+  /// Commands that are not known yet.
+  Other {
+    cmd: c_uint,
+    key: AddressType,
+    value: AddressType,
+    aux: c_int,
+  },
+}
+
+impl FsconfigCommand {
+  /// The number of bytes of a FSCONFIG_SET_BINARY value of `aux` bytes that are read.
+  ///
+  /// The kernel rejects binary values larger than 1 MiB.
+  fn binary_value_len(aux: c_int) -> usize {
+    (aux.max(0) as usize).min(READ_SIZE_MAX)
+  }
+}
+
+impl InspectFromRawArgs<FsconfigRawArgs> for FsconfigCommand {
+  fn inspect_from(pid: Pid, raw_args: &FsconfigRawArgs) -> Self {
+    let key = || InspectFromPid::inspect_from(pid, raw_args.key as AddressType);
+    let value = raw_args.value as AddressType;
+    let aux = raw_args.aux;
+    match raw_args.cmd {
+      0 => Self::SetFlag { key: key() },
+      1 => Self::SetString {
+        key: key(),
+        value: InspectFromPid::inspect_from(pid, value),
+      },
+      2 => Self::SetBinary {
+        key: key(),
+        value: read_bytes(pid, value, Self::binary_value_len(aux)),
+      },
+      3 => Self::SetPath {
+        key: key(),
+        dirfd: aux,
+        path: InspectFromPid::inspect_from(pid, value),
+      },
+      4 => Self::SetPathEmpty {
+        key: key(),
+        dirfd: aux,
+        path: InspectFromPid::inspect_from(pid, value),
+      },
+      5 => Self::SetFd { key: key(), fd: aux },
+      6 => Self::CmdCreate,
+      7 => Self::CmdReconfigure,
+      8 => Self::CmdCreateExcl,
+      cmd => Self::Other {
+        cmd,
+        key: raw_args.key as AddressType,
+        value,
+        aux,
+      },
+    }
+  }
+}
+
+/// Decode a flags argument of the new mount API.
+macro_rules! impl_flags_from_raw_args {
+  ($($raw_args:ty => $field:ident: $flags:ty),* $(,)?) => {
+    $(
+      impl InspectFromRawArgs<$raw_args> for BitFlags<$flags> {
+        fn inspect_from(_pid: Pid, raw_args: &$raw_args) -> Self {
+          BitFlags::from_bits_truncate(raw_args.$field)
+        }
+      }
+    )*
+  };
+}
+
+impl_flags_from_raw_args! {
+  FsopenRawArgs => flags: FsopenFlags,
+  FsmountRawArgs => flags: FsmountFlags,
+  FsmountRawArgs => ms_flags: MountAttrFlags,
+  FspickRawArgs => flags: FspickFlags,
+  MoveMountRawArgs => ms_flags: MoveMountFlags,
+  OpenTreeRawArgs => flags: OpenTreeFlags,
+  OpenTreeAttrRawArgs => flags: OpenTreeFlags,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_fsconfig_binary_value_len() {
+    assert_eq!(FsconfigCommand::binary_value_len(16), 16);
+    assert_eq!(FsconfigCommand::binary_value_len(0), 0);
+    assert_eq!(FsconfigCommand::binary_value_len(-1), 0);
+    assert_eq!(FsconfigCommand::binary_value_len(1 << 20), 1 << 20);
+    assert_eq!(FsconfigCommand::binary_value_len(c_int::MAX), 1 << 20);
+  }
+}
@@ -0,0 +1,362 @@
+use std::{collections::BTreeMap, path::Path, sync::Mutex};
+
+use enumflags2::{bitflags, BitFlags};
+use nix::libc::clockid_t;
+
+use crate::{AddressType, InspectError, InspectFromPid, InspectResult, Pid, StructFields};
+
+use super::{read_extensible, Extensible};
+
+/// PERF_ATTR_SIZE_VER0, the size assumed by the kernel if `size` is zero.
+const PERF_ATTR_SIZE_VER0: usize = 64;
+/// PERF_ATTR_SIZE_VER8, the size of the fields known to this crate
+const PERF_ATTR_SIZE_VER8: usize = 136;
+
+int_enum! {
+  /// `enum perf_hw_id`
+  pub enum PerfHwId: u64 {
+    CpuCycles = 0,
+    Instructions = 1,
+    CacheReferences = 2,
+    CacheMisses = 3,
+    BranchInstructions = 4,
+    BranchMisses = 5,
+    BusCycles = 6,
+    StalledCyclesFrontend = 7,
+    StalledCyclesBackend = 8,
+    RefCpuCycles = 9,
+  }
+}
+
+int_enum! {
+  /// `enum perf_sw_ids`
+  pub enum PerfSwId: u64 {
+    CpuClock = 0,
+    TaskClock = 1,
+    PageFaults = 2,
+    ContextSwitches = 3,
+    CpuMigrations = 4,
+    PageFaultsMin = 5,
+    PageFaultsMaj = 6,
+    AlignmentFaults = 7,
+    EmulationFaults = 8,
+    Dummy = 9,
+    BpfOutput = 10,
+    CgroupSwitches = 11,
+  }
+}
+
+int_enum! {
+  /// `enum perf_hw_cache_id`
+  pub enum PerfHwCacheId: u64 {
+    L1d = 0,
+    L1i = 1,
+    Ll = 2,
+    Dtlb = 3,
+    Itlb = 4,
+    Bpu = 5,
+    Node = 6,
+  }
+}
+
+int_enum! {
+  /// `enum perf_hw_cache_op_id`
+  pub enum PerfHwCacheOp: u64 {
+    Read = 0,
+    Write = 1,
+    Prefetch = 2,
+  }
+}
+
+int_enum! {
+  /// `enum perf_hw_cache_op_result_id`
+  pub enum PerfHwCacheOpResult: u64 {
+    Access = 0,
+    Miss = 1,
+  }
+}
+
+/// The access types that trigger a breakpoint event
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum HwBreakpointType {
+  /// HW_BREAKPOINT_R
+  Read = 1,
+  /// HW_BREAKPOINT_W
+  Write = 2,
+  /// HW_BREAKPOINT_X
+  Execute = 4,
+}
+
+/// The event selected by `type` and `config` of `perf_event_attr`
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PerfEvent {
+  /// PERF_TYPE_HARDWARE, `pmu_type` is the PMU in the extended type bits of `config` on hybrid systems.
+  Hardware { id: PerfHwId, pmu_type: u32 },
+  /// PERF_TYPE_SOFTWARE
+  Software(PerfSwId),
+  /// PERF_TYPE_TRACEPOINT, the name is resolved through tracefs if it is mounted.
+  Tracepoint { id: u64, name: Option<String> },
+  /// PERF_TYPE_HW_CACHE
+  HwCache {
+    cache: PerfHwCacheId,
+    op: PerfHwCacheOp,
+    result: PerfHwCacheOpResult,
+    pmu_type: u32,
+  },
+  /// PERF_TYPE_RAW
+  Raw(u64),
+  /// PERF_TYPE_BREAKPOINT
+  Breakpoint {
+    bp_type: BitFlags<HwBreakpointType>,
+    bp_addr: u64,
+    bp_len: u64,
+  },
+  /// A dynamic PMU from `/sys/bus/event_source/devices/*/type`
+  Dynamic { pmu_type: u32, config: u64 },
+}
+
+/// The sampling interval of `perf_event_attr`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerfSampleInterval {
+  /// `sample_period`
+  Period(u64),
+  /// `sample_freq`, used if the `freq` flag is set.
+  Freq(u64),
+}
+
+/// The bitfield of `perf_event_attr`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PerfEventAttrFlags {
+  pub disabled: bool,
+  pub inherit: bool,
+  pub pinned: bool,
+  pub exclusive: bool,
+  pub exclude_user: bool,
+  pub exclude_kernel: bool,
+  pub exclude_hv: bool,
+  pub exclude_idle: bool,
+  pub mmap: bool,
+  pub comm: bool,
+  pub freq: bool,
+  pub inherit_stat: bool,
+  pub enable_on_exec: bool,
+  pub task: bool,
+  pub watermark: bool,
+  /// The 2-bit skid constraint
+  pub precise_ip: u8,
+  pub mmap_data: bool,
+  pub sample_id_all: bool,
+  pub exclude_host: bool,
+  pub exclude_guest: bool,
+  pub exclude_callchain_kernel: bool,
+  pub exclude_callchain_user: bool,
+  pub mmap2: bool,
+  pub comm_exec: bool,
+  pub use_clockid: bool,
+  pub context_switch: bool,
+  pub write_backward: bool,
+  pub namespaces: bool,
+  pub ksymbol: bool,
+  pub bpf_event: bool,
+  pub aux_output: bool,
+  pub cgroup: bool,
+  pub text_poke: bool,
+  pub build_id: bool,
+  pub inherit_thread: bool,
+  pub remove_on_exec: bool,
+  pub sigtrap: bool,
+}
+
+impl From<u64> for PerfEventAttrFlags {
+  fn from(bits: u64) -> Self {
+    let bit = |n: u32| bits & (1 << n) != 0;
+    Self {
+      disabled: bit(0),
+      inherit: bit(1),
+      pinned: bit(2),
+      exclusive: bit(3),
+      exclude_user: bit(4),
+      exclude_kernel: bit(5),
+      exclude_hv: bit(6),
+      exclude_idle: bit(7),
+      mmap: bit(8),
+      comm: bit(9),
+      freq: bit(10),
+      inherit_stat: bit(11),
+      enable_on_exec: bit(12),
+      task: bit(13),
+      watermark: bit(14),
+      precise_ip: ((bits >> 15) & 0b11) as u8,
+      mmap_data: bit(17),
+      sample_id_all: bit(18),
+      exclude_host: bit(19),
+      exclude_guest: bit(20),
+      exclude_callchain_kernel: bit(21),
+      exclude_callchain_user: bit(22),
+      mmap2: bit(23),
+      comm_exec: bit(24),
+      use_clockid: bit(25),
+      context_switch: bit(26),
+      write_backward: bit(27),
+      namespaces: bit(28),
+      ksymbol: bit(29),
+      bpf_event: bit(30),
+      aux_output: bit(31),
+      cgroup: bit(32),
+      text_poke: bit(33),
+      build_id: bit(34),
+      inherit_thread: bit(35),
+      remove_on_exec: bit(36),
+      sigtrap: bit(37),
+    }
+  }
+}
+
+/// The decoded `struct perf_event_attr`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerfEventAttr {
+  pub event: PerfEvent,
+  /// The `size` field, 0 for PERF_ATTR_SIZE_VER0
+  pub size: u32,
+  pub sample: PerfSampleInterval,
+  pub sample_type: u64,
+  pub read_format: u64,
+  pub flags: PerfEventAttrFlags,
+  /// `wakeup_events`, or `wakeup_watermark` if the `watermark` flag is set
+  pub wakeup: u32,
+  pub config1: u64,
+  pub config2: u64,
+  pub branch_sample_type: u64,
+  pub sample_regs_user: u64,
+  pub sample_stack_user: u32,
+  pub clockid: clockid_t,
+  pub sample_regs_intr: u64,
+  pub aux_watermark: u32,
+  pub sample_max_stack: u16,
+  pub aux_sample_size: u32,
+  pub sig_data: u64,
+  pub config3: u64,
+}
+
+/// The tracepoints found in tracefs by id, as their system and event names
+static TRACEPOINTS: Mutex<BTreeMap<u64, (String, String)>> = Mutex::new(BTreeMap::new());
+
+/// The `events` directory of tracefs
+fn tracefs_events() -> Option<&'static Path> {
+  ["/sys/kernel/tracing/events", "/sys/kernel/debug/tracing/events"]
+    .into_iter()
+    .map(Path::new)
+    .find(|dir| dir.is_dir())
+}
+
+fn read_tracepoint_id(path: &Path) -> Option<u64> {
+  std::fs::read_to_string(path.join("id")).ok()?.trim().parse().ok()
+}
+
+fn scan_tracepoints(events: &Path) -> BTreeMap<u64, (String, String)> {
+  let mut tracepoints = BTreeMap::new();
+  for system in std::fs::read_dir(events).into_iter().flatten().flatten() {
+    for event in std::fs::read_dir(system.path()).into_iter().flatten().flatten() {
+      if let Some(id) = read_tracepoint_id(&event.path()) {
+        let name = |entry: &std::fs::DirEntry| entry.file_name().to_string_lossy().into_owned();
+        tracepoints.insert(id, (name(&system), name(&event)));
+      }
+    }
+  }
+  tracepoints
+}
+
+/// The name of a tracepoint, `<system>:<event>`, found by its id in tracefs.
+///
+/// Tracepoints are created and removed at runtime, e.g. by kprobe events, and their ids are reused.
+/// So tracefs is scanned again if the id is unknown or no longer belongs to the tracepoint found before.
+fn tracepoint_name(id: u64) -> Option<String> {
+  let events = tracefs_events()?;
+  let mut tracepoints = TRACEPOINTS.lock().unwrap();
+  let is_current =
+    |(system, event): &(String, String)| read_tracepoint_id(&events.join(system).join(event)) == Some(id);
+  if !tracepoints.get(&id).is_some_and(is_current) {
+    *tracepoints = scan_tracepoints(events);
+  }
+  tracepoints.get(&id).map(|(system, event)| format!("{system}:{event}"))
+}
+
+impl PerfEvent {
+  fn decode(pmu_type: u32, config: u64, fields: &StructFields) -> Self {
+    const PERF_HW_EVENT_MASK: u64 = 0xffffffff;
+    let extended_type = (config >> 32) as u32;
+    match pmu_type {
+      0 => Self::Hardware {
+        id: (config & PERF_HW_EVENT_MASK).into(),
+        pmu_type: extended_type,
+      },
+      1 => Self::Software(config.into()),
+      2 => Self::Tracepoint {
+        id: config,
+        name: tracepoint_name(config),
+      },
+      3 => Self::HwCache {
+        cache: (config & 0xff).into(),
+        op: ((config >> 8) & 0xff).into(),
+        result: ((config >> 16) & 0xff).into(),
+        pmu_type: extended_type,
+      },
+      4 => Self::Raw(config),
+      5 => Self::Breakpoint {
+        bp_type: BitFlags::from_bits_truncate(fields.u32(52)),
+        bp_addr: fields.u64(56),
+        bp_len: fields.u64(64),
+      },
+      pmu_type => Self::Dynamic { pmu_type, config },
+    }
+  }
+}
+
+impl PerfEventAttr {
+  pub fn from_bytes(bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    let flags = PerfEventAttrFlags::from(fields.u64(40));
+    let sample_period = fields.u64(16);
+    Self {
+      event: PerfEvent::decode(fields.u32(0), fields.u64(8), &fields),
+      size: fields.u32(4),
+      sample: if flags.freq {
+        PerfSampleInterval::Freq(sample_period)
+      } else {
+        PerfSampleInterval::Period(sample_period)
+      },
+      sample_type: fields.u64(24),
+      read_format: fields.u64(32),
+      flags,
+      wakeup: fields.u32(48),
+      config1: fields.u64(56),
+      config2: fields.u64(64),
+      branch_sample_type: fields.u64(72),
+      sample_regs_user: fields.u64(80),
+      sample_stack_user: fields.u32(88),
+      clockid: fields.i32(92),
+      sample_regs_intr: fields.u64(96),
+      aux_watermark: fields.u32(104),
+      sample_max_stack: fields.u16(108),
+      aux_sample_size: fields.u32(112),
+      sig_data: fields.u64(120),
+      config3: fields.u64(128),
+    }
+  }
+}
+
+/// The size of `struct perf_event_attr` is its second field.
+impl InspectFromPid for InspectResult<Extensible<PerfEventAttr>> {
+  fn inspect_from(pid: Pid, address: AddressType) -> Self {
+    let size = InspectResult::<u32>::inspect_from(pid, unsafe { address.byte_add(4) })
+      .map_err(|_| InspectError::DependencyInspectFailure { field: "size" })?;
+    let size = match size {
+      0 => PERF_ATTR_SIZE_VER0,
+      size => size as usize,
+    };
+    read_extensible(pid, address, size, PERF_ATTR_SIZE_VER8, PerfEventAttr::from_bytes)
+  }
+}
@@ -0,0 +1,304 @@
+use std::{ffi::CString, os::fd::RawFd};
+
+use nix::libc::{self, c_int, c_ulong, pid_t};
+
+use crate::{
+  read_bounded_cstring, AddressType, InspectCountedFromPid, InspectFromPid, InspectFromRawArgs, InspectResult,
+  InspectResultFromRawArgs, Pid, PrctlRawArgs,
+};
+
+use super::{prctl_mm_map, read_seccomp_filter, Capability, SeccompInsn};
+
+/// https://elixir.bootlin.com/linux/v6.11/source/include/linux/sched.h#L318
+const TASK_COMM_LEN: usize = 16;
+/// https://elixir.bootlin.com/linux/v6.11/A/ident/ANON_VMA_NAME_MAX_LEN
+const ANON_VMA_NAME_MAX_LEN: usize = 80;
+
+/// The decoded `option` and `arg2..arg5` of prctl.
+///
+/// Operations that retrieve something only carry their inputs here.
+/// The retrieved value is either the syscall result or available in [`PrctlResult`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PrctlOp {
+  /// PR_SET_PDEATHSIG, a signal of 0 clears the parent-death signal.
+  SetPdeathsig { signal: c_int },
+  /// PR_GET_PDEATHSIG
+  GetPdeathsig,
+  /// PR_SET_DUMPABLE
+  SetDumpable { dumpable: c_ulong },
+  /// PR_GET_DUMPABLE
+  GetDumpable,
+  /// PR_SET_KEEPCAPS
+  SetKeepCaps { keep: c_ulong },
+  /// PR_GET_KEEPCAPS
+  GetKeepCaps,
+  /// PR_SET_NAME, the kernel copies at most 15 bytes of the name.
+  SetName { name: InspectResult<CString> },
+  /// PR_GET_NAME
+  GetName,
+  /// PR_GET_SECCOMP
+  GetSeccomp,
+  /// PR_SET_SECCOMP with SECCOMP_MODE_STRICT
+  SetSeccompStrict,
+  /// PR_SET_SECCOMP with SECCOMP_MODE_FILTER
+  SetSeccompFilter { filter: InspectResult<Vec<SeccompInsn>> },
+  /// PR_CAPBSET_READ
+  CapBsetRead { cap: Capability },
+  /// PR_CAPBSET_DROP
+  CapBsetDrop { cap: Capability },
+  /// PR_CAP_AMBIENT, `cap` is unused by PR_CAP_AMBIENT_CLEAR_ALL.
+  CapAmbient { op: CapAmbientOp, cap: Capability },
+  /// PR_SET_MM
+  SetMm(PrctlMmOp),
+  /// PR_SET_PTRACER
+  SetPtracer { tracer: PrctlPtracer },
+  /// PR_SET_CHILD_SUBREAPER
+  SetChildSubreaper { enabled: c_ulong },
+  /// PR_GET_CHILD_SUBREAPER
+  GetChildSubreaper,
+  /// PR_SET_NO_NEW_PRIVS
+  SetNoNewPrivs { value: c_ulong },
+  /// PR_GET_NO_NEW_PRIVS
+  GetNoNewPrivs,
+  /// PR_GET_TID_ADDRESS
+  GetTidAddress,
+  /// PR_SET_VMA with PR_SET_VMA_ANON_NAME, a null name clears the name of the anonymous mappings.
+  SetVmaAnonName {
+    addr: AddressType,
+    size: usize,
+    name: InspectResult<Option<CString>>,
+  },
+  /// Options that are not decoded yet.
+  Other {
+    option: c_int,
+    arg2: c_ulong,
+    arg3: c_ulong,
+    arg4: c_ulong,
+    arg5: c_ulong,
+  },
+}
+
+int_enum! {
+  /// The PR_CAP_AMBIENT_* operations of PR_CAP_AMBIENT
+  pub enum CapAmbientOp: c_int {
+    IsSet = 1,
+    Raise = 2,
+    Lower = 3,
+    ClearAll = 4,
+  }
+}
+
+/// The sub-operations of PR_SET_MM
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PrctlMmOp {
+  /// PR_SET_MM_{START,END}_CODE, PR_SET_MM_{START,END}_DATA, PR_SET_MM_START_STACK, PR_SET_MM_START_BRK,
+  /// PR_SET_MM_BRK, PR_SET_MM_ARG_{START,END} and PR_SET_MM_ENV_{START,END}
+  Field { field: PrctlMmField, addr: AddressType },
+  /// PR_SET_MM_AUXV
+  Auxv { auxv: InspectResult<Vec<u64>> },
+  /// PR_SET_MM_EXE_FILE
+  ExeFile { fd: RawFd },
+  /// PR_SET_MM_MAP
+  Map { map: InspectResult<prctl_mm_map> },
+  /// PR_SET_MM_MAP_SIZE, the size is available in [`PrctlResult::MmMapSize`].
+  MapSize,
+  Other {
+    option: c_int,
+    arg3: c_ulong,
+    arg4: c_ulong,
+    arg5: c_ulong,
+  },
+}
+
+/// The memory map fields that can be set individually by PR_SET_MM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrctlMmField {
+  StartCode,
+  EndCode,
+  StartData,
+  EndData,
+  StartStack,
+  StartBrk,
+  Brk,
+  ArgStart,
+  ArgEnd,
+  EnvStart,
+  EnvEnd,
+}
+
+impl PrctlMmField {
+  /// Decode the PR_SET_MM_* option, `None` if it doesn't set a single field.
+  pub fn from_raw(option: c_int) -> Option<Self> {
+    Some(match option {
+      libc::PR_SET_MM_START_CODE => Self::StartCode,
+      libc::PR_SET_MM_END_CODE => Self::EndCode,
+      libc::PR_SET_MM_START_DATA => Self::StartData,
+      libc::PR_SET_MM_END_DATA => Self::EndData,
+      libc::PR_SET_MM_START_STACK => Self::StartStack,
+      libc::PR_SET_MM_START_BRK => Self::StartBrk,
+      libc::PR_SET_MM_BRK => Self::Brk,
+      libc::PR_SET_MM_ARG_START => Self::ArgStart,
+      libc::PR_SET_MM_ARG_END => Self::ArgEnd,
+      libc::PR_SET_MM_ENV_START => Self::EnvStart,
+      libc::PR_SET_MM_ENV_END => Self::EnvEnd,
+      _ => return None,
+    })
+  }
+}
+
+/// The tracer allowed by PR_SET_PTRACER
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrctlPtracer {
+  /// Clear the previously allowed tracer.
+  None,
+  /// PR_SET_PTRACER_ANY, any process is allowed to trace the caller.
+  Any,
+  Pid(pid_t),
+}
+
+impl From<c_ulong> for PrctlPtracer {
+  fn from(arg: c_ulong) -> Self {
+    match arg {
+      0 => Self::None,
+      libc::PR_SET_PTRACER_ANY => Self::Any,
+      pid => Self::Pid(pid as pid_t),
+    }
+  }
+}
+
+/// The values retrieved by prctl through pointer arguments.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PrctlResult {
+  /// PR_GET_PDEATHSIG
+  Pdeathsig(c_int),
+  /// PR_GET_NAME
+  Name(CString),
+  /// PR_GET_CHILD_SUBREAPER
+  ChildSubreaper(c_int),
+  /// PR_GET_TID_ADDRESS
+  TidAddress(AddressType),
+  /// PR_SET_MM with PR_SET_MM_MAP_SIZE
+  MmMapSize(u32),
+  /// The operation returns its value, if any, as the syscall result.
+  InSyscallResult,
+}
+
+/// Read the name of PR_SET_VMA_ANON_NAME, which the kernel rejects if it is longer than ANON_VMA_NAME_MAX_LEN.
+fn read_anon_vma_name(pid: Pid, address: AddressType) -> InspectResult<Option<CString>> {
+  if address.is_null() {
+    return Ok(None);
+  }
+  read_bounded_cstring(pid, address, ANON_VMA_NAME_MAX_LEN)
+    .map(Some)
+    .map_err(|e| e.map_ptrace_failure(Some))
+}
+
+impl InspectFromRawArgs<PrctlRawArgs> for PrctlOp {
+  fn inspect_from(pid: Pid, raw_args: &PrctlRawArgs) -> Self {
+    let PrctlRawArgs {
+      option,
+      arg2,
+      arg3,
+      arg4,
+      arg5,
+    } = *raw_args;
+    match option {
+      libc::PR_SET_PDEATHSIG => Self::SetPdeathsig { signal: arg2 as c_int },
+      libc::PR_GET_PDEATHSIG => Self::GetPdeathsig,
+      libc::PR_SET_DUMPABLE => Self::SetDumpable { dumpable: arg2 },
+      libc::PR_GET_DUMPABLE => Self::GetDumpable,
+      libc::PR_SET_KEEPCAPS => Self::SetKeepCaps { keep: arg2 },
+      libc::PR_GET_KEEPCAPS => Self::GetKeepCaps,
+      libc::PR_SET_NAME => Self::SetName {
+        name: read_bounded_cstring(pid, arg2 as AddressType, TASK_COMM_LEN - 1),
+      },
+      libc::PR_GET_NAME => Self::GetName,
+      libc::PR_GET_SECCOMP => Self::GetSeccomp,
+      libc::PR_SET_SECCOMP if arg2 == libc::SECCOMP_MODE_STRICT as c_ulong => Self::SetSeccompStrict,
+      libc::PR_SET_SECCOMP if arg2 == libc::SECCOMP_MODE_FILTER as c_ulong => Self::SetSeccompFilter {
+        filter: read_seccomp_filter(pid, arg3 as AddressType),
+      },
+      libc::PR_CAPBSET_READ => Self::CapBsetRead {
+        cap: (arg2 as u32).into(),
+      },
+      libc::PR_CAPBSET_DROP => Self::CapBsetDrop {
+        cap: (arg2 as u32).into(),
+      },
+      libc::PR_CAP_AMBIENT => Self::CapAmbient {
+        op: (arg2 as c_int).into(),
+        cap: (arg3 as u32).into(),
+      },
+      libc::PR_SET_MM => Self::SetMm(match (arg2 as c_int, PrctlMmField::from_raw(arg2 as c_int)) {
+        (_, Some(field)) => PrctlMmOp::Field {
+          field,
+          addr: arg3 as AddressType,
+        },
+        (libc::PR_SET_MM_AUXV, _) => PrctlMmOp::Auxv {
+          auxv: InspectCountedFromPid::inspect_from(
+            pid,
+            arg3 as AddressType,
+            arg4 as usize / std::mem::size_of::<u64>(),
+          ),
+        },
+        (libc::PR_SET_MM_EXE_FILE, _) => PrctlMmOp::ExeFile { fd: arg3 as RawFd },
+        (libc::PR_SET_MM_MAP, _) => PrctlMmOp::Map {
+          map: InspectFromPid::inspect_from(pid, arg3 as AddressType),
+        },
+        (libc::PR_SET_MM_MAP_SIZE, _) => PrctlMmOp::MapSize,
+        (option, _) => PrctlMmOp::Other {
+          option,
+          arg3,
+          arg4,
+          arg5,
+        },
+      }),
+      libc::PR_SET_PTRACER => Self::SetPtracer { tracer: arg2.into() },
+      libc::PR_SET_CHILD_SUBREAPER => Self::SetChildSubreaper { enabled: arg2 },
+      libc::PR_GET_CHILD_SUBREAPER => Self::GetChildSubreaper,
+      libc::PR_SET_NO_NEW_PRIVS => Self::SetNoNewPrivs { value: arg2 },
+      libc::PR_GET_NO_NEW_PRIVS => Self::GetNoNewPrivs,
+      libc::PR_GET_TID_ADDRESS => Self::GetTidAddress,
+      libc::PR_SET_VMA if arg2 == libc::PR_SET_VMA_ANON_NAME as c_ulong => Self::SetVmaAnonName {
+        addr: arg3 as AddressType,
+        size: arg4 as usize,
+        name: read_anon_vma_name(pid, arg5 as AddressType),
+      },
+      option => Self::Other {
+        option,
+        arg2,
+        arg3,
+        arg4,
+        arg5,
+      },
+    }
+  }
+}
+
+impl InspectResultFromRawArgs<PrctlRawArgs> for InspectResult<PrctlResult> {
+  fn inspect_from(pid: Pid, raw_args: &PrctlRawArgs, _syscall_result: isize) -> Self {
+    let address = raw_args.arg2 as AddressType;
+    match raw_args.option {
+      libc::PR_GET_PDEATHSIG => InspectResult::<c_int>::inspect_from(pid, address)
+        .map(PrctlResult::Pdeathsig)
+        .map_err(|e| e.map_ptrace_failure(PrctlResult::Pdeathsig)),
+      libc::PR_GET_NAME => read_bounded_cstring(pid, address, TASK_COMM_LEN)
+        .map(PrctlResult::Name)
+        .map_err(|e| e.map_ptrace_failure(PrctlResult::Name)),
+      libc::PR_GET_CHILD_SUBREAPER => InspectResult::<c_int>::inspect_from(pid, address)
+        .map(PrctlResult::ChildSubreaper)
+        .map_err(|e| e.map_ptrace_failure(PrctlResult::ChildSubreaper)),
+      libc::PR_GET_TID_ADDRESS => InspectResult::<AddressType>::inspect_from(pid, address)
+        .map(PrctlResult::TidAddress)
+        .map_err(|e| e.map_ptrace_failure(PrctlResult::TidAddress)),
+      libc::PR_SET_MM if raw_args.arg2 == libc::PR_SET_MM_MAP_SIZE as c_ulong => {
+        InspectResult::<u32>::inspect_from(pid, raw_args.arg3 as AddressType)
+          .map(PrctlResult::MmMapSize)
+          .map_err(|e| e.map_ptrace_failure(PrctlResult::MmMapSize))
+      }
+      _ => Ok(PrctlResult::InSyscallResult),
+    }
+  }
+}
@@ -0,0 +1,331 @@
+use enumflags2::{bitflags, BitFlags};
+use nix::libc::{c_int, c_long, c_ulong, iovec};
+
+use crate::{
+  read_bytes, AddressType, InspectFromPid, InspectFromRawArgs, InspectResult, InspectResultFromRawArgs, Pid,
+  PtraceRawArgs, StructFields,
+};
+
+use super::{signal::read_sigset, SigSet, SignalInfo};
+
+/// The size of `struct ptrace_syscall_info` as of Linux 6.11
+const PTRACE_SYSCALL_INFO_SIZE: usize = 88;
+
+// https://elixir.bootlin.com/linux/v6.11/source/include/uapi/linux/ptrace.h
+const PTRACE_TRACEME: c_int = 0;
+const PTRACE_PEEKTEXT: c_int = 1;
+const PTRACE_PEEKDATA: c_int = 2;
+const PTRACE_PEEKUSR: c_int = 3;
+const PTRACE_POKETEXT: c_int = 4;
+const PTRACE_POKEDATA: c_int = 5;
+const PTRACE_POKEUSR: c_int = 6;
+const PTRACE_CONT: c_int = 7;
+const PTRACE_KILL: c_int = 8;
+const PTRACE_SINGLESTEP: c_int = 9;
+const PTRACE_ATTACH: c_int = 16;
+const PTRACE_DETACH: c_int = 17;
+const PTRACE_SYSCALL: c_int = 24;
+const PTRACE_SETOPTIONS: c_int = 0x4200;
+const PTRACE_GETEVENTMSG: c_int = 0x4201;
+const PTRACE_GETSIGINFO: c_int = 0x4202;
+const PTRACE_SETSIGINFO: c_int = 0x4203;
+const PTRACE_GETREGSET: c_int = 0x4204;
+const PTRACE_SETREGSET: c_int = 0x4205;
+const PTRACE_SEIZE: c_int = 0x4206;
+const PTRACE_INTERRUPT: c_int = 0x4207;
+const PTRACE_LISTEN: c_int = 0x4208;
+const PTRACE_GETSIGMASK: c_int = 0x420a;
+const PTRACE_SETSIGMASK: c_int = 0x420b;
+const PTRACE_GET_SYSCALL_INFO: c_int = 0x420e;
+
+/// The PTRACE_O_* options of PTRACE_SETOPTIONS and PTRACE_SEIZE
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum PtraceOptions {
+  TraceSysGood = 0x00000001,
+  TraceFork = 0x00000002,
+  TraceVfork = 0x00000004,
+  TraceClone = 0x00000008,
+  TraceExec = 0x00000010,
+  TraceVforkDone = 0x00000020,
+  TraceExit = 0x00000040,
+  TraceSeccomp = 0x00000080,
+  ExitKill = 0x00100000,
+  SuspendSeccomp = 0x00200000,
+}
+
+int_enum! {
+  /// The NT_* note types of the register sets accessed by PTRACE_GETREGSET and PTRACE_SETREGSET
+  pub enum NtType: u32 {
+    Prstatus = 1,
+    Prfpreg = 2,
+    Prpsinfo = 3,
+    Taskstruct = 4,
+    Auxv = 6,
+    X86Tls = 0x200,
+    X86Ioperm = 0x201,
+    X86Xstate = 0x202,
+    X86Shstk = 0x204,
+    ArmTls = 0x401,
+    ArmHwBreak = 0x402,
+    ArmHwWatch = 0x403,
+    ArmSystemCall = 0x404,
+    ArmSve = 0x405,
+    ArmPacMask = 0x406,
+    ArmTaggedAddrCtrl = 0x409,
+    ArmPacEnabledKeys = 0x40a,
+    ArmSsve = 0x40b,
+    ArmZa = 0x40c,
+    RiscvCsr = 0x900,
+    RiscvVector = 0x901,
+  }
+}
+
+/// The memory area accessed by the PEEK and POKE requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtraceArea {
+  /// PTRACE_PEEKTEXT and PTRACE_POKETEXT
+  Text,
+  /// PTRACE_PEEKDATA and PTRACE_POKEDATA
+  Data,
+  /// PTRACE_PEEKUSER and PTRACE_POKEUSER, the address is an offset in `struct user`.
+  User,
+}
+
+/// The decoded request and arguments of ptrace.
+///
+/// Signals passed to the resuming requests are 0 if no signal is delivered.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PtraceRequest {
+  /// PTRACE_TRACEME
+  TraceMe,
+  /// PTRACE_PEEK*, the word is stored at `data` and is available in [`PtraceResult`].
+  Peek { area: PtraceArea, addr: AddressType },
+  /// PTRACE_POKE*
+  Poke {
+    area: PtraceArea,
+    addr: AddressType,
+    value: c_ulong,
+  },
+  /// PTRACE_CONT
+  Cont { signal: c_int },
+  /// PTRACE_SYSCALL
+  Syscall { signal: c_int },
+  /// PTRACE_SINGLESTEP
+  SingleStep { signal: c_int },
+  /// PTRACE_KILL
+  Kill,
+  /// PTRACE_ATTACH
+  Attach,
+  /// PTRACE_DETACH
+  Detach { signal: c_int },
+  /// PTRACE_SETOPTIONS
+  SetOptions { options: BitFlags<PtraceOptions> },
+  /// PTRACE_GETEVENTMSG
+  GetEventMsg,
+  /// PTRACE_GETSIGINFO
+  GetSigInfo,
+  /// PTRACE_SETSIGINFO
+  SetSigInfo { info: InspectResult<SignalInfo> },
+  /// PTRACE_GETREGSET, the kernel updates `iov_len` to the size of the register set.
+  GetRegSet { nt_type: NtType, iov: InspectResult<iovec> },
+  /// PTRACE_SETREGSET
+  SetRegSet { nt_type: NtType, iov: InspectResult<iovec> },
+  /// PTRACE_SEIZE
+  Seize { options: BitFlags<PtraceOptions> },
+  /// PTRACE_INTERRUPT
+  Interrupt,
+  /// PTRACE_LISTEN
+  Listen,
+  /// PTRACE_GETSIGMASK
+  GetSigMask { sigsetsize: usize },
+  /// PTRACE_SETSIGMASK
+  SetSigMask { mask: InspectResult<Option<SigSet>> },
+  /// PTRACE_GET_SYSCALL_INFO, the size of the buffer is passed in `addr`.
+  GetSyscallInfo { size: usize },
+  /// Requests that are not decoded yet, including the architecture specific ones.
+  Other {
+    request: c_int,
+    addr: AddressType,
+    data: AddressType,
+  },
+}
+
+/// The `op` specific part of `struct ptrace_syscall_info`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtraceSyscallInfoOp {
+  /// PTRACE_SYSCALL_INFO_NONE
+  None,
+  /// PTRACE_SYSCALL_INFO_ENTRY
+  Entry { nr: u64, args: [u64; 6] },
+  /// PTRACE_SYSCALL_INFO_EXIT
+  Exit { rval: i64, is_error: bool },
+  /// PTRACE_SYSCALL_INFO_SECCOMP
+  Seccomp { nr: u64, args: [u64; 6], ret_data: u32 },
+  /// An `op` not known to this crate
+  Unknown(u8),
+}
+
+/// `struct ptrace_syscall_info` filled by PTRACE_GET_SYSCALL_INFO
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtraceSyscallInfo {
+  /// The AUDIT_ARCH_* value of the syscall
+  pub arch: u32,
+  pub instruction_pointer: u64,
+  pub stack_pointer: u64,
+  pub op: PtraceSyscallInfoOp,
+}
+
+impl PtraceSyscallInfo {
+  /// Decode a `struct ptrace_syscall_info`, missing trailing fields are zero.
+  pub fn from_bytes(bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    let args = || std::array::from_fn(|i| fields.u64(32 + i * 8));
+    let op = match bytes.first().copied().unwrap_or_default() {
+      0 => PtraceSyscallInfoOp::None,
+      1 => PtraceSyscallInfoOp::Entry {
+        nr: fields.u64(24),
+        args: args(),
+      },
+      2 => PtraceSyscallInfoOp::Exit {
+        rval: fields.u64(24) as i64,
+        is_error: fields.bytes::<1>(32)[0] != 0,
+      },
+      3 => PtraceSyscallInfoOp::Seccomp {
+        nr: fields.u64(24),
+        args: args(),
+        ret_data: fields.u32(80),
+      },
+      op => PtraceSyscallInfoOp::Unknown(op),
+    };
+    Self {
+      arch: fields.u32(4),
+      instruction_pointer: fields.u64(8),
+      stack_pointer: fields.u64(16),
+      op,
+    }
+  }
+}
+
+/// The values retrieved by ptrace into `data`
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PtraceResult {
+  /// The word read by PTRACE_PEEK*
+  Word(c_long),
+  /// PTRACE_GETEVENTMSG
+  EventMsg(c_ulong),
+  /// PTRACE_GETSIGINFO
+  SigInfo(SignalInfo),
+  /// The updated iovec of PTRACE_GETREGSET
+  RegSet(iovec),
+  /// PTRACE_GETSIGMASK
+  SigMask(SigSet),
+  /// PTRACE_GET_SYSCALL_INFO
+  SyscallInfo(PtraceSyscallInfo),
+  /// The request only returns a status, if anything.
+  InSyscallResult,
+}
+
+/// The area of a PEEK or POKE request
+fn ptrace_area(request: c_int) -> PtraceArea {
+  match request {
+    PTRACE_PEEKTEXT | PTRACE_POKETEXT => PtraceArea::Text,
+    PTRACE_PEEKDATA | PTRACE_POKEDATA => PtraceArea::Data,
+    _ => PtraceArea::User,
+  }
+}
+
+impl InspectFromRawArgs<PtraceRawArgs> for PtraceRequest {
+  fn inspect_from(pid: Pid, raw_args: &PtraceRawArgs) -> Self {
+    let addr = raw_args.addr as AddressType;
+    let data = raw_args.data as AddressType;
+    let signal = raw_args.data as c_int;
+    let options = || BitFlags::from_bits_truncate(raw_args.data as u32);
+    match raw_args.request {
+      PTRACE_TRACEME => Self::TraceMe,
+      request @ (PTRACE_PEEKTEXT | PTRACE_PEEKDATA | PTRACE_PEEKUSR) => Self::Peek {
+        area: ptrace_area(request),
+        addr,
+      },
+      request @ (PTRACE_POKETEXT | PTRACE_POKEDATA | PTRACE_POKEUSR) => Self::Poke {
+        area: ptrace_area(request),
+        addr,
+        value: raw_args.data as c_ulong,
+      },
+      PTRACE_CONT => Self::Cont { signal },
+      PTRACE_SYSCALL => Self::Syscall { signal },
+      PTRACE_SINGLESTEP => Self::SingleStep { signal },
+      PTRACE_KILL => Self::Kill,
+      PTRACE_ATTACH => Self::Attach,
+      PTRACE_DETACH => Self::Detach { signal },
+      PTRACE_SETOPTIONS => Self::SetOptions { options: options() },
+      PTRACE_GETEVENTMSG => Self::GetEventMsg,
+      PTRACE_GETSIGINFO => Self::GetSigInfo,
+      PTRACE_SETSIGINFO => Self::SetSigInfo {
+        info: InspectFromPid::inspect_from(pid, data),
+      },
+      PTRACE_GETREGSET => Self::GetRegSet {
+        nt_type: (raw_args.addr as u32).into(),
+        iov: InspectFromPid::inspect_from(pid, data),
+      },
+      PTRACE_SETREGSET => Self::SetRegSet {
+        nt_type: (raw_args.addr as u32).into(),
+        iov: InspectFromPid::inspect_from(pid, data),
+      },
+      PTRACE_SEIZE => Self::Seize { options: options() },
+      PTRACE_INTERRUPT => Self::Interrupt,
+      PTRACE_LISTEN => Self::Listen,
+      PTRACE_GETSIGMASK => Self::GetSigMask {
+        sigsetsize: raw_args.addr as usize,
+      },
+      PTRACE_SETSIGMASK => Self::SetSigMask {
+        mask: read_sigset(pid, data, raw_args.addr as usize),
+      },
+      PTRACE_GET_SYSCALL_INFO => Self::GetSyscallInfo {
+        size: raw_args.addr as usize,
+      },
+      request => Self::Other { request, addr, data },
+    }
+  }
+}
+
+impl InspectResultFromRawArgs<PtraceRawArgs> for InspectResult<PtraceResult> {
+  fn inspect_from(pid: Pid, raw_args: &PtraceRawArgs, syscall_result: isize) -> Self {
+    let data = raw_args.data as AddressType;
+    match raw_args.request {
+      PTRACE_PEEKTEXT | PTRACE_PEEKDATA | PTRACE_PEEKUSR => {
+        let word = |bytes: Vec<u8>| PtraceResult::Word(StructFields(&bytes).u64(0) as c_long);
+        read_bytes(pid, data, 8)
+          .map(word)
+          .map_err(|e| e.map_ptrace_failure(word))
+      }
+      PTRACE_GETEVENTMSG => {
+        let msg = |bytes: Vec<u8>| PtraceResult::EventMsg(StructFields(&bytes).u64(0) as c_ulong);
+        read_bytes(pid, data, 8).map(msg).map_err(|e| e.map_ptrace_failure(msg))
+      }
+      PTRACE_GETSIGINFO => InspectResult::<SignalInfo>::inspect_from(pid, data)
+        .map(PtraceResult::SigInfo)
+        .map_err(|e| e.map_ptrace_failure(PtraceResult::SigInfo)),
+      PTRACE_GETREGSET => InspectResult::<iovec>::inspect_from(pid, data)
+        .map(PtraceResult::RegSet)
+        .map_err(|e| e.map_ptrace_failure(PtraceResult::RegSet)),
+      PTRACE_GETSIGMASK => read_sigset(pid, data, raw_args.addr as usize)
+        .map(|mask| PtraceResult::SigMask(mask.unwrap_or_default()))
+        .map_err(|e| e.map_ptrace_failure(|mask| PtraceResult::SigMask(mask.unwrap_or_default()))),
+      PTRACE_GET_SYSCALL_INFO => {
+        // The kernel returns the full size of the info, but only copies what fits in the buffer.
+        let size = (raw_args.addr as usize)
+          .min(syscall_result as usize)
+          .min(PTRACE_SYSCALL_INFO_SIZE);
+        let info = |bytes: Vec<u8>| PtraceResult::SyscallInfo(PtraceSyscallInfo::from_bytes(&bytes));
+        read_bytes(pid, data, size)
+          .map(info)
+          .map_err(|e| e.map_ptrace_failure(info))
+      }
+      _ => Ok(PtraceResult::InSyscallResult),
+    }
+  }
+}
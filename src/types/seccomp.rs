@@ -0,0 +1,71 @@
+use enumflags2::{bitflags, BitFlags};
+use nix::libc::{self, c_uint};
+
+use crate::{AddressType, InspectFromPid, InspectFromRawArgs, InspectResult, Pid, SeccompRawArgs};
+
+use super::{read_seccomp_filter, SeccompAction, SeccompInsn};
+
+/// The flags of SECCOMP_SET_MODE_FILTER
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum SeccompFilterFlags {
+  /// SECCOMP_FILTER_FLAG_TSYNC
+  Tsync = libc::SECCOMP_FILTER_FLAG_TSYNC as u32,
+  /// SECCOMP_FILTER_FLAG_LOG
+  Log = libc::SECCOMP_FILTER_FLAG_LOG as u32,
+  /// SECCOMP_FILTER_FLAG_SPEC_ALLOW
+  SpecAllow = libc::SECCOMP_FILTER_FLAG_SPEC_ALLOW as u32,
+  /// SECCOMP_FILTER_FLAG_NEW_LISTENER
+  NewListener = libc::SECCOMP_FILTER_FLAG_NEW_LISTENER as u32,
+  /// SECCOMP_FILTER_FLAG_TSYNC_ESRCH
+  TsyncEsrch = libc::SECCOMP_FILTER_FLAG_TSYNC_ESRCH as u32,
+  /// SECCOMP_FILTER_FLAG_WAIT_KILLABLE_RECV
+  WaitKillableRecv = libc::SECCOMP_FILTER_FLAG_WAIT_KILLABLE_RECV as u32,
+}
+
+/// The decoded `operation`, `flags` and `args` of seccomp.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SeccompOp {
+  /// SECCOMP_SET_MODE_STRICT
+  SetModeStrict,
+  /// SECCOMP_SET_MODE_FILTER
+  SetModeFilter {
+    flags: BitFlags<SeccompFilterFlags>,
+    filter: InspectResult<Vec<SeccompInsn>>,
+  },
+  /// SECCOMP_GET_ACTION_AVAIL
+  GetActionAvail { action: InspectResult<SeccompAction> },
+  /// SECCOMP_GET_NOTIF_SIZES
+  GetNotifSizes,
+  Unknown {
+    operation: c_uint,
+    flags: c_uint,
+    args: AddressType,
+  },
+}
+
+impl InspectFromRawArgs<SeccompRawArgs> for SeccompOp {
+  fn inspect_from(pid: Pid, raw_args: &SeccompRawArgs) -> Self {
+    let args = raw_args.args as AddressType;
+    match raw_args.operation {
+      libc::SECCOMP_SET_MODE_STRICT => Self::SetModeStrict,
+      libc::SECCOMP_SET_MODE_FILTER => Self::SetModeFilter {
+        flags: BitFlags::from_bits_truncate(raw_args.flags),
+        filter: read_seccomp_filter(pid, args),
+      },
+      libc::SECCOMP_GET_ACTION_AVAIL => Self::GetActionAvail {
+        action: InspectResult::<u32>::inspect_from(pid, args)
+          .map(SeccompAction::from)
+          .map_err(|e| e.map_ptrace_failure(SeccompAction::from)),
+      },
+      libc::SECCOMP_GET_NOTIF_SIZES => Self::GetNotifSizes,
+      operation => Self::Unknown {
+        operation,
+        flags: raw_args.flags,
+        args,
+      },
+    }
+  }
+}
@@ -0,0 +1,59 @@
+use std::os::fd::RawFd;
+
+use crate::{
+  read_bytes, AddressType, InspectCountedFromPid, InspectError, InspectFromRawArgs, InspectResult, Pid,
+  Pselect6RawArgs, StructFields, READ_SIZE_MAX,
+};
+
+use super::{signal::read_sigset, SigSet};
+
+/// The fds set in an `fd_set`, in ascending order.
+///
+/// Only the fds below `nfds` are decoded, as the kernel ignores the rest of the bitmap.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FdSet(pub Vec<RawFd>);
+
+impl FdSet {
+  /// Decode the first `nfds` bits of an `fd_set` bitmap.
+  pub fn from_bitmap(bitmap: &[u8], nfds: usize) -> Self {
+    Self(
+      (0..nfds.min(bitmap.len() * 8))
+        .filter(|fd| bitmap[fd / 8] & (1 << (fd % 8)) != 0)
+        .map(|fd| fd as RawFd)
+        .collect(),
+    )
+  }
+}
+
+/// An `fd_set` counted by `nfds`, `None` if the pointer is NULL.
+///
+/// The kernel accepts `nfds` up to the size of the fd table, the bitmap is truncated to 1 MiB.
+impl InspectCountedFromPid for InspectResult<Option<FdSet>> {
+  fn inspect_from(pid: Pid, address: AddressType, nfds: usize) -> Self {
+    if address.is_null() {
+      return Ok(None);
+    }
+    let nfds = nfds.min(READ_SIZE_MAX * 8);
+    // The kernel reads the bitmap in longs.
+    let len = nfds.div_ceil(64) * 8;
+    read_bytes(pid, address, len)
+      .map(|bitmap| Some(FdSet::from_bitmap(&bitmap, nfds)))
+      .map_err(|e| e.map_ptrace_failure(|bitmap| Some(FdSet::from_bitmap(&bitmap, nfds))))
+  }
+}
+
+/// The sixth argument of pselect6, `{ const kernel_sigset_t *ss; size_t ss_len; }`.
+///
+/// `None` if there is no signal mask, either because the argument or `ss` is NULL.
+impl InspectFromRawArgs<Pselect6RawArgs> for InspectResult<Option<SigSet>> {
+  fn inspect_from(pid: Pid, raw_args: &Pselect6RawArgs) -> Self {
+    let address = raw_args.sigmask as AddressType;
+    if address.is_null() {
+      return Ok(None);
+    }
+    let bytes =
+      read_bytes(pid, address, 16).map_err(|_| InspectError::DependencyInspectFailure { field: "sigmask" })?;
+    let fields = StructFields(&bytes);
+    read_sigset(pid, fields.ptr(0), fields.u64(8) as usize)
+  }
+}
@@ -0,0 +1,328 @@
+use nix::libc::{
+  c_int, c_long, c_uint, clock_t, pid_t, siginfo_t, uid_t, SIGBUS, SIGCHLD, SIGFPE, SIGILL, SIGPOLL, SIGSEGV, SIGSYS,
+  SIGTRAP,
+};
+
+use crate::{read_bytes, AddressType, InspectFromPid, InspectResult, Pid, StructFields};
+
+use super::ChildState;
+
+/// The size of `siginfo_t`
+const SIGINFO_SIZE: usize = 128;
+
+int_enum! {
+  /// The SI_* codes that are not specific to a signal
+  pub enum SiCode: c_int {
+    User = 0,
+    Kernel = 0x80,
+    Queue = -1,
+    Timer = -2,
+    Mesgq = -3,
+    Asyncio = -4,
+    Sigio = -5,
+    Tkill = -6,
+    DetHread = -7,
+    Asyncnl = -60,
+  }
+}
+
+int_enum! {
+  /// The SEGV_* codes of SIGSEGV
+  pub enum SegvCode: c_int {
+    MapErr = 1,
+    AccErr = 2,
+    BndErr = 3,
+    PkuErr = 4,
+    AccAdi = 5,
+    AdiDErr = 6,
+    AdiPErr = 7,
+    MteAErr = 8,
+    MteSErr = 9,
+    CpErr = 10,
+  }
+}
+
+int_enum! {
+  /// The BUS_* codes of SIGBUS
+  pub enum BusCode: c_int {
+    AdrAln = 1,
+    AdrErr = 2,
+    ObjErr = 3,
+    MceErrAr = 4,
+    MceErrAo = 5,
+  }
+}
+
+int_enum! {
+  /// The ILL_* codes of SIGILL
+  pub enum IllCode: c_int {
+    IllOpc = 1,
+    IllOpn = 2,
+    IllAdr = 3,
+    IllTrp = 4,
+    PrvOpc = 5,
+    PrvReg = 6,
+    Coproc = 7,
+    BadStk = 8,
+    BadIAddr = 9,
+  }
+}
+
+int_enum! {
+  /// The FPE_* codes of SIGFPE
+  pub enum FpeCode: c_int {
+    IntDiv = 1,
+    IntOvf = 2,
+    FltDiv = 3,
+    FltOvf = 4,
+    FltUnd = 5,
+    FltRes = 6,
+    FltInv = 7,
+    FltSub = 8,
+    FltUnk = 14,
+    CondTrap = 15,
+  }
+}
+
+int_enum! {
+  /// The TRAP_* codes of SIGTRAP
+  pub enum TrapCode: c_int {
+    Brkpt = 1,
+    Trace = 2,
+    Branch = 3,
+    HwBkpt = 4,
+    Unk = 5,
+    Perf = 6,
+  }
+}
+
+int_enum! {
+  /// The POLL_* codes of SIGPOLL
+  pub enum PollCode: c_int {
+    In = 1,
+    Out = 2,
+    Msg = 3,
+    Err = 4,
+    Pri = 5,
+    Hup = 6,
+  }
+}
+
+int_enum! {
+  /// The SYS_* codes of SIGSYS
+  pub enum SysCode: c_int {
+    Seccomp = 1,
+    UserDispatch = 2,
+  }
+}
+
+/// The `si_code` of a fault, named according to the signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultCode {
+  Segv(SegvCode),
+  Bus(BusCode),
+  Ill(IllCode),
+  Fpe(FpeCode),
+  Trap(TrapCode),
+}
+
+/// A `siginfo_t`, decoded with the union member selected by the signal and `si_code` the way the kernel does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SignalInfo {
+  /// Sent by kill or the kernel, SI_USER, SI_KERNEL and the codes above SI_KERNEL,
+  /// e.g. the `SIGTRAP | 0x80` of ptrace syscall-stops and the `SIGTRAP | event << 8` of event stops
+  Kill {
+    signo: c_int,
+    code: SiCode,
+    pid: pid_t,
+    uid: uid_t,
+  },
+  /// Queued with a value, SI_QUEUE and the other negative codes
+  Queue {
+    signo: c_int,
+    code: SiCode,
+    pid: pid_t,
+    uid: uid_t,
+    /// The `sival_int` or `sival_ptr`
+    value: u64,
+  },
+  /// A POSIX timer expiration, SI_TIMER
+  Timer {
+    signo: c_int,
+    timer_id: c_int,
+    overrun: c_int,
+    value: u64,
+  },
+  /// SIGCHLD
+  Child {
+    pid: pid_t,
+    uid: uid_t,
+    state: ChildState,
+    utime: clock_t,
+    stime: clock_t,
+  },
+  /// SIGSEGV, SIGBUS, SIGILL, SIGFPE and SIGTRAP raised by a fault
+  Fault {
+    code: FaultCode,
+    addr: AddressType,
+    /// The least significant bit of the address for BUS_MCEERR_*
+    addr_lsb: Option<i16>,
+    /// The protection key for SEGV_PKUERR
+    pkey: Option<u32>,
+  },
+  /// SIGPOLL, also used for SI_SIGIO
+  Poll {
+    signo: c_int,
+    code: PollCode,
+    band: c_long,
+    fd: c_int,
+  },
+  /// SIGSYS
+  Sys {
+    code: SysCode,
+    call_addr: AddressType,
+    syscall: c_int,
+    /// The AUDIT_ARCH_* value of the syscall
+    arch: c_uint,
+  },
+  /// A signal specific `si_code` of a signal whose codes are not decoded
+  Other { signo: c_int, code: c_int },
+}
+
+impl SignalInfo {
+  /// Decode a `siginfo_t`, missing trailing fields are zero.
+  pub fn from_bytes(bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    let signo = fields.i32(0);
+    let code = fields.i32(8);
+    let pid = fields.i32(16);
+    let uid = fields.u32(20);
+    // The signal specific codes are between SI_USER and SI_KERNEL.
+    if code > 0 && code < SiCode::Kernel.into() {
+      let addr = fields.ptr(16);
+      let fault = |code: FaultCode| Self::Fault {
+        code,
+        addr,
+        addr_lsb: None,
+        pkey: None,
+      };
+      return match signo {
+        SIGSEGV => {
+          let code = SegvCode::from(code);
+          Self::Fault {
+            code: FaultCode::Segv(code),
+            addr,
+            addr_lsb: None,
+            pkey: (code == SegvCode::PkuErr).then(|| fields.u32(32)),
+          }
+        }
+        SIGBUS => {
+          let code = BusCode::from(code);
+          Self::Fault {
+            code: FaultCode::Bus(code),
+            addr,
+            addr_lsb: matches!(code, BusCode::MceErrAr | BusCode::MceErrAo).then(|| fields.u16(24) as i16),
+            pkey: None,
+          }
+        }
+        SIGILL => fault(FaultCode::Ill(code.into())),
+        SIGFPE => fault(FaultCode::Fpe(code.into())),
+        SIGTRAP => fault(FaultCode::Trap(code.into())),
+        SIGCHLD => Self::Child {
+          pid,
+          uid,
+          state: ChildState::from_code(code, fields.i32(24)),
+          utime: fields.u64(32) as clock_t,
+          stime: fields.u64(40) as clock_t,
+        },
+        SIGPOLL => Self::poll(signo, code, &fields),
+        SIGSYS => Self::Sys {
+          code: code.into(),
+          call_addr: fields.ptr(16),
+          syscall: fields.i32(24),
+          arch: fields.u32(28),
+        },
+        signo => Self::Other { signo, code },
+      };
+    }
+    match SiCode::from(code) {
+      SiCode::Timer => Self::Timer {
+        signo,
+        timer_id: fields.i32(16),
+        overrun: fields.i32(20),
+        value: fields.u64(24),
+      },
+      SiCode::Sigio => Self::poll(signo, code, &fields),
+      si_code if code < 0 => Self::Queue {
+        signo,
+        code: si_code,
+        pid,
+        uid,
+        value: fields.u64(24),
+      },
+      si_code => Self::Kill {
+        signo,
+        code: si_code,
+        pid,
+        uid,
+      },
+    }
+  }
+
+  fn poll(signo: c_int, code: c_int, fields: &StructFields) -> Self {
+    Self::Poll {
+      signo,
+      code: code.into(),
+      band: fields.u64(16) as c_long,
+      fd: fields.i32(24),
+    }
+  }
+
+  /// The signal number
+  pub fn signo(&self) -> c_int {
+    match self {
+      Self::Kill { signo, .. }
+      | Self::Queue { signo, .. }
+      | Self::Timer { signo, .. }
+      | Self::Poll { signo, .. }
+      | Self::Other { signo, .. } => *signo,
+      Self::Child { .. } => SIGCHLD,
+      Self::Fault { code, .. } => match code {
+        FaultCode::Segv(_) => SIGSEGV,
+        FaultCode::Bus(_) => SIGBUS,
+        FaultCode::Ill(_) => SIGILL,
+        FaultCode::Fpe(_) => SIGFPE,
+        FaultCode::Trap(_) => SIGTRAP,
+      },
+      Self::Sys { .. } => SIGSYS,
+    }
+  }
+}
+
+/// A `siginfo_t` retrieved by the tracer, e.g. with PTRACE_GETSIGINFO or waitid.
+impl From<&siginfo_t> for SignalInfo {
+  fn from(info: &siginfo_t) -> Self {
+    let bytes = unsafe { std::slice::from_raw_parts(info as *const siginfo_t as *const u8, size_of::<siginfo_t>()) };
+    Self::from_bytes(bytes)
+  }
+}
+
+impl InspectFromPid for InspectResult<SignalInfo> {
+  fn inspect_from(pid: Pid, address: AddressType) -> Self {
+    read_bytes(pid, address, SIGINFO_SIZE)
+      .map(|bytes| SignalInfo::from_bytes(&bytes))
+      .map_err(|e| e.map_ptrace_failure(|bytes| SignalInfo::from_bytes(&bytes)))
+  }
+}
+
+impl InspectFromPid for InspectResult<Option<SignalInfo>> {
+  fn inspect_from(pid: Pid, address: AddressType) -> Self {
+    if address.is_null() {
+      return Ok(None);
+    }
+    InspectResult::<SignalInfo>::inspect_from(pid, address)
+      .map(Some)
+      .map_err(|e| e.map_ptrace_failure(Some))
+  }
+}
@@ -0,0 +1,181 @@
+use enumflags2::{bitflags, BitFlags};
+use nix::{libc::c_int, sys::signal::Signal};
+
+use crate::{
+  read_bytes, AddressType, InspectError, InspectFromRawArgs, InspectResult, InspectResultFromRawArgs, Pid,
+  RtSigactionRawArgs, RtSigpendingRawArgs, RtSigprocmaskRawArgs, RtSigsuspendRawArgs, RtSigtimedwaitRawArgs,
+  StructFields,
+};
+
+/// The kernel only accepts a `sigsetsize` of 8 on the supported architectures,
+/// larger sizes are capped to avoid reading huge buffers.
+const SIGSET_SIZE_MAX: usize = 128;
+
+/// The size of `struct sigaction` before `sa_mask`, which is the last field in the kernel ABI.
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+const SIGACTION_MASK_OFFSET: usize = 24;
+/// riscv64 doesn't define SA_RESTORER so there is no `sa_restorer` field.
+#[cfg(target_arch = "riscv64")]
+const SIGACTION_MASK_OFFSET: usize = 16;
+
+/// A kernel signal set, read with exactly `sigsetsize` bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SigSet(pub Vec<u8>);
+
+impl SigSet {
+  /// Whether the signal numbered `signo` is in the set.
+  pub fn contains_raw(&self, signo: c_int) -> bool {
+    let Some(bit) = (signo as usize).checked_sub(1) else {
+      return false;
+    };
+    self.0.get(bit / 8).is_some_and(|byte| byte & (1 << (bit % 8)) != 0)
+  }
+
+  pub fn contains(&self, signal: Signal) -> bool {
+    self.contains_raw(signal as c_int)
+  }
+
+  /// The numbers of the signals in the set, including the realtime signals.
+  pub fn iter_raw(&self) -> impl Iterator<Item = c_int> + '_ {
+    (1..=(self.0.len() * 8) as c_int).filter(|&signo| self.contains_raw(signo))
+  }
+
+  /// The standard signals in the set, realtime signals are skipped as they are not in [`Signal`].
+  pub fn iter(&self) -> impl Iterator<Item = Signal> + '_ {
+    self.iter_raw().filter_map(|signo| Signal::try_from(signo).ok())
+  }
+}
+
+/// The `sa_handler` of a `struct sigaction`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigHandler {
+  /// SIG_DFL
+  Default,
+  /// SIG_IGN
+  Ignore,
+  /// The address of the handler, which is `sa_sigaction` if SA_SIGINFO is set.
+  Handler(AddressType),
+}
+
+impl From<u64> for SigHandler {
+  fn from(handler: u64) -> Self {
+    match handler {
+      0 => Self::Default,
+      1 => Self::Ignore,
+      address => Self::Handler(address as AddressType),
+    }
+  }
+}
+
+/// The SA_* flags of a `struct sigaction`
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub enum SaFlags {
+  NoCldStop = 0x00000001,
+  NoCldWait = 0x00000002,
+  Siginfo = 0x00000004,
+  /// SA_UNSUPPORTED, used to detect whether the kernel supports the flags
+  Unsupported = 0x00000400,
+  ExposeTagbits = 0x00000800,
+  Restorer = 0x04000000,
+  Onstack = 0x08000000,
+  Restart = 0x10000000,
+  Nodefer = 0x40000000,
+  Resethand = 0x80000000,
+}
+
+/// The kernel ABI `struct sigaction` of rt_sigaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct KernelSigaction {
+  pub handler: SigHandler,
+  pub flags: BitFlags<SaFlags>,
+  /// `sa_restorer`, only present if SA_RESTORER is set
+  pub restorer: Option<AddressType>,
+  pub mask: SigSet,
+}
+
+impl KernelSigaction {
+  fn from_bytes(bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    let flags = BitFlags::from_bits_truncate(fields.u64(8));
+    Self {
+      handler: fields.u64(0).into(),
+      flags,
+      restorer: (SIGACTION_MASK_OFFSET > 16 && flags.contains(SaFlags::Restorer)).then(|| fields.ptr(16)),
+      mask: SigSet(bytes.get(SIGACTION_MASK_OFFSET..).unwrap_or_default().to_vec()),
+    }
+  }
+}
+
+/// Read a signal set of `size` bytes, `None` if `address` is NULL.
+pub(crate) fn read_sigset(pid: Pid, address: AddressType, size: usize) -> InspectResult<Option<SigSet>> {
+  if address.is_null() {
+    return Ok(None);
+  }
+  read_bytes(pid, address, size.min(SIGSET_SIZE_MAX))
+    .map(|bytes| Some(SigSet(bytes)))
+    .map_err(|e| e.map_ptrace_failure(|bytes| Some(SigSet(bytes))))
+}
+
+/// Read a `struct sigaction` whose mask has `sigsetsize` bytes, `None` if `address` is NULL.
+fn read_sigaction(pid: Pid, address: AddressType, sigsetsize: usize) -> InspectResult<Option<KernelSigaction>> {
+  if address.is_null() {
+    return Ok(None);
+  }
+  read_bytes(pid, address, SIGACTION_MASK_OFFSET + sigsetsize.min(SIGSET_SIZE_MAX))
+    .map(|bytes| Some(KernelSigaction::from_bytes(&bytes)))
+    .map_err(|e| e.map_ptrace_failure(|bytes| Some(KernelSigaction::from_bytes(&bytes))))
+}
+
+/// The new action
+impl InspectFromRawArgs<RtSigactionRawArgs> for InspectResult<Option<KernelSigaction>> {
+  fn inspect_from(pid: Pid, raw_args: &RtSigactionRawArgs) -> Self {
+    read_sigaction(pid, raw_args.act as AddressType, raw_args.sigsetsize)
+  }
+}
+
+/// The old action
+impl InspectResultFromRawArgs<RtSigactionRawArgs> for InspectResult<Option<KernelSigaction>> {
+  fn inspect_from(pid: Pid, raw_args: &RtSigactionRawArgs, _syscall_result: isize) -> Self {
+    read_sigaction(pid, raw_args.oact as AddressType, raw_args.sigsetsize)
+  }
+}
+
+/// The new mask
+impl InspectFromRawArgs<RtSigprocmaskRawArgs> for InspectResult<Option<SigSet>> {
+  fn inspect_from(pid: Pid, raw_args: &RtSigprocmaskRawArgs) -> Self {
+    read_sigset(pid, raw_args.set as AddressType, raw_args.sigsetsize)
+  }
+}
+
+/// The old mask
+impl InspectResultFromRawArgs<RtSigprocmaskRawArgs> for InspectResult<Option<SigSet>> {
+  fn inspect_from(pid: Pid, raw_args: &RtSigprocmaskRawArgs, _syscall_result: isize) -> Self {
+    read_sigset(pid, raw_args.oldset as AddressType, raw_args.sigsetsize)
+  }
+}
+
+impl InspectResultFromRawArgs<RtSigpendingRawArgs> for InspectResult<SigSet> {
+  fn inspect_from(pid: Pid, raw_args: &RtSigpendingRawArgs, _syscall_result: isize) -> Self {
+    read_sigset(pid, raw_args.set as AddressType, raw_args.sigsetsize)
+      .map_err(|e| e.map_ptrace_failure(Option::unwrap_or_default))?
+      .ok_or(InspectError::DependencyInspectFailure { field: "set" })
+  }
+}
+
+impl InspectFromRawArgs<RtSigsuspendRawArgs> for InspectResult<SigSet> {
+  fn inspect_from(pid: Pid, raw_args: &RtSigsuspendRawArgs) -> Self {
+    read_sigset(pid, raw_args.newset as AddressType, raw_args.sigsetsize)
+      .map_err(|e| e.map_ptrace_failure(Option::unwrap_or_default))?
+      .ok_or(InspectError::DependencyInspectFailure { field: "newset" })
+  }
+}
+
+impl InspectFromRawArgs<RtSigtimedwaitRawArgs> for InspectResult<SigSet> {
+  fn inspect_from(pid: Pid, raw_args: &RtSigtimedwaitRawArgs) -> Self {
+    read_sigset(pid, raw_args.set as AddressType, raw_args.sigsetsize)
+      .map_err(|e| e.map_ptrace_failure(Option::unwrap_or_default))?
+      .ok_or(InspectError::DependencyInspectFailure { field: "set" })
+  }
+}
@@ -0,0 +1,27 @@
+use nix::libc;
+
+use crate::{read_bytes, AddressType, InspectFromRawArgs, InspectResult, Pid, SetsockoptRawArgs, READ_SIZE_MAX};
+
+use super::{read_cbpf_program, CbpfInsn};
+
+/// The `optval` of setsockopt
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SockoptValue {
+  /// SO_ATTACH_FILTER and SO_ATTACH_REUSEPORT_CBPF
+  Filter(InspectResult<Vec<CbpfInsn>>),
+  /// The raw bytes of options that are not decoded yet, truncated to 1 MiB.
+  Raw(InspectResult<Vec<u8>>),
+}
+
+impl InspectFromRawArgs<SetsockoptRawArgs> for SockoptValue {
+  fn inspect_from(pid: Pid, raw_args: &SetsockoptRawArgs) -> Self {
+    let optval = raw_args.optval as AddressType;
+    match (raw_args.level, raw_args.optname) {
+      (libc::SOL_SOCKET, libc::SO_ATTACH_FILTER | libc::SO_ATTACH_REUSEPORT_CBPF) => {
+        Self::Filter(read_cbpf_program(pid, optval))
+      }
+      _ => Self::Raw(read_bytes(pid, optval, (raw_args.optlen as usize).min(READ_SIZE_MAX))),
+    }
+  }
+}
@@ -0,0 +1,55 @@
+use std::ffi::OsString;
+
+use nix::libc::c_int;
+
+use crate::{
+  AddressType, InspectFromRawArgs, InspectResult, InspectResultFromRawArgs, InspectSizedStringFromPid, Pid,
+  SyslogRawArgs,
+};
+
+int_enum! {
+  /// The SYSLOG_ACTION_* actions of syslog
+  pub enum SyslogAction: c_int {
+    Close = 0,
+    Open = 1,
+    Read = 2,
+    ReadAll = 3,
+    ReadClear = 4,
+    Clear = 5,
+    ConsoleOff = 6,
+    ConsoleOn = 7,
+    ConsoleLevel = 8,
+    SizeUnread = 9,
+    SizeBuffer = 10,
+  }
+}
+
+impl SyslogAction {
+  /// Whether the action reads the log into `buf`
+  pub fn reads_log(self) -> bool {
+    matches!(self, Self::Read | Self::ReadAll | Self::ReadClear)
+  }
+
+  /// Read the `len` bytes of log read into `buf`, `None` for the actions that don't read the log.
+  pub fn read_log(self, pid: Pid, buf: AddressType, len: usize) -> InspectResult<Option<OsString>> {
+    if !self.reads_log() {
+      return Ok(None);
+    }
+    InspectResult::<OsString>::inspect_from(pid, buf, len)
+      .map(Some)
+      .map_err(|e| e.map_ptrace_failure(Some))
+  }
+}
+
+impl InspectFromRawArgs<SyslogRawArgs> for SyslogAction {
+  fn inspect_from(_pid: Pid, raw_args: &SyslogRawArgs) -> Self {
+    raw_args.typ.into()
+  }
+}
+
+/// The log read into `buf`
+impl InspectResultFromRawArgs<SyslogRawArgs> for InspectResult<Option<OsString>> {
+  fn inspect_from(pid: Pid, raw_args: &SyslogRawArgs, syscall_result: isize) -> Self {
+    SyslogAction::from(raw_args.typ).read_log(pid, raw_args.buf as AddressType, syscall_result as usize)
+  }
+}
@@ -0,0 +1,155 @@
+use enumflags2::{bitflags, BitFlags};
+use nix::libc::{c_int, c_uint, idtype_t, SIGTRAP};
+
+use crate::{
+  AddressType, InspectFromPid, InspectFromRawArgs, InspectResult, InspectResultFromRawArgs, Pid, Wait4RawArgs,
+  WaitidRawArgs,
+};
+
+use super::SignalInfo;
+
+/// The options of wait4 and waitid
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WaitOptions {
+  /// WNOHANG
+  NoHang = 0x00000001,
+  /// WUNTRACED for wait4, WSTOPPED for waitid
+  Stopped = 0x00000002,
+  /// WEXITED
+  Exited = 0x00000004,
+  /// WCONTINUED
+  Continued = 0x00000008,
+  /// WNOWAIT
+  NoWait = 0x01000000,
+  /// __WNOTHREAD
+  NoThread = 0x20000000,
+  /// __WALL
+  All = 0x40000000,
+  /// __WCLONE
+  Clone = 0x80000000,
+}
+
+int_enum! {
+  /// The P_* id types of waitid
+  pub enum IdType: idtype_t {
+    All = 0,
+    Pid = 1,
+    Pgid = 2,
+    Pidfd = 3,
+  }
+}
+
+/// A wait status stored by wait4, equivalent to `nix::sys::wait::WaitStatus` without the pid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitStatus {
+  /// The child exited with the exit code.
+  Exited(c_int),
+  /// The child was killed by the signal.
+  Signaled { signal: c_int, core_dumped: bool },
+  /// The child was stopped by the signal.
+  Stopped(c_int),
+  /// The child stopped at a PTRACE_EVENT_* stop.
+  PtraceEvent { signal: c_int, event: c_int },
+  /// The child stopped at a syscall stop with PTRACE_O_TRACESYSGOOD.
+  PtraceSyscall,
+  /// The child was resumed by SIGCONT.
+  Continued,
+}
+
+impl From<c_int> for WaitStatus {
+  fn from(status: c_int) -> Self {
+    if status & 0x7f == 0 {
+      Self::Exited((status >> 8) & 0xff)
+    } else if status & 0xff == 0x7f {
+      let signal = (status >> 8) & 0xff;
+      match status >> 16 {
+        0 if signal == SIGTRAP | 0x80 => Self::PtraceSyscall,
+        0 => Self::Stopped(signal),
+        event => Self::PtraceEvent { signal, event },
+      }
+    } else if status == 0xffff {
+      Self::Continued
+    } else {
+      Self::Signaled {
+        signal: status & 0x7f,
+        core_dumped: status & 0x80 != 0,
+      }
+    }
+  }
+}
+
+/// The state change of a child, decoded from the CLD_* `si_code` of its SIGCHLD siginfo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChildState {
+  /// CLD_EXITED, with the exit code
+  Exited(c_int),
+  /// CLD_KILLED, with the signal
+  Killed(c_int),
+  /// CLD_DUMPED, with the signal
+  Dumped(c_int),
+  /// CLD_TRAPPED, with the signal
+  Trapped(c_int),
+  /// CLD_STOPPED, with the signal
+  Stopped(c_int),
+  /// CLD_CONTINUED, with SIGCONT
+  Continued(c_int),
+  /// An unknown `si_code`
+  Unknown { code: c_int, status: c_int },
+}
+
+impl ChildState {
+  pub fn from_code(code: c_int, status: c_int) -> Self {
+    match code {
+      1 => Self::Exited(status),
+      2 => Self::Killed(status),
+      3 => Self::Dumped(status),
+      4 => Self::Trapped(status),
+      5 => Self::Stopped(status),
+      6 => Self::Continued(status),
+      code => Self::Unknown { code, status },
+    }
+  }
+}
+
+impl InspectFromRawArgs<Wait4RawArgs> for BitFlags<WaitOptions> {
+  fn inspect_from(_pid: Pid, raw_args: &Wait4RawArgs) -> Self {
+    BitFlags::from_bits_truncate(raw_args.options as c_uint)
+  }
+}
+
+impl InspectFromRawArgs<WaitidRawArgs> for BitFlags<WaitOptions> {
+  fn inspect_from(_pid: Pid, raw_args: &WaitidRawArgs) -> Self {
+    BitFlags::from_bits_truncate(raw_args.options as c_uint)
+  }
+}
+
+impl InspectFromRawArgs<WaitidRawArgs> for IdType {
+  fn inspect_from(_pid: Pid, raw_args: &WaitidRawArgs) -> Self {
+    raw_args.idtype.into()
+  }
+}
+
+/// The status, `None` if `wstatus` is NULL or no child changed state.
+impl InspectResultFromRawArgs<Wait4RawArgs> for InspectResult<Option<WaitStatus>> {
+  fn inspect_from(pid: Pid, raw_args: &Wait4RawArgs, syscall_result: isize) -> Self {
+    if syscall_result == 0 {
+      return Ok(None);
+    }
+    let decode = |status: Option<c_int>| status.map(WaitStatus::from);
+    InspectResult::<Option<c_int>>::inspect_from(pid, raw_args.wstatus as AddressType)
+      .map(decode)
+      .map_err(|e| e.map_ptrace_failure(decode))
+  }
+}
+
+/// The siginfo of the child, `None` if `infop` is NULL or no child changed state, which happens with WNOHANG.
+impl InspectResultFromRawArgs<WaitidRawArgs> for InspectResult<Option<SignalInfo>> {
+  fn inspect_from(pid: Pid, raw_args: &WaitidRawArgs, _syscall_result: isize) -> Self {
+    let decode = |info: Option<SignalInfo>| info.filter(|info| info.signo() != 0);
+    <InspectResult<Option<SignalInfo>> as InspectFromPid>::inspect_from(pid, raw_args.infop as AddressType)
+      .map(decode)
+      .map_err(|e| e.map_ptrace_failure(decode))
+  }
+}
@@ -0,0 +1,275 @@
+use std::ffi::CString;
+
+use enumflags2::{bitflags, BitFlags};
+
+use crate::{
+  read_bytes, AddressType, FgetxattrRawArgs, FsetxattrRawArgs, GetxattrRawArgs, GetxattratRawArgs, InspectError,
+  InspectFromPid, InspectFromRawArgs, InspectResult, InspectResultFromRawArgs, LgetxattrRawArgs, LsetxattrRawArgs, Pid,
+  SetxattrRawArgs, SetxattratRawArgs, StructFields,
+};
+
+use super::{read_extensible, CapabilitySet, Extensible};
+
+/// XATTR_SIZE_MAX, the kernel rejects larger values.
+const XATTR_SIZE_MAX: usize = 65536;
+/// XATTR_ARGS_SIZE_VER0, the size of the `struct xattr_args` fields known to this crate
+const XATTR_ARGS_SIZE_VER0: usize = 16;
+
+/// POSIX_ACL_XATTR_VERSION
+const POSIX_ACL_XATTR_VERSION: u32 = 0x0002;
+/// ACL_UNDEFINED_ID, the id of entries that are not for a specific user or group
+const ACL_UNDEFINED_ID: u32 = u32::MAX;
+
+/// VFS_CAP_REVISION_MASK, the revision in `magic_etc` of `struct vfs_cap_data`
+const VFS_CAP_REVISION_MASK: u32 = 0xff000000;
+const VFS_CAP_REVISION_1: u32 = 0x01000000;
+const VFS_CAP_REVISION_2: u32 = 0x02000000;
+const VFS_CAP_REVISION_3: u32 = 0x03000000;
+/// VFS_CAP_FLAGS_EFFECTIVE
+const VFS_CAP_FLAGS_EFFECTIVE: u32 = 0x000001;
+
+int_enum! {
+  /// The ACL_* tags of a POSIX ACL entry
+  pub enum PosixAclTag: u16 {
+    UserObj = 0x01,
+    User = 0x02,
+    GroupObj = 0x04,
+    Group = 0x08,
+    Mask = 0x10,
+    Other = 0x20,
+  }
+}
+
+/// The ACL_* permissions of a POSIX ACL entry
+#[bitflags]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum PosixAclPerm {
+  Execute = 0x01,
+  Write = 0x02,
+  Read = 0x04,
+}
+
+/// An entry of a POSIX ACL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PosixAclEntry {
+  pub tag: PosixAclTag,
+  pub perm: BitFlags<PosixAclPerm>,
+  /// The user or group id for ACL_USER and ACL_GROUP entries
+  pub id: Option<u32>,
+}
+
+/// The file capabilities, `struct vfs_cap_data` or `struct vfs_ns_cap_data`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileCapabilities {
+  /// The VFS_CAP_REVISION_* revision, from 1 to 3
+  pub revision: u8,
+  /// Whether the permitted capabilities are raised in the effective set
+  pub effective: bool,
+  pub permitted: CapabilitySet,
+  pub inheritable: CapabilitySet,
+  /// The root uid of the user namespace for revision 3
+  pub rootid: Option<u32>,
+}
+
+/// An extended attribute value, decoded according to the attribute name.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum XattrValue {
+  /// `system.posix_acl_access` and `system.posix_acl_default`
+  PosixAcl(Vec<PosixAclEntry>),
+  /// `security.capability`
+  FileCapabilities(FileCapabilities),
+  /// Other attributes, or well-known attributes whose value is malformed
+  Raw(Vec<u8>),
+}
+
+impl XattrValue {
+  /// Decode the value of the attribute `name`.
+  pub fn decode(name: &[u8], value: Vec<u8>) -> Self {
+    let decoded = match name {
+      b"system.posix_acl_access" | b"system.posix_acl_default" => Self::decode_posix_acl(&value),
+      b"security.capability" => Self::decode_file_capabilities(&value),
+      _ => None,
+    };
+    decoded.unwrap_or(Self::Raw(value))
+  }
+
+  fn decode_posix_acl(value: &[u8]) -> Option<Self> {
+    if value.len() < 4 || !(value.len() - 4).is_multiple_of(8) || StructFields(value).u32(0) != POSIX_ACL_XATTR_VERSION
+    {
+      return None;
+    }
+    Some(Self::PosixAcl(
+      value[4..]
+        .chunks_exact(8)
+        .map(|entry| {
+          let fields = StructFields(entry);
+          let id = fields.u32(4);
+          PosixAclEntry {
+            tag: fields.u16(0).into(),
+            perm: BitFlags::from_bits_truncate(fields.u16(2)),
+            id: (id != ACL_UNDEFINED_ID).then_some(id),
+          }
+        })
+        .collect(),
+    ))
+  }
+
+  fn decode_file_capabilities(value: &[u8]) -> Option<Self> {
+    let fields = StructFields(value);
+    let magic_etc = fields.u32(0);
+    let size = match magic_etc & VFS_CAP_REVISION_MASK {
+      VFS_CAP_REVISION_1 => 12,
+      VFS_CAP_REVISION_2 => 20,
+      VFS_CAP_REVISION_3 => 24,
+      _ => return None,
+    };
+    if value.len() != size {
+      return None;
+    }
+    let high = |offset: usize| {
+      if size > 12 {
+        (fields.u32(offset) as u64) << 32
+      } else {
+        0
+      }
+    };
+    Some(Self::FileCapabilities(FileCapabilities {
+      revision: (magic_etc >> 24) as u8,
+      effective: magic_etc & VFS_CAP_FLAGS_EFFECTIVE != 0,
+      permitted: CapabilitySet(fields.u32(4) as u64 | high(12)),
+      inheritable: CapabilitySet(fields.u32(8) as u64 | high(16)),
+      rootid: (size == 24).then(|| fields.u32(20)),
+    }))
+  }
+}
+
+/// Read `size` bytes of the value of the attribute whose name is at `name`.
+fn read_xattr_value(pid: Pid, name: AddressType, value: AddressType, size: usize) -> InspectResult<XattrValue> {
+  let name = InspectResult::<CString>::inspect_from(pid, name).unwrap_or_default();
+  let decode = |bytes| XattrValue::decode(name.as_bytes(), bytes);
+  read_bytes(pid, value, size.min(XATTR_SIZE_MAX))
+    .map(decode)
+    .map_err(|e| e.map_ptrace_failure(decode))
+}
+
+macro_rules! impl_xattr_value {
+  ($($set:ident),* ; $($get:ident),*) => {
+    $(
+      /// The value to set
+      impl InspectFromRawArgs<$set> for InspectResult<XattrValue> {
+        fn inspect_from(pid: Pid, raw_args: &$set) -> Self {
+          read_xattr_value(pid, raw_args.name as AddressType, raw_args.value as AddressType, raw_args.size)
+        }
+      }
+    )*
+    $(
+      /// The value retrieved, `None` if the size of the value is queried with a zero `size`.
+      impl InspectResultFromRawArgs<$get> for InspectResult<Option<XattrValue>> {
+        fn inspect_from(pid: Pid, raw_args: &$get, syscall_result: isize) -> Self {
+          if raw_args.size == 0 {
+            return Ok(None);
+          }
+          read_xattr_value(pid, raw_args.name as AddressType, raw_args.value as AddressType, syscall_result as usize)
+            .map(Some)
+            .map_err(|e| e.map_ptrace_failure(Some))
+        }
+      }
+    )*
+  };
+}
+
+impl_xattr_value!(SetxattrRawArgs, LsetxattrRawArgs, FsetxattrRawArgs; GetxattrRawArgs, LgetxattrRawArgs, FgetxattrRawArgs);
+
+/// The decoded `struct xattr_args` of getxattrat and setxattrat
+#[derive(Debug, Clone, PartialEq)]
+pub struct XattrArgs {
+  /// The address of the value buffer
+  pub value: AddressType,
+  pub size: u32,
+  /// XATTR_CREATE or XATTR_REPLACE for setxattrat
+  pub flags: u32,
+}
+
+impl XattrArgs {
+  fn from_bytes(bytes: &[u8]) -> Self {
+    let fields = StructFields(bytes);
+    Self {
+      value: fields.ptr(0),
+      size: fields.u32(8),
+      flags: fields.u32(12),
+    }
+  }
+}
+
+/// Read `struct xattr_args` honouring its `usize`.
+///
+/// The kernel rejects a `usize` smaller than XATTR_ARGS_SIZE_VER0 with EINVAL,
+/// such an argument is still decoded with the missing fields zero.
+fn read_xattr_args(pid: Pid, address: AddressType, size: usize) -> InspectResult<Extensible<XattrArgs>> {
+  read_extensible(pid, address, size, XATTR_ARGS_SIZE_VER0, XattrArgs::from_bytes)
+}
+
+impl InspectFromRawArgs<GetxattratRawArgs> for InspectResult<Extensible<XattrArgs>> {
+  fn inspect_from(pid: Pid, raw_args: &GetxattratRawArgs) -> Self {
+    read_xattr_args(pid, raw_args.uargs as AddressType, raw_args.r#usize)
+  }
+}
+
+impl InspectFromRawArgs<SetxattratRawArgs> for InspectResult<Extensible<XattrArgs>> {
+  fn inspect_from(pid: Pid, raw_args: &SetxattratRawArgs) -> Self {
+    read_xattr_args(pid, raw_args.uargs as AddressType, raw_args.r#usize)
+  }
+}
+
+/// The value to set, pointed to by `struct xattr_args`
+impl InspectFromRawArgs<SetxattratRawArgs> for InspectResult<XattrValue> {
+  fn inspect_from(pid: Pid, raw_args: &SetxattratRawArgs) -> Self {
+    let args = read_xattr_args(pid, raw_args.uargs as AddressType, raw_args.r#usize)
+      .map_err(|_| InspectError::DependencyInspectFailure { field: "uargs" })?
+      .value;
+    read_xattr_value(pid, raw_args.name as AddressType, args.value, args.size as usize)
+  }
+}
+
+/// The value retrieved into the buffer of `struct xattr_args`, `None` if the size of the value is queried.
+impl InspectResultFromRawArgs<GetxattratRawArgs> for InspectResult<Option<XattrValue>> {
+  fn inspect_from(pid: Pid, raw_args: &GetxattratRawArgs, syscall_result: isize) -> Self {
+    let args = read_xattr_args(pid, raw_args.uargs as AddressType, raw_args.r#usize)
+      .map_err(|_| InspectError::DependencyInspectFailure { field: "uargs" })?
+      .value;
+    if args.size == 0 {
+      return Ok(None);
+    }
+    read_xattr_value(pid, raw_args.name as AddressType, args.value, syscall_result as usize)
+      .map(Some)
+      .map_err(|e| e.map_ptrace_failure(Some))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_xattr_args_trailing_bytes_are_kept() {
+    let mut bytes = [0u8; 24];
+    bytes[0..8].copy_from_slice(&0x1000u64.to_ne_bytes());
+    bytes[8..12].copy_from_slice(&64u32.to_ne_bytes());
+    bytes[12..16].copy_from_slice(&1u32.to_ne_bytes());
+    bytes[16] = 7;
+    assert_eq!(
+      read_xattr_args(Pid::this(), bytes.as_ptr() as AddressType, bytes.len()),
+      Ok(Extensible {
+        size: 24,
+        value: XattrArgs {
+          value: 0x1000 as AddressType,
+          size: 64,
+          flags: 1,
+        },
+        trailing: vec![7, 0, 0, 0, 0, 0, 0, 0],
+      })
+    );
+  }
+}
@@ -0,0 +1,96 @@
+use nix::{libc, unistd::Pid};
+use ptrace_syscalls::{
+  types::{io_event, AioBuffer, AioCompletion, AioRequest, IocbCmd},
+  AddressType,
+};
+
+fn iocb(opcode: IocbCmd, buf: u64, nbytes: u64) -> libc::iocb {
+  let mut iocb: libc::iocb = unsafe { std::mem::zeroed() };
+  iocb.aio_data = 0xdead;
+  iocb.aio_lio_opcode = opcode.into();
+  iocb.aio_fildes = 3;
+  iocb.aio_buf = buf;
+  iocb.aio_nbytes = nbytes;
+  iocb.aio_offset = 4096;
+  iocb
+}
+
+#[test]
+fn test_aio_request_pread() {
+  let iocb = iocb(IocbCmd::Pread, 0x1000, 512);
+  let request = AioRequest::from_iocb(Pid::this(), 0x2000 as AddressType, &iocb);
+  assert_eq!(request.iocb, 0x2000 as AddressType);
+  assert_eq!(request.data, 0xdead);
+  assert_eq!(request.opcode, IocbCmd::Pread);
+  assert_eq!(request.fd, 3);
+  assert_eq!(request.offset, 4096);
+  assert_eq!(
+    request.buffer,
+    AioBuffer::Read {
+      buf: 0x1000 as AddressType,
+      nbytes: 512
+    }
+  );
+  assert_eq!(request.resfd, None);
+}
+
+#[test]
+fn test_aio_request_pwrite_reads_data() {
+  let data = b"hello asynchronous io".to_vec();
+  let iocb = iocb(IocbCmd::Pwrite, data.as_ptr() as u64, data.len() as u64);
+  let request = AioRequest::from_iocb(Pid::this(), 0x2000 as AddressType, &iocb);
+  assert_eq!(
+    request.buffer,
+    AioBuffer::Write {
+      nbytes: data.len() as u64,
+      data: Ok(data.clone())
+    }
+  );
+}
+
+#[test]
+fn test_aio_request_poll_and_resfd() {
+  let mut iocb = iocb(IocbCmd::Poll, libc::POLLIN as u64, 0);
+  iocb.aio_flags = 1;
+  iocb.aio_resfd = 7;
+  let request = AioRequest::from_iocb(Pid::this(), 0x2000 as AddressType, &iocb);
+  assert_eq!(request.buffer, AioBuffer::Poll { events: 1 });
+  assert_eq!(request.resfd, Some(7));
+  let noop = AioRequest::from_iocb(Pid::this(), 0x2000 as AddressType, &self::iocb(IocbCmd::Noop, 0, 0));
+  assert_eq!(noop.buffer, AioBuffer::None);
+}
+
+#[test]
+fn test_aio_completion_links_to_request() {
+  let iocb = Box::new(iocb(IocbCmd::Fsync, 0, 0));
+  let address = &*iocb as *const libc::iocb as u64;
+  let event = io_event {
+    data: 0xdead,
+    obj: address,
+    res: 0,
+    res2: 0,
+  };
+  let completion = AioCompletion::from_event(Pid::this(), event.clone());
+  let request = completion.request.unwrap().unwrap();
+  assert!(request.matches(&event));
+  assert_eq!(request.opcode, IocbCmd::Fsync);
+  let stale = io_event { data: 0xbeef, ..event };
+  assert_eq!(AioCompletion::from_event(Pid::this(), stale).request, Ok(None));
+}
+
+#[test]
+fn test_aio_request_iovec_count_is_capped() {
+  let iov = vec![
+    libc::iovec {
+      iov_base: std::ptr::null_mut(),
+      iov_len: 0,
+    };
+    2048
+  ];
+  let iocb = iocb(IocbCmd::Pwritev, iov.as_ptr() as u64, u64::MAX);
+  let request = AioRequest::from_iocb(Pid::this(), 0x2000 as AddressType, &iocb);
+  let AioBuffer::Vectored { iov } = request.buffer else {
+    panic!("not vectored: {:?}", request.buffer);
+  };
+  assert_eq!(iov.unwrap().len(), 1024);
+}
@@ -0,0 +1,35 @@
+use enumflags2::BitFlags;
+use ptrace_syscalls::types::{CpuSet, Mempolicy, MempolicyMode, MempolicyModeFlags, NodeMask};
+
+#[test]
+fn test_cpu_set_round_trip_and_ranges() {
+  let set = CpuSet::from(vec![8, 0, 2, 1, 3, 65, 3]);
+  assert_eq!(set, CpuSet(vec![0, 1, 2, 3, 8, 65]));
+  assert_eq!(set.to_string(), "0-3,8,65");
+  let bitmap = set.to_bitmap();
+  assert_eq!(bitmap.len(), 16);
+  assert_eq!(CpuSet::from_bitmap(&bitmap, 128), set);
+  // Bits past nbits are ignored
+  assert_eq!(CpuSet::from_bitmap(&bitmap, 64).to_string(), "0-3,8");
+  assert!(set.contains(65));
+  assert!(!set.contains(4));
+  assert_eq!(CpuSet::default().to_string(), "");
+}
+
+#[test]
+fn test_node_mask_ranges() {
+  let mask: NodeMask = [1, 2, 4, 5, 6].into_iter().collect();
+  assert_eq!(mask.to_string(), "1-2,4-6");
+}
+
+#[test]
+fn test_mempolicy_mode_flags() {
+  assert_eq!(
+    Mempolicy::from(2 | 1 << 15),
+    Mempolicy {
+      mode: MempolicyMode::Bind,
+      flags: BitFlags::from(MempolicyModeFlags::StaticNodes),
+    }
+  );
+  assert_eq!(Mempolicy::from(6).mode, MempolicyMode::WeightedInterleave);
+}
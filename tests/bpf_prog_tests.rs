@@ -0,0 +1,79 @@
+use nix::libc::{
+  sock_filter, BPF_ABS, BPF_JEQ, BPF_JMP, BPF_K, BPF_LD, BPF_RET, BPF_W, SECCOMP_RET_ALLOW, SECCOMP_RET_ERRNO,
+};
+use ptrace_syscalls::{
+  types::{bpf_insn, disassemble_ebpf, disassemble_seccomp_filter, SeccompAction, SeccompAnnotation},
+  SYS_execve,
+};
+
+fn stmt(code: u32, k: u32) -> sock_filter {
+  sock_filter {
+    code: code as u16,
+    jt: 0,
+    jf: 0,
+    k,
+  }
+}
+
+fn jump(code: u32, k: u32, jt: u8, jf: u8) -> sock_filter {
+  sock_filter {
+    code: code as u16,
+    jt,
+    jf,
+    k,
+  }
+}
+
+#[test]
+fn test_seccomp_filter_disassembly() {
+  let filter = [
+    stmt(BPF_LD | BPF_W | BPF_ABS, 0),
+    jump(BPF_JMP | BPF_JEQ | BPF_K, SYS_execve as u32, 0, 1),
+    stmt(BPF_RET | BPF_K, SECCOMP_RET_ERRNO | 1),
+    stmt(BPF_RET | BPF_K, SECCOMP_RET_ALLOW),
+  ];
+  let insns = disassemble_seccomp_filter(&filter);
+  assert_eq!(insns[0].to_string(), "ld [0]");
+  assert_eq!(insns[1].annotation, Some(SeccompAnnotation::Syscall("execve")));
+  assert_eq!(insns[1].to_string(), format!("jeq #{:#x}, jt 2, jf 3 ; execve", SYS_execve));
+  assert_eq!(insns[2].annotation, Some(SeccompAnnotation::Action(SeccompAction::Errno { errno: 1 })));
+  assert_eq!(insns[3].annotation, Some(SeccompAnnotation::Action(SeccompAction::Allow)));
+}
+
+#[test]
+fn test_ebpf_disassembly() {
+  let insn = |code: u8, dst: u8, src: u8, off: i16, imm: i32| bpf_insn {
+    code,
+    regs: if cfg!(target_endian = "little") {
+      dst | src << 4
+    } else {
+      dst << 4 | src
+    },
+    off,
+    imm,
+  };
+  let insns = [
+    // r1 = 0x100000002 ll
+    insn(0x18, 1, 0, 0, 2),
+    insn(0x00, 0, 0, 0, 1),
+    // r0 = *(u32 *)(r1 + 4)
+    insn(0x61, 0, 1, 4, 0),
+    // if r0 == 0x3 goto pc+1
+    insn(0x15, 0, 0, 1, 3),
+    // w0 += 1
+    insn(0x04, 0, 0, 0, 1),
+    // exit
+    insn(0x95, 0, 0, 0, 0),
+  ];
+  let disassembled: Vec<String> = disassemble_ebpf(&insns).iter().map(ToString::to_string).collect();
+  assert_eq!(
+    disassembled,
+    [
+      "r1 = 0x100000002 ll",
+      "r0 = *(u32 *)(r1 + 4)",
+      "if r0 == 0x3 goto pc+1",
+      "w0 += 0x1",
+      "exit"
+    ]
+  );
+}
@@ -0,0 +1,79 @@
+use std::ffi::CString;
+
+use ptrace_syscalls::types::{BpfAttr, BpfCmd, BpfMapType};
+
+fn put(attr: &mut [u8], offset: usize, bytes: &[u8]) {
+  attr[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+#[test]
+fn test_bpf_cmd_decoding() {
+  assert_eq!(BpfCmd::from(0), BpfCmd::MapCreate);
+  assert_eq!(BpfCmd::from(5), BpfCmd::ProgLoad);
+  assert_eq!(BpfCmd::from(36), BpfCmd::TokenCreate);
+  assert_eq!(BpfCmd::from(1000), BpfCmd::Unknown(1000));
+}
+
+#[test]
+fn test_bpf_map_create_decoding() {
+  let mut attr = vec![0u8; 72];
+  put(&mut attr, 0, &u32::from(BpfMapType::PercpuHash).to_ne_bytes());
+  put(&mut attr, 4, &4u32.to_ne_bytes());
+  put(&mut attr, 8, &8u32.to_ne_bytes());
+  put(&mut attr, 12, &1024u32.to_ne_bytes());
+  put(&mut attr, 20, &(-1i32).to_ne_bytes());
+  put(&mut attr, 28, b"counters");
+  put(&mut attr, 48, &7i32.to_ne_bytes());
+  put(&mut attr, 64, &42u64.to_ne_bytes());
+  assert_eq!(
+    BpfAttr::from_bytes(BpfCmd::MapCreate, attr),
+    BpfAttr::MapCreate {
+      map_type: BpfMapType::PercpuHash,
+      key_size: 4,
+      value_size: 8,
+      max_entries: 1024,
+      map_flags: 0,
+      inner_map_fd: -1,
+      numa_node: 0,
+      map_name: CString::new("counters").unwrap(),
+      map_ifindex: 0,
+      btf_fd: 7,
+      map_extra: 42,
+    }
+  );
+}
+
+#[test]
+fn test_bpf_attr_shorter_than_known() {
+  // An old caller passes a smaller attr, the fields after it are zero.
+  let mut attr = vec![0u8; 16];
+  put(&mut attr, 0, &3i32.to_ne_bytes());
+  put(&mut attr, 4, &4i32.to_ne_bytes());
+  put(&mut attr, 8, &38u32.to_ne_bytes());
+  assert_eq!(
+    BpfAttr::from_bytes(BpfCmd::LinkCreate, attr),
+    BpfAttr::LinkCreate {
+      prog_fd: 3,
+      target_fd: 4,
+      attach_type: 38,
+      flags: 0,
+    }
+  );
+  let attr = vec![1u8; 12];
+  assert_eq!(
+    BpfAttr::from_bytes(BpfCmd::ProgAttach, attr),
+    BpfAttr::ProgAttach {
+      target_fd: 0x01010101,
+      attach_bpf_fd: 0x01010101,
+      attach_type: 0x01010101,
+      attach_flags: 0,
+      replace_bpf_fd: 0,
+    }
+  );
+}
+
+#[test]
+fn test_bpf_attr_undecoded_command() {
+  let attr = vec![1, 2, 3, 4];
+  assert_eq!(BpfAttr::from_bytes(BpfCmd::EnableStats, attr.clone()), BpfAttr::Other(attr));
+}
@@ -0,0 +1,28 @@
+use ptrace_syscalls::types::{CapUserData, Capability, CapabilitySet};
+
+#[test]
+fn test_v3_cap_user_data_keeps_high_capabilities() {
+  // Two cap_user_data elements of {effective, permitted, inheritable}
+  let bytes: Vec<u8> = [1u32 << 12, 1 << 12 | 1 << 21, 0, 1 << 7, 1 << 7, 0]
+    .iter()
+    .flat_map(|x| x.to_ne_bytes())
+    .collect();
+  let data = CapUserData::from_bytes(&bytes);
+  assert!(data.effective.contains(Capability::NetAdmin));
+  assert!(data.effective.contains(Capability::Bpf));
+  assert!(!data.effective.contains(Capability::SysAdmin));
+  assert_eq!(
+    data.permitted.iter().collect::<Vec<_>>(),
+    [Capability::NetAdmin, Capability::SysAdmin, Capability::Bpf]
+  );
+  assert_eq!(data.inheritable, CapabilitySet(0));
+  // A single v1 element leaves the high capabilities empty
+  let data = CapUserData::from_bytes(&bytes[..12]);
+  assert_eq!(data.effective, CapabilitySet(1 << 12));
+  assert_eq!(format!("{:?}", data.effective), "{NetAdmin}");
+}
+
+#[test]
+fn test_unknown_capabilities_are_kept() {
+  assert_eq!(CapabilitySet(1 << 63).iter().collect::<Vec<_>>(), [Capability::Unknown(63)]);
+}
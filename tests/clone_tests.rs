@@ -0,0 +1,66 @@
+use enumflags2::BitFlags;
+use nix::{libc, unistd::Pid};
+use ptrace_syscalls::types::{CloneFlags, CloneRequest, Extensible};
+
+fn clone_args(words: &[(usize, u64)]) -> Vec<u8> {
+  let mut bytes = vec![0u8; 88];
+  for &(offset, value) in words {
+    bytes[offset..offset + 8].copy_from_slice(&value.to_ne_bytes());
+  }
+  bytes
+}
+
+#[test]
+fn test_clone_args_decoding() {
+  let flags = (libc::CLONE_VM | libc::CLONE_PIDFD | libc::CLONE_PARENT_SETTID) as u64;
+  let bytes = clone_args(&[
+    (0, flags),
+    (8, 0x1000),
+    (16, 0x2000),
+    (24, 0x3000),
+    (32, libc::SIGCHLD as u64),
+    (40, 0x7000_0000),
+    (48, 0x10000),
+    (56, 0x4000),
+  ]);
+  let request = CloneRequest::from_clone_args(Pid::this(), &bytes);
+  assert_eq!(
+    request,
+    CloneRequest {
+      flags: CloneFlags::Vm | CloneFlags::Pidfd | CloneFlags::ParentSetTid,
+      exit_signal: libc::SIGCHLD,
+      stack: 0x7000_0000 as _,
+      stack_size: 0x10000,
+      tls: 0x4000,
+      pidfd: 0x1000 as _,
+      parent_tid: 0x3000 as _,
+      child_tid: 0x2000 as _,
+      set_tid: Ok(vec![]),
+      cgroup: None,
+    }
+  );
+}
+
+#[test]
+fn test_clone_args_cgroup_only_with_into_cgroup() {
+  let bytes = clone_args(&[(80, 5)]);
+  let request = CloneRequest::from_clone_args(Pid::this(), &bytes);
+  assert_eq!(request.flags, BitFlags::empty());
+  assert_eq!(request.cgroup, None);
+  let bytes = clone_args(&[(0, 0x200000000), (80, 5)]);
+  let request = CloneRequest::from_clone_args(Pid::this(), &bytes);
+  assert_eq!(request.flags, CloneFlags::IntoCgroup);
+  assert_eq!(request.cgroup, Some(5));
+}
+
+#[test]
+fn test_clone_args_smaller_than_ver0() {
+  // The kernel rejects it with EINVAL, the fields that were passed are still decoded.
+  let bytes = clone_args(&[(0, libc::CLONE_VM as u64), (32, libc::SIGCHLD as u64)]);
+  let args = Extensible::decode(40, &bytes[..40], 88, |bytes| CloneRequest::from_clone_args(Pid::this(), bytes));
+  assert_eq!(args.size, 40);
+  assert_eq!(args.value.flags, CloneFlags::Vm);
+  assert_eq!(args.value.exit_signal, libc::SIGCHLD);
+  assert_eq!(args.value.stack, std::ptr::null_mut());
+  assert_eq!(args.value.set_tid, Ok(vec![]));
+}
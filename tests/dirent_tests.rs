@@ -0,0 +1,72 @@
+use std::ffi::OsString;
+
+use ptrace_syscalls::types::{parse_dirents, parse_dirents64, DirEntry, FileType};
+
+fn dirent64(ino: u64, off: i64, kind: u8, name: &str) -> Vec<u8> {
+  let reclen = (19 + name.len() + 1).next_multiple_of(8);
+  let mut record = Vec::with_capacity(reclen);
+  record.extend(ino.to_ne_bytes());
+  record.extend(off.to_ne_bytes());
+  record.extend((reclen as u16).to_ne_bytes());
+  record.push(kind);
+  record.extend(name.as_bytes());
+  record.resize(reclen, 0);
+  record
+}
+
+#[test]
+fn test_dirent64_parsing() {
+  let mut buf = dirent64(2, 1, 4, ".");
+  buf.extend(dirent64(42, 2, 8, "a-long-file-name.txt"));
+  assert_eq!(
+    parse_dirents64(&buf),
+    vec![
+      DirEntry {
+        ino: 2,
+        off: 1,
+        kind: FileType::Dir,
+        name: OsString::from("."),
+      },
+      DirEntry {
+        ino: 42,
+        off: 2,
+        kind: FileType::Reg,
+        name: OsString::from("a-long-file-name.txt"),
+      },
+    ]
+  );
+}
+
+#[test]
+fn test_dirent_parsing_stops_at_malformed_reclen() {
+  let mut buf = dirent64(7, 1, 10, "link");
+  let mut malformed = dirent64(8, 2, 8, "file");
+  malformed[16..18].copy_from_slice(&1000u16.to_ne_bytes());
+  buf.extend(malformed);
+  let entries = parse_dirents64(&buf);
+  assert_eq!(entries.len(), 1);
+  assert_eq!(entries[0].kind, FileType::Link);
+  // A zero reclen must not loop forever.
+  assert!(parse_dirents(&[0; 32]).is_empty());
+}
+
+#[test]
+fn test_dirent_type_is_last_byte() {
+  // struct linux_dirent: ino, off, reclen, name, pad, type
+  let mut record = Vec::new();
+  record.extend(5u64.to_ne_bytes());
+  record.extend(1i64.to_ne_bytes());
+  record.extend(24u16.to_ne_bytes());
+  record.extend(b"foo\0");
+  record.resize(23, 0);
+  record.push(12);
+  assert_eq!(
+    parse_dirents(&record),
+    vec![DirEntry {
+      ino: 5,
+      off: 1,
+      kind: FileType::Sock,
+      name: OsString::from("foo"),
+    }]
+  );
+}
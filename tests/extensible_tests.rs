@@ -0,0 +1,30 @@
+use ptrace_syscalls::types::Extensible;
+
+fn words(bytes: &[u8]) -> Vec<u64> {
+  bytes
+    .chunks(8)
+    .map(|c| u64::from_ne_bytes(c.try_into().unwrap()))
+    .collect()
+}
+
+#[test]
+fn test_smaller_struct_is_zero_filled() {
+  let bytes = 7u64.to_ne_bytes();
+  let decoded = Extensible::decode(8, &bytes, 24, words);
+  assert_eq!(
+    decoded,
+    Extensible {
+      size: 8,
+      value: vec![7, 0, 0],
+      trailing: vec![]
+    }
+  );
+}
+
+#[test]
+fn test_larger_struct_keeps_trailing_bytes() {
+  let bytes: Vec<u8> = [1u64, 2, 3].iter().flat_map(|x| x.to_ne_bytes()).collect();
+  let decoded = Extensible::decode(24, &bytes, 16, words);
+  assert_eq!(decoded.value, [1, 2]);
+  assert_eq!(decoded.trailing, 3u64.to_ne_bytes());
+}
@@ -0,0 +1,48 @@
+use nix::{
+  fcntl::{FdFlag, OFlag, SealFlag},
+  libc,
+};
+use ptrace_syscalls::types::{FcntlCmd, FcntlResult};
+
+#[test]
+fn test_fcntl_value_arg_decoding() {
+  assert_eq!(FcntlCmd::from_value_arg(libc::F_DUPFD_CLOEXEC, 10), Some(FcntlCmd::DupFdCloexec { min_fd: 10 }));
+  assert_eq!(
+    FcntlCmd::from_value_arg(libc::F_SETFL, (libc::O_NONBLOCK | libc::O_APPEND) as usize),
+    Some(FcntlCmd::SetFl {
+      flags: OFlag::O_NONBLOCK | OFlag::O_APPEND
+    })
+  );
+  assert_eq!(FcntlCmd::from_value_arg(libc::F_SETOWN, -42i64 as usize), Some(FcntlCmd::SetOwn { owner: -42 }));
+  assert_eq!(
+    FcntlCmd::from_value_arg(libc::F_ADD_SEALS, (libc::F_SEAL_SEAL | libc::F_SEAL_WRITE) as usize),
+    Some(FcntlCmd::AddSeals {
+      seals: SealFlag::F_SEAL_SEAL | SealFlag::F_SEAL_WRITE
+    })
+  );
+  assert_eq!(FcntlCmd::from_value_arg(0x1234, 5), Some(FcntlCmd::Other { cmd: 0x1234, arg: 5 }));
+}
+
+#[test]
+fn test_fcntl_pointer_arg_commands_need_memory() {
+  for cmd in [libc::F_GETLK, libc::F_SETLKW, libc::F_OFD_SETLK, 15] {
+    assert_eq!(FcntlCmd::from_value_arg(cmd, 0x1000), None);
+  }
+}
+
+#[test]
+fn test_fcntl_result_from_syscall_result() {
+  assert_eq!(
+    FcntlResult::from_syscall_result(libc::F_GETFD, libc::FD_CLOEXEC as isize),
+    FcntlResult::FdFlags(FdFlag::FD_CLOEXEC)
+  );
+  assert_eq!(
+    FcntlResult::from_syscall_result(libc::F_GETFL, (libc::O_RDWR | libc::O_NONBLOCK) as isize),
+    FcntlResult::StatusFlags(OFlag::O_RDWR | OFlag::O_NONBLOCK)
+  );
+  assert_eq!(
+    FcntlResult::from_syscall_result(libc::F_GET_SEALS, libc::F_SEAL_GROW as isize),
+    FcntlResult::Seals(SealFlag::F_SEAL_GROW)
+  );
+  assert_eq!(FcntlResult::from_syscall_result(libc::F_DUPFD, 7), FcntlResult::InSyscallResult);
+}
@@ -0,0 +1,28 @@
+use nix::libc::{FUTEX_OP, FUTEX_OP_ADD, FUTEX_OP_CMP_EQ, FUTEX_OP_CMP_GT, FUTEX_OP_OPARG_SHIFT, FUTEX_OP_SET};
+use ptrace_syscalls::types::{FutexAtomicOp, FutexWakeOp, FutexWakeOpCmp};
+
+#[test]
+fn test_futex_wake_op_decoding() {
+  let op = FutexWakeOp::from(FUTEX_OP(FUTEX_OP_ADD, 1, FUTEX_OP_CMP_GT, 0) as u32);
+  assert_eq!(
+    op,
+    FutexWakeOp {
+      op: FutexAtomicOp::Add,
+      oparg_shift: false,
+      cmp: FutexWakeOpCmp::Gt,
+      oparg: 1,
+      cmparg: 0,
+    }
+  );
+  let op = FutexWakeOp::from(FUTEX_OP(FUTEX_OP_SET | FUTEX_OP_OPARG_SHIFT, 0xfff, FUTEX_OP_CMP_EQ, 0x800) as u32);
+  assert_eq!(
+    op,
+    FutexWakeOp {
+      op: FutexAtomicOp::Set,
+      oparg_shift: true,
+      cmp: FutexWakeOpCmp::Eq,
+      oparg: -1,
+      cmparg: -2048,
+    }
+  );
+}
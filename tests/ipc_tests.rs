@@ -0,0 +1,72 @@
+use std::mem::zeroed;
+
+use nix::libc::{self, c_long};
+use ptrace_syscalls::types::{semid_ds, seminfo, shm_info, shminfo};
+
+const SHM_INFO: i32 = 14;
+
+/// The numbers in a /proc/sys/kernel file
+fn sysctl(name: &str) -> Vec<u64> {
+  std::fs::read_to_string(format!("/proc/sys/kernel/{name}"))
+    .unwrap()
+    .split_whitespace()
+    .map(|value| value.parse().unwrap())
+    .collect()
+}
+
+fn semctl<T>(semid: i32, cmd: i32, buf: &mut T) -> c_long {
+  unsafe { libc::syscall(libc::SYS_semctl, semid, 0, cmd, buf as *mut T) }
+}
+
+fn shmctl<T>(shmid: i32, cmd: i32, buf: &mut T) -> c_long {
+  unsafe { libc::syscall(libc::SYS_shmctl, shmid, cmd, buf as *mut T) }
+}
+
+#[test]
+fn test_semid_ds_layout() {
+  let semid = unsafe { libc::semget(libc::IPC_PRIVATE, 3, libc::IPC_CREAT | 0o640) };
+  assert!(semid >= 0);
+  let mut stat: semid_ds = unsafe { zeroed() };
+  let result = semctl(semid, libc::IPC_STAT, &mut stat);
+  unsafe { libc::semctl(semid, 0, libc::IPC_RMID) };
+  assert_eq!(result, 0);
+  assert_eq!(stat.sem_nsems, 3);
+  assert_eq!(stat.sem_perm.mode & 0o777, 0o640);
+  assert_eq!(stat.sem_perm.uid, unsafe { libc::geteuid() });
+  assert_eq!(stat.sem_otime, 0);
+  assert_ne!(stat.sem_ctime, 0);
+}
+
+#[test]
+fn test_seminfo_layout() {
+  let mut info: seminfo = unsafe { zeroed() };
+  assert!(semctl(0, libc::IPC_INFO, &mut info) >= 0);
+  // SEMMSL SEMMNS SEMOPM SEMMNI
+  let sem = sysctl("sem");
+  assert_eq!(
+    [info.semmsl, info.semmns, info.semopm, info.semmni].map(|value| value as u64),
+    [sem[0], sem[1], sem[2], sem[3]]
+  );
+  assert_eq!(info.semvmx, 32767);
+}
+
+#[test]
+fn test_shm_info_layout() {
+  let shmid = unsafe { libc::shmget(libc::IPC_PRIVATE, 4096, libc::IPC_CREAT | 0o600) };
+  assert!(shmid >= 0);
+  let mut info: shm_info = unsafe { zeroed() };
+  let result = shmctl(0, SHM_INFO, &mut info);
+  let mut limits: shminfo = unsafe { zeroed() };
+  let limits_result = shmctl(0, libc::IPC_INFO, &mut limits);
+  unsafe { libc::shmctl(shmid, libc::IPC_RMID, std::ptr::null_mut()) };
+  // SHM_INFO returns the highest used index.
+  assert!(result >= 0);
+  assert!(info.used_ids >= 1);
+  assert!(info.shm_tot >= 1);
+  assert_eq!(info.swap_attempts, 0);
+  assert_eq!(info.swap_successes, 0);
+  assert!(limits_result >= 0);
+  assert_eq!(limits.shmmax, sysctl("shmmax")[0]);
+  assert_eq!(limits.shmall, sysctl("shmall")[0]);
+  assert_eq!(limits.shmmni, sysctl("shmmni")[0]);
+}
@@ -0,0 +1,21 @@
+use enumflags2::BitFlags;
+use ptrace_syscalls::types::{KeyPerm, KeySerial};
+
+#[test]
+fn test_special_key_serials_are_named() {
+  assert_eq!(KeySerial::from(-3), KeySerial::SessionKeyring);
+  assert_eq!(KeySerial::from(-8), KeySerial::RequestorKeyring);
+  assert_eq!(KeySerial::from(0), KeySerial::Serial(0));
+  assert_eq!(KeySerial::from(123456789), KeySerial::Serial(123456789));
+  // keyctl passes serials as unsigned longs
+  assert_eq!(KeySerial::from(-4i64 as u64), KeySerial::UserKeyring);
+}
+
+#[test]
+fn test_key_permissions() {
+  let perm: BitFlags<KeyPerm> = BitFlags::from_bits_truncate(0x3f010000);
+  assert!(perm.contains(KeyPerm::PossessorSetattr | KeyPerm::PossessorView));
+  assert!(perm.contains(KeyPerm::UserView));
+  assert!(!perm.contains(KeyPerm::UserRead));
+  assert_eq!(perm.len(), 7);
+}
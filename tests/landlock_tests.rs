@@ -0,0 +1,85 @@
+use enumflags2::BitFlags;
+use ptrace_syscalls::types::{
+  landlock_net_port_attr, landlock_path_beneath_attr, LandlockAccessFs, LandlockAccessNet, LandlockBits, LandlockRule,
+  LandlockRulesetAttr, LandlockScope,
+};
+
+#[test]
+fn test_landlock_ruleset_attr_decoding() {
+  let mut bytes = vec![];
+  bytes.extend_from_slice(&(1u64 << 2 | 1 << 3 | 1 << 14).to_ne_bytes());
+  bytes.extend_from_slice(&(1u64 << 1).to_ne_bytes());
+  bytes.extend_from_slice(&(1u64 << 1).to_ne_bytes());
+  assert_eq!(
+    LandlockRulesetAttr::from_bytes(&bytes),
+    LandlockRulesetAttr {
+      handled_access_fs: LandlockBits {
+        known: LandlockAccessFs::ReadFile | LandlockAccessFs::ReadDir | LandlockAccessFs::Truncate,
+        unknown: 0,
+      },
+      handled_access_net: LandlockBits {
+        known: LandlockAccessNet::ConnectTcp.into(),
+        unknown: 0,
+      },
+      scoped: LandlockBits {
+        known: LandlockScope::Signal.into(),
+        unknown: 0,
+      },
+    }
+  );
+}
+
+#[test]
+fn test_landlock_ruleset_attr_v1_size() {
+  // The first ABI only has handled_access_fs, the other fields are zero.
+  let bytes = (1u64 << 0).to_ne_bytes();
+  let attr = LandlockRulesetAttr::from_bytes(&bytes);
+  assert_eq!(attr.handled_access_fs.known, LandlockAccessFs::Execute);
+  assert_eq!(attr.handled_access_net, LandlockBits::from(0));
+  assert_eq!(attr.scoped.known, BitFlags::empty());
+}
+
+#[test]
+fn test_landlock_unknown_bits_are_kept() {
+  let mut bytes = vec![];
+  bytes.extend_from_slice(&(1u64 << 0 | 1 << 40).to_ne_bytes());
+  bytes.extend_from_slice(&(1u64 << 5).to_ne_bytes());
+  bytes.extend_from_slice(&(1u64 << 0 | 1 << 2).to_ne_bytes());
+  let attr = LandlockRulesetAttr::from_bytes(&bytes);
+  assert_eq!(attr.handled_access_fs.known, LandlockAccessFs::Execute);
+  assert_eq!(attr.handled_access_fs.unknown, 1 << 40);
+  assert_eq!(attr.handled_access_net.known, BitFlags::empty());
+  assert_eq!(attr.handled_access_net.unknown, 1 << 5);
+  assert_eq!(attr.scoped.known, LandlockScope::AbstractUnixSocket);
+  assert_eq!(attr.scoped.unknown, 1 << 2);
+}
+
+#[test]
+fn test_landlock_rule_decoding() {
+  assert_eq!(
+    LandlockRule::from(landlock_path_beneath_attr {
+      allowed_access: 1 << 1 | 1 << 63,
+      parent_fd: 3,
+    }),
+    LandlockRule::PathBeneath {
+      allowed_access: LandlockBits {
+        known: LandlockAccessFs::WriteFile.into(),
+        unknown: 1 << 63,
+      },
+      parent_fd: 3,
+    }
+  );
+  assert_eq!(
+    LandlockRule::from(landlock_net_port_attr {
+      allowed_access: 1 << 0,
+      port: 8080,
+    }),
+    LandlockRule::NetPort {
+      allowed_access: LandlockBits {
+        known: LandlockAccessNet::BindTcp.into(),
+        unknown: 0,
+      },
+      port: 8080,
+    }
+  );
+}
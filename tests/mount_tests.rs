@@ -0,0 +1,46 @@
+use enumflags2::BitFlags;
+use nix::libc;
+use ptrace_syscalls::types::{MountAttrFlags, MoveMountFlags, OpenTreeFlags};
+
+#[test]
+fn test_mount_attr_flags() {
+  let attr = (libc::MOUNT_ATTR_RDONLY | libc::MOUNT_ATTR_NOATIME | libc::MOUNT_ATTR_IDMAP) as u32;
+  assert_eq!(
+    BitFlags::<MountAttrFlags>::from_bits_truncate(attr),
+    MountAttrFlags::Rdonly | MountAttrFlags::Noatime | MountAttrFlags::Idmap
+  );
+  // MOUNT_ATTR_RELATIME is the absence of the atime flags.
+  assert!(BitFlags::<MountAttrFlags>::from_bits_truncate(libc::MOUNT_ATTR_RELATIME as u32).is_empty());
+  assert_eq!(
+    BitFlags::<MountAttrFlags>::from_bits_truncate(libc::MOUNT_ATTR__ATIME as u32),
+    MountAttrFlags::Noatime | MountAttrFlags::Strictatime
+  );
+  assert_eq!(
+    BitFlags::<MountAttrFlags>::from_bits_truncate(libc::MOUNT_ATTR_NOSYMFOLLOW as u32),
+    MountAttrFlags::Nosymfollow
+  );
+}
+
+#[test]
+fn test_move_mount_flags() {
+  // MOVE_MOUNT_F_EMPTY_PATH | MOVE_MOUNT_T_SYMLINKS | MOVE_MOUNT_BENEATH
+  assert_eq!(
+    BitFlags::<MoveMountFlags>::from_bits_truncate(0x00000004 | 0x00000010 | 0x00000200),
+    MoveMountFlags::FEmptyPath | MoveMountFlags::TSymlinks | MoveMountFlags::Beneath
+  );
+  // 0x8 is not a flag.
+  assert_eq!(BitFlags::<MoveMountFlags>::from_bits_truncate(0x00000008 | 0x00000100), MoveMountFlags::SetGroup);
+}
+
+#[test]
+fn test_open_tree_flags() {
+  let flags = libc::OPEN_TREE_CLONE | libc::OPEN_TREE_CLOEXEC | libc::AT_RECURSIVE as u32 | libc::AT_EMPTY_PATH as u32;
+  assert_eq!(
+    BitFlags::<OpenTreeFlags>::from_bits_truncate(flags),
+    OpenTreeFlags::Clone | OpenTreeFlags::Cloexec | OpenTreeFlags::Recursive | OpenTreeFlags::EmptyPath
+  );
+  assert_eq!(
+    BitFlags::<OpenTreeFlags>::from_bits_truncate(libc::AT_SYMLINK_NOFOLLOW as u32 | libc::AT_NO_AUTOMOUNT as u32),
+    OpenTreeFlags::SymlinkNofollow | OpenTreeFlags::NoAutomount
+  );
+}
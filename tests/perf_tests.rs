@@ -0,0 +1,41 @@
+use ptrace_syscalls::types::{Extensible, PerfEvent, PerfEventAttr, PerfEventAttrFlags, PerfHwId, PerfSampleInterval};
+
+#[test]
+fn test_perf_event_attr_flags_decoding() {
+  // disabled, exclude_kernel, exclude_hv, precise_ip = 2 and sigtrap
+  let flags = PerfEventAttrFlags::from(1 | 1 << 5 | 1 << 6 | 2 << 15 | 1 << 37);
+  assert_eq!(
+    flags,
+    PerfEventAttrFlags {
+      disabled: true,
+      exclude_kernel: true,
+      exclude_hv: true,
+      precise_ip: 2,
+      sigtrap: true,
+      ..Default::default()
+    }
+  );
+}
+
+#[test]
+fn test_perf_event_attr_ver0_decoding() {
+  // A PERF_ATTR_SIZE_VER0 attr counting instructions with a sample period, the later fields are zero.
+  let mut bytes = vec![0u8; 64];
+  bytes[8..16].copy_from_slice(&1u64.to_ne_bytes());
+  bytes[16..24].copy_from_slice(&4000u64.to_ne_bytes());
+  bytes[40..48].copy_from_slice(&1u64.to_ne_bytes());
+  let attr = Extensible::decode(64, &bytes, 136, PerfEventAttr::from_bytes);
+  assert_eq!(attr.size, 64);
+  assert_eq!(attr.value.size, 0);
+  assert!(attr.trailing.is_empty());
+  assert_eq!(
+    attr.value.event,
+    PerfEvent::Hardware {
+      id: PerfHwId::Instructions,
+      pmu_type: 0
+    }
+  );
+  assert_eq!(attr.value.sample, PerfSampleInterval::Period(4000));
+  assert!(attr.value.flags.disabled);
+  assert_eq!(attr.value.config3, 0);
+}
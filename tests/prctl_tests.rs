@@ -0,0 +1,24 @@
+use nix::libc::{self, c_ulong};
+use ptrace_syscalls::types::{CapAmbientOp, PrctlMmField, PrctlPtracer};
+
+#[test]
+fn test_prctl_ptracer_decoding() {
+  assert_eq!(PrctlPtracer::from(0), PrctlPtracer::None);
+  assert_eq!(PrctlPtracer::from(libc::PR_SET_PTRACER_ANY), PrctlPtracer::Any);
+  assert_eq!(PrctlPtracer::from(1234 as c_ulong), PrctlPtracer::Pid(1234));
+}
+
+#[test]
+fn test_prctl_mm_field_decoding() {
+  assert_eq!(PrctlMmField::from_raw(libc::PR_SET_MM_START_CODE), Some(PrctlMmField::StartCode));
+  assert_eq!(PrctlMmField::from_raw(libc::PR_SET_MM_ENV_END), Some(PrctlMmField::EnvEnd));
+  assert_eq!(PrctlMmField::from_raw(libc::PR_SET_MM_AUXV), None);
+  assert_eq!(PrctlMmField::from_raw(libc::PR_SET_MM_MAP), None);
+}
+
+#[test]
+fn test_cap_ambient_op_decoding() {
+  assert_eq!(CapAmbientOp::from(libc::PR_CAP_AMBIENT_RAISE), CapAmbientOp::Raise);
+  assert_eq!(CapAmbientOp::from(libc::PR_CAP_AMBIENT_CLEAR_ALL), CapAmbientOp::ClearAll);
+  assert_eq!(CapAmbientOp::from(42), CapAmbientOp::Unknown(42));
+}
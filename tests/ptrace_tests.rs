@@ -0,0 +1,45 @@
+use ptrace_syscalls::types::{PtraceSyscallInfo, PtraceSyscallInfoOp};
+
+fn syscall_info(op: u8, words: &[u64]) -> Vec<u8> {
+  let mut bytes = vec![op, 0, 0, 0];
+  bytes.extend_from_slice(&0xc000003eu32.to_ne_bytes());
+  bytes.extend_from_slice(&0x1000u64.to_ne_bytes());
+  bytes.extend_from_slice(&0x7ff0u64.to_ne_bytes());
+  for word in words {
+    bytes.extend_from_slice(&word.to_ne_bytes());
+  }
+  bytes
+}
+
+#[test]
+fn test_syscall_info_decoding() {
+  let entry = PtraceSyscallInfo::from_bytes(&syscall_info(1, &[59, 1, 2, 3, 4, 5, 6]));
+  assert_eq!(entry.arch, 0xc000003e);
+  assert_eq!(entry.instruction_pointer, 0x1000);
+  assert_eq!(entry.stack_pointer, 0x7ff0);
+  assert_eq!(
+    entry.op,
+    PtraceSyscallInfoOp::Entry {
+      nr: 59,
+      args: [1, 2, 3, 4, 5, 6]
+    }
+  );
+  let exit = PtraceSyscallInfo::from_bytes(&syscall_info(2, &[-2i64 as u64, 1]));
+  assert_eq!(
+    exit.op,
+    PtraceSyscallInfoOp::Exit {
+      rval: -2,
+      is_error: true
+    }
+  );
+  // A buffer smaller than the struct leaves the missing fields zeroed
+  let seccomp = PtraceSyscallInfo::from_bytes(&syscall_info(3, &[1, 7]));
+  assert_eq!(
+    seccomp.op,
+    PtraceSyscallInfoOp::Seccomp {
+      nr: 1,
+      args: [7, 0, 0, 0, 0, 0],
+      ret_data: 0
+    }
+  );
+}
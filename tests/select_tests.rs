@@ -0,0 +1,13 @@
+use ptrace_syscalls::types::FdSet;
+
+#[test]
+fn test_fd_set_decoding_is_bounded_by_nfds() {
+  let mut bitmap = vec![0u8; 16];
+  for fd in [0, 3, 9, 63, 64, 70] {
+    bitmap[fd / 8] |= 1 << (fd % 8);
+  }
+  assert_eq!(FdSet::from_bitmap(&bitmap, 128), FdSet(vec![0, 3, 9, 63, 64, 70]));
+  assert_eq!(FdSet::from_bitmap(&bitmap, 64), FdSet(vec![0, 3, 9, 63]));
+  assert_eq!(FdSet::from_bitmap(&bitmap, 4), FdSet(vec![0, 3]));
+  assert_eq!(FdSet::from_bitmap(&bitmap, 1000), FdSet(vec![0, 3, 9, 63, 64, 70]));
+}
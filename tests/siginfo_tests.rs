@@ -0,0 +1,122 @@
+use nix::libc::{
+  getuid, siginfo_t, waitid, PTRACE_EVENT_EXEC, P_PID, SIGCHLD, SIGKILL, SIGSEGV, SIGSYS, SIGTRAP, SIGUSR1, WEXITED,
+};
+use ptrace_syscalls::types::{ChildState, FaultCode, SegvCode, SiCode, SignalInfo, SysCode};
+
+fn siginfo(signo: i32, code: i32, union: &[u8]) -> Vec<u8> {
+  let mut bytes = vec![0u8; 128];
+  bytes[0..4].copy_from_slice(&signo.to_ne_bytes());
+  bytes[8..12].copy_from_slice(&code.to_ne_bytes());
+  bytes[16..16 + union.len()].copy_from_slice(union);
+  bytes
+}
+
+fn ints(values: &[i32]) -> Vec<u8> {
+  values.iter().flat_map(|x| x.to_ne_bytes()).collect()
+}
+
+#[test]
+fn test_kill_and_queue() {
+  assert_eq!(
+    SignalInfo::from_bytes(&siginfo(SIGUSR1, 0, &ints(&[42, 1000]))),
+    SignalInfo::Kill {
+      signo: SIGUSR1,
+      code: SiCode::User,
+      pid: 42,
+      uid: 1000
+    }
+  );
+  assert_eq!(
+    SignalInfo::from_bytes(&siginfo(SIGUSR1, -1, &ints(&[42, 1000, 7, 0]))),
+    SignalInfo::Queue {
+      signo: SIGUSR1,
+      code: SiCode::Queue,
+      pid: 42,
+      uid: 1000,
+      value: 7
+    }
+  );
+}
+
+#[test]
+fn test_ptrace_stops() {
+  // The siginfo of a syscall-stop with PTRACE_O_TRACESYSGOOD
+  assert_eq!(
+    SignalInfo::from_bytes(&siginfo(SIGTRAP, SIGTRAP | 0x80, &ints(&[42, 1000]))),
+    SignalInfo::Kill {
+      signo: SIGTRAP,
+      code: SiCode::Unknown(SIGTRAP | 0x80),
+      pid: 42,
+      uid: 1000
+    }
+  );
+  let code = SIGTRAP | PTRACE_EVENT_EXEC << 8;
+  assert_eq!(
+    SignalInfo::from_bytes(&siginfo(SIGTRAP, code, &ints(&[42, 1000]))),
+    SignalInfo::Kill {
+      signo: SIGTRAP,
+      code: SiCode::Unknown(code),
+      pid: 42,
+      uid: 1000
+    }
+  );
+}
+
+#[test]
+fn test_sigchld() {
+  let mut union = ints(&[42, 1000, SIGKILL, 0]);
+  union.extend_from_slice(&5u64.to_ne_bytes());
+  union.extend_from_slice(&6u64.to_ne_bytes());
+  let info = SignalInfo::from_bytes(&siginfo(SIGCHLD, 2, &union));
+  assert_eq!(
+    info,
+    SignalInfo::Child {
+      pid: 42,
+      uid: 1000,
+      state: ChildState::Killed(SIGKILL),
+      utime: 5,
+      stime: 6
+    }
+  );
+  assert_eq!(info.signo(), SIGCHLD);
+}
+
+#[test]
+fn test_faults_and_sigsys() {
+  let info = SignalInfo::from_bytes(&siginfo(SIGSEGV, 1, &0xdeadb000u64.to_ne_bytes()));
+  assert_eq!(
+    info,
+    SignalInfo::Fault {
+      code: FaultCode::Segv(SegvCode::MapErr),
+      addr: 0xdeadb000usize as _,
+      addr_lsb: None,
+      pkey: None
+    }
+  );
+  let mut union = 0x1000u64.to_ne_bytes().to_vec();
+  union.extend_from_slice(&ints(&[59, 0xc000003eu32 as i32]));
+  assert_eq!(
+    SignalInfo::from_bytes(&siginfo(SIGSYS, 1, &union)),
+    SignalInfo::Sys {
+      code: SysCode::Seccomp,
+      call_addr: 0x1000usize as _,
+      syscall: 59,
+      arch: 0xc000003e
+    }
+  );
+}
+
+#[test]
+fn test_from_siginfo_t() {
+  // The child is reaped by waitid.
+  let child = std::process::Command::new("true").spawn().unwrap().id();
+  let mut info: siginfo_t = unsafe { std::mem::zeroed() };
+  let result = unsafe { waitid(P_PID, child, &mut info, WEXITED) };
+  assert_eq!(result, 0);
+  let SignalInfo::Child { pid, uid, state, .. } = SignalInfo::from(&info) else {
+    panic!("not a SIGCHLD siginfo");
+  };
+  assert_eq!(pid, child as i32);
+  assert_eq!(uid, unsafe { getuid() });
+  assert_eq!(state, ChildState::Exited(0));
+}
@@ -0,0 +1,14 @@
+use nix::sys::signal::Signal;
+use ptrace_syscalls::types::SigSet;
+
+#[test]
+fn test_sigset_iteration() {
+  // SIGINT(2), SIGKILL(9), SIGUSR2(12) and the first realtime signal (34)
+  let set = SigSet(vec![0b0000_0010, 0b0000_1001, 0, 0, 0b0000_0010, 0, 0, 0]);
+  assert_eq!(set.iter().collect::<Vec<_>>(), vec![Signal::SIGINT, Signal::SIGKILL, Signal::SIGUSR2]);
+  assert_eq!(set.iter_raw().collect::<Vec<_>>(), vec![2, 9, 12, 34]);
+  assert!(set.contains(Signal::SIGKILL));
+  assert!(!set.contains(Signal::SIGTERM));
+  assert!(!set.contains_raw(0));
+  assert!(!set.contains_raw(65));
+}
@@ -0,0 +1,39 @@
+use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+use nix::unistd::Pid;
+use ptrace_syscalls::{types::SyslogAction, AddressType};
+
+#[test]
+fn test_syslog_actions() {
+  assert_eq!(SyslogAction::from(3), SyslogAction::ReadAll);
+  assert_eq!(SyslogAction::from(10), SyslogAction::SizeBuffer);
+  assert_eq!(SyslogAction::from(11), SyslogAction::Unknown(11));
+  assert!(SyslogAction::Read.reads_log());
+  assert!(SyslogAction::ReadAll.reads_log());
+  assert!(SyslogAction::ReadClear.reads_log());
+  assert!(!SyslogAction::Clear.reads_log());
+  assert!(!SyslogAction::SizeUnread.reads_log());
+  assert!(!SyslogAction::Unknown(11).reads_log());
+}
+
+#[test]
+fn test_syslog_read_log_is_sized() {
+  // The log is not NUL terminated and its length is the syscall result.
+  let log = b"<6>[    0.000000] Linux\0version\nleftover".to_vec();
+  let buf = log.as_ptr() as AddressType;
+  assert_eq!(
+    SyslogAction::ReadAll.read_log(Pid::this(), buf, 31),
+    Ok(Some(OsString::from_vec(log[..31].to_vec())))
+  );
+  assert_eq!(SyslogAction::Clear.read_log(Pid::this(), buf, 31), Ok(None));
+}
+
+#[test]
+fn test_syslog_read_log_is_capped() {
+  let log = vec![b'x'; 3 << 20];
+  let read = SyslogAction::Read
+    .read_log(Pid::this(), log.as_ptr() as AddressType, log.len())
+    .unwrap()
+    .unwrap();
+  assert_eq!(read.len(), 1 << 20);
+}
@@ -0,0 +1,31 @@
+use nix::libc::{SIGKILL, SIGSTOP, SIGTRAP};
+use ptrace_syscalls::types::WaitStatus;
+
+#[test]
+fn test_wait_status_decoding() {
+  assert_eq!(WaitStatus::from(3 << 8), WaitStatus::Exited(3));
+  assert_eq!(
+    WaitStatus::from(SIGKILL),
+    WaitStatus::Signaled {
+      signal: SIGKILL,
+      core_dumped: false
+    }
+  );
+  assert_eq!(
+    WaitStatus::from(0x80 | 11),
+    WaitStatus::Signaled {
+      signal: 11,
+      core_dumped: true
+    }
+  );
+  assert_eq!(WaitStatus::from(SIGSTOP << 8 | 0x7f), WaitStatus::Stopped(SIGSTOP));
+  assert_eq!(WaitStatus::from((SIGTRAP | 0x80) << 8 | 0x7f), WaitStatus::PtraceSyscall);
+  assert_eq!(
+    WaitStatus::from(4 << 16 | SIGTRAP << 8 | 0x7f),
+    WaitStatus::PtraceEvent {
+      signal: SIGTRAP,
+      event: 4
+    }
+  );
+  assert_eq!(WaitStatus::from(0xffff), WaitStatus::Continued);
+}
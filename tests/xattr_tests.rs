@@ -0,0 +1,56 @@
+use enumflags2::BitFlags;
+use ptrace_syscalls::types::{CapabilitySet, FileCapabilities, PosixAclEntry, PosixAclPerm, PosixAclTag, XattrValue};
+
+#[test]
+fn test_posix_acl_decoding() {
+  let mut value = 2u32.to_ne_bytes().to_vec();
+  for (tag, perm, id) in [(0x01u16, 6u16, u32::MAX), (0x02, 4, 1000), (0x20, 0, u32::MAX)] {
+    value.extend(tag.to_ne_bytes());
+    value.extend(perm.to_ne_bytes());
+    value.extend(id.to_ne_bytes());
+  }
+  assert_eq!(
+    XattrValue::decode(b"system.posix_acl_access", value),
+    XattrValue::PosixAcl(vec![
+      PosixAclEntry {
+        tag: PosixAclTag::UserObj,
+        perm: PosixAclPerm::Read | PosixAclPerm::Write,
+        id: None,
+      },
+      PosixAclEntry {
+        tag: PosixAclTag::User,
+        perm: PosixAclPerm::Read.into(),
+        id: Some(1000),
+      },
+      PosixAclEntry {
+        tag: PosixAclTag::Other,
+        perm: BitFlags::empty(),
+        id: None,
+      },
+    ])
+  );
+  // Truncated entries are left undecoded.
+  assert_eq!(
+    XattrValue::decode(b"system.posix_acl_default", vec![2, 0, 0, 0, 1]),
+    XattrValue::Raw(vec![2, 0, 0, 0, 1])
+  );
+}
+
+#[test]
+fn test_file_capabilities_decoding() {
+  let value: Vec<u8> = [0x02000001u32, 1 << 13, 0, 1, 0]
+    .iter()
+    .flat_map(|x| x.to_ne_bytes())
+    .collect();
+  assert_eq!(
+    XattrValue::decode(b"security.capability", value),
+    XattrValue::FileCapabilities(FileCapabilities {
+      revision: 2,
+      effective: true,
+      permitted: CapabilitySet(1 << 13 | 1 << 32),
+      inheritable: CapabilitySet(0),
+      rootid: None,
+    })
+  );
+  assert_eq!(XattrValue::decode(b"user.foo", b"bar".to_vec()), XattrValue::Raw(b"bar".to_vec()));
+}